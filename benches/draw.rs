@@ -0,0 +1,301 @@
+//! Benchmarks of drawing many shapes.
+//!
+//! Run with `cargo bench`, and with `cargo bench --features simd`
+//! to compare the SIMD vertex transforms with the scalar ones.
+//! `bench_quads_transform4` and `bench_quads_transform4_scalar`
+//! compare them in one build.
+
+#![feature(test)]
+
+extern crate test;
+extern crate graphics;
+
+use test::{ Bencher, black_box };
+
+use graphics::triangulation::{ self, TessellationBuffers };
+use graphics::vecmath::{ translate, Matrix2d, Scalar, Vec2d };
+use graphics::{
+    AttributeEmitter, CachedShape, Context, DrawState, Ellipse, Emitter, Graphics, Image,
+    ImageSize, Polygon, Rectangle, RelativeTransform
+};
+
+struct Texture;
+
+impl ImageSize for Texture {
+    fn get_size(&self) -> (u32, u32) { (256, 256) }
+}
+
+/// A back-end that reads every vertex, reusing its buffers.
+struct Sink {
+    sum: f32,
+    vertices: Vec<f32>,
+    uvs: Vec<f32>,
+}
+
+impl Sink {
+    fn new() -> Sink {
+        Sink { sum: 0.0, vertices: Vec::new(), uvs: Vec::new() }
+    }
+}
+
+impl Graphics for Sink {
+    type Texture = Texture;
+
+    fn clear(&mut self, _color: [f32; 4]) {}
+
+    fn tri_list<F>(&mut self, _draw_state: &DrawState, _color: &[f32; 4], mut f: F)
+        where F: FnMut(&mut Emitter)
+    {
+        self.vertices.clear();
+        f(&mut Emitter::new(&mut self.vertices));
+        for &x in self.vertices.iter() { self.sum += x; }
+    }
+
+    fn tri_list_uv<F>(
+        &mut self,
+        _draw_state: &DrawState,
+        _color: &[f32; 4],
+        _texture: &Texture,
+        mut f: F
+    ) where F: FnMut(&mut AttributeEmitter)
+    {
+        self.vertices.clear();
+        self.uvs.clear();
+        f(&mut AttributeEmitter::new(&mut self.vertices, &mut self.uvs));
+        for (&x, &u) in self.vertices.iter().zip(self.uvs.iter()) { self.sum += x + u; }
+    }
+}
+
+/// Returns concave arrow heads spread over the window.
+fn arrows(n: usize) -> Vec<Vec<Vec2d>> {
+    (0..n).map(|i| {
+        let (x, y) = ((i % 40) as Scalar * 20.0, (i / 40) as Scalar * 20.0);
+        vec![[x, y], [x + 16.0, y], [x + 8.0, y + 4.0], [x + 16.0, y + 16.0], [x, y + 16.0]]
+    }).collect()
+}
+
+#[bench]
+fn bench_concave_polygons(b: &mut Bencher) {
+    let c = Context::abs(800.0, 600.0);
+    let polygons = arrows(1000);
+    let mut g = Sink::new();
+    b.iter(|| {
+        for polygon in polygons.iter() {
+            Polygon::new([1.0; 4]).draw(polygon, &c, &mut g);
+        }
+        black_box(g.sum);
+    });
+}
+
+#[bench]
+fn bench_concave_polygons_buffered(b: &mut Bencher) {
+    let c = Context::abs(800.0, 600.0);
+    let polygons = arrows(1000);
+    let mut g = Sink::new();
+    let mut buffers = TessellationBuffers::new();
+    b.iter(|| {
+        for polygon in polygons.iter() {
+            Polygon::new([1.0; 4]).draw_with_buffers(polygon, &mut buffers, &c, &mut g);
+        }
+        black_box(g.sum);
+    });
+}
+
+#[bench]
+fn bench_rect_quads(b: &mut Bencher) {
+    let c = Context::abs(800.0, 600.0).trans(10.0, 20.0).rot_deg(30.0);
+    b.iter(|| {
+        let mut sum = 0.0;
+        for i in 0..100_000 {
+            let x = (i % 400) as Scalar * 2.0;
+            let vertices = triangulation::rect_tri_list_xy(c.transform, [x, x, 1.5, 1.5]);
+            sum += vertices[0] + vertices[11];
+        }
+        black_box(sum);
+    });
+}
+
+/// Transforms the corners of quads four points at a time.
+fn transform_quads<F>(b: &mut Bencher, transform4: F)
+    where F: Fn(Matrix2d, [Scalar; 4], [Scalar; 4]) -> [f32; 8]
+{
+    let c = Context::abs(800.0, 600.0).trans(10.0, 20.0).rot_deg(30.0);
+    b.iter(|| {
+        let mut sum = 0.0;
+        for i in 0..100_000 {
+            let x = (i % 400) as Scalar * 2.0;
+            let x2 = x + 1.5;
+            let p = transform4(c.transform, [x, x2, x, x2], [x, x, x2, x2]);
+            sum += p[0] + p[7];
+        }
+        black_box(sum);
+    });
+}
+
+#[bench]
+fn bench_quads_transform4(b: &mut Bencher) {
+    transform_quads(b, triangulation::transform4);
+}
+
+#[bench]
+fn bench_quads_transform4_scalar(b: &mut Bencher) {
+    transform_quads(b, triangulation::transform4_scalar);
+}
+
+#[bench]
+fn bench_circles(b: &mut Bencher) {
+    let c = Context::abs(800.0, 600.0);
+    let mut g = Sink::new();
+    let circle = Ellipse::new([1.0; 4]);
+    b.iter(|| {
+        for i in 0..5000 {
+            let (x, y) = ((i % 100) as Scalar * 8.0, (i / 100) as Scalar * 8.0);
+            circle.draw([x, y, 6.0, 6.0], &c, &mut g);
+        }
+        black_box(g.sum);
+    });
+}
+
+/// Returns sprites in a grid, as destination rectangles and transforms.
+fn sprites(n: usize) -> Vec<([Scalar; 4], Matrix2d)> {
+    (0..n).map(|i| {
+        let (x, y) = ((i % 100) as Scalar * 8.0, (i / 100) as Scalar * 6.0);
+        ([0.0, 0.0, 8.0, 8.0], translate([x, y]))
+    }).collect()
+}
+
+#[bench]
+fn bench_sprites(b: &mut Bencher) {
+    let c = Context::abs(800.0, 600.0);
+    let instances = sprites(10_000);
+    let image = Image::new().rot_deg_center(15.0);
+    let mut g = Sink::new();
+    b.iter(|| {
+        for &(rect, transform) in instances.iter() {
+            let mut image = image;
+            image.rectangle = Some(rect);
+            image.draw(&Texture, &c.append_transform(transform), &mut g);
+        }
+        black_box(g.sum);
+    });
+}
+
+#[bench]
+fn bench_sprites_draw_many(b: &mut Bencher) {
+    let c = Context::abs(800.0, 600.0);
+    let instances = sprites(10_000);
+    let image = Image::new().rot_deg_center(15.0);
+    let mut g = Sink::new();
+    b.iter(|| {
+        image.draw_many(&instances, None, &Texture, &c, &mut g);
+        black_box(g.sum);
+    });
+}
+
+#[bench]
+fn bench_ellipses_per_frame(b: &mut Bencher) {
+    let c = Context::abs(800.0, 600.0);
+    let ellipse = Ellipse::new([1.0; 4]);
+    let mut g = Sink::new();
+    b.iter(|| {
+        for i in 0..1000 {
+            let (x, y) = ((i % 40) as Scalar * 20.0, (i / 40) as Scalar * 20.0);
+            let c = c.trans(x, y).rot_deg(i as Scalar);
+            ellipse.draw([-8.0, -4.0, 16.0, 8.0], &c, &mut g);
+        }
+        black_box(g.sum);
+    });
+}
+
+#[bench]
+fn bench_ellipses_cached(b: &mut Bencher) {
+    let c = Context::abs(800.0, 600.0);
+    let ellipse = CachedShape::new(|m, f|
+        triangulation::with_ellipse_tri_list(128, m, [-8.0, -4.0, 16.0, 8.0], |v| f(v)));
+    let mut g = Sink::new();
+    b.iter(|| {
+        for i in 0..1000 {
+            let (x, y) = ((i % 40) as Scalar * 20.0, (i / 40) as Scalar * 20.0);
+            let c = c.trans(x, y).rot_deg(i as Scalar);
+            ellipse.draw([1.0; 4], &c, &mut g);
+        }
+        black_box(g.sum);
+    });
+}
+
+/// Returns rectangles in a grid, with a few colors.
+fn rects(n: usize) -> Vec<([Scalar; 4], [f32; 4])> {
+    let colors = [[1.0, 0.0, 0.0, 1.0], [0.0, 1.0, 0.0, 1.0], [0.0, 0.0, 1.0, 1.0]];
+    (0..n).map(|i| {
+        let (x, y) = ((i % 100) as Scalar * 8.0, (i / 100) as Scalar * 6.0);
+        ([x, y, 6.0, 4.0], colors[i / 1000 % 3])
+    }).collect()
+}
+
+#[bench]
+fn bench_rectangles(b: &mut Bencher) {
+    let c = Context::abs(800.0, 600.0);
+    let rects = rects(10_000);
+    let mut g = Sink::new();
+    b.iter(|| {
+        for &(rect, color) in rects.iter() {
+            Rectangle::new(color).draw(rect, &c, &mut g);
+        }
+        black_box(g.sum);
+    });
+}
+
+#[bench]
+fn bench_rectangles_draw_iter(b: &mut Bencher) {
+    let c = Context::abs(800.0, 600.0);
+    let rects = rects(10_000);
+    let mut g = Sink::new();
+    b.iter(|| {
+        Rectangle::draw_iter(rects.iter().map(|r| *r), &c, &mut g);
+        black_box(g.sum);
+    });
+}
+
+/// A back-end collecting vertices through the inner callback
+/// of `tri_list` before `Emitter`, a trait object.
+fn tri_list_trait_object<F>(vertices: &mut Vec<f32>, mut f: F)
+    where F: FnMut(&mut FnMut(&[f32]))
+{
+    vertices.clear();
+    f(&mut |chunk| vertices.extend(chunk.iter().map(|x| *x)));
+}
+
+/// A back-end collecting vertices through `Emitter`.
+fn tri_list_emitter<F>(vertices: &mut Vec<f32>, mut f: F)
+    where F: FnMut(&mut Emitter)
+{
+    vertices.clear();
+    f(&mut Emitter::new(vertices));
+}
+
+#[bench]
+fn bench_frame_trait_object(b: &mut Bencher) {
+    let c = Context::abs(800.0, 600.0);
+    // 10k triangles, two per rectangle.
+    let rects = rects(5000);
+    let mut vertices = Vec::new();
+    b.iter(|| {
+        tri_list_trait_object(&mut vertices, |f| for &(rect, _) in rects.iter() {
+            f(&triangulation::rect_tri_list_xy(c.transform, rect));
+        });
+        black_box(vertices.len());
+    });
+}
+
+#[bench]
+fn bench_frame_emitter(b: &mut Bencher) {
+    let c = Context::abs(800.0, 600.0);
+    let rects = rects(5000);
+    let mut vertices = Vec::new();
+    b.iter(|| {
+        tri_list_emitter(&mut vertices, |e| for &(rect, _) in rects.iter() {
+            e.emit(&triangulation::rect_tri_list_xy(c.transform, rect));
+        });
+        black_box(vertices.len());
+    });
+}
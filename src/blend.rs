@@ -0,0 +1,100 @@
+//! Common blend settings.
+//!
+//! Use the `Context` methods `additive`, `multiply`, `screen`,
+//! `alpha_blend` and `replace` to draw with them.
+//! The source is the color being drawn,
+//! and the destination is the color of the render target.
+
+pub use draw_state::BlendPreset;
+pub use draw_state::block::{ Blend, BlendChannel, BlendValue, Equation, Factor };
+
+use draw_state::DrawState;
+
+/// Mixes the source with the destination by the alpha of the source.
+///
+/// The usual transparency, which is the default of `Context`.
+pub fn alpha() -> Blend {
+    DrawState::new().blend(BlendPreset::Alpha).blend.unwrap()
+}
+
+/// Adds the source to the destination.
+///
+/// Brightens, saturating at white, like light or glow effects.
+/// Black has no effect.
+pub fn additive() -> Blend {
+    DrawState::new().blend(BlendPreset::Add).blend.unwrap()
+}
+
+/// Multiplies the source with the destination.
+///
+/// Darkens, like shadows or tinted glass.
+/// White has no effect, and black gives black.
+pub fn multiply() -> Blend {
+    Blend {
+        color: BlendChannel {
+            equation: Equation::Add,
+            source: Factor::ZeroPlus(BlendValue::DestColor),
+            destination: Factor::Zero,
+        },
+        alpha: BlendChannel {
+            equation: Equation::Add,
+            source: Factor::ZeroPlus(BlendValue::DestAlpha),
+            destination: Factor::Zero,
+        },
+        value: [0.0; 4],
+    }
+}
+
+/// Multiplies the inverses of the source and the destination,
+/// and inverts the result.
+///
+/// Brightens softly, without saturating like `additive`.
+/// Black has no effect, and white gives white.
+pub fn screen() -> Blend {
+    Blend {
+        color: BlendChannel {
+            equation: Equation::Add,
+            source: Factor::One,
+            destination: Factor::OneMinus(BlendValue::SourceColor),
+        },
+        alpha: BlendChannel {
+            equation: Equation::Add,
+            source: Factor::One,
+            destination: Factor::OneMinus(BlendValue::SourceAlpha),
+        },
+        value: [0.0; 4],
+    }
+}
+
+/// Serializes a blend preset by name, for the `serialize_with`
+/// attribute of serde.
+///
+/// Fails for presets other than `Alpha` and `Add`.
+#[cfg(feature = "serde")]
+pub fn serialize_preset<S>(preset: &BlendPreset, s: &mut S) -> Result<(), S::Error>
+    where S: ::serde::Serializer
+{
+    use serde::ser::Error;
+
+    let blend = DrawState::new().blend(*preset).blend;
+    let name = if blend == Some(alpha()) { "Alpha" }
+        else if blend == Some(additive()) { "Add" }
+        else { return Err(S::Error::custom("unsupported blend preset")) };
+    s.serialize_str(name)
+}
+
+/// Deserializes a blend preset from its name, for the `deserialize_with`
+/// attribute of serde.
+#[cfg(feature = "serde")]
+pub fn deserialize_preset<D>(d: &mut D) -> Result<BlendPreset, D::Error>
+    where D: ::serde::Deserializer
+{
+    use serde::de::Error;
+
+    let name: String = try!(::serde::Deserialize::deserialize(d));
+    match &name[..] {
+        "Alpha" => Ok(BlendPreset::Alpha),
+        "Add" => Ok(BlendPreset::Add),
+        _ => Err(D::Error::invalid_value("expected the blend preset Alpha or Add"))
+    }
+}
@@ -0,0 +1,99 @@
+//! Reuse the triangulation of static shapes
+
+use std::vec::Vec;
+use internal;
+use triangulation::{ self, tx, ty };
+use vecmath::{ identity, Matrix2d, Scalar };
+use Graphics;
+use Context;
+
+/// A triangle list in local coordinates, computed once and drawn many times
+///
+/// The transform is applied when drawing,
+/// so the same shape can be moved and rotated.
+#[derive(Clone)]
+pub struct CachedShape {
+    /// The vertices of the triangles, two components per vertex.
+    pub vertices: Vec<Scalar>,
+}
+
+impl CachedShape {
+    /// Records the output of a triangulation function.
+    ///
+    /// The function is called with the identity matrix,
+    /// and a function to pass vertices to, for example:
+    ///
+    /// ```ignore
+    /// let circle = CachedShape::new(|m, f|
+    ///     triangulation::with_ellipse_tri_list(128, m, rect, |v| f(v)));
+    /// ```
+    pub fn new<F>(f: F) -> CachedShape
+        where F: FnOnce(Matrix2d, &mut FnMut(&[f32]))
+    {
+        let mut vertices = Vec::new();
+        f(identity(), &mut |chunk| {
+            vertices.extend(chunk.iter().map(|x| *x as Scalar));
+        });
+        CachedShape { vertices: vertices }
+    }
+
+    /// Draws the shape with a color.
+    pub fn draw<B>(
+        &self,
+        color: internal::Color,
+        c: &Context,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        let color = c.apply_alpha(color);
+        if c.skips(color) { return; }
+        let m = c.transform;
+        back_end.tri_list(
+            &c.draw_state,
+            &color,
+            |f| {
+                let mut buffer: [f32; 720] = [0.0; 720];
+                for chunk in self.vertices.chunks(720) {
+                    let n = chunk.len() / 6 * 6;
+                    for i in 0..n / 2 {
+                        let (x, y) = (chunk[2 * i], chunk[2 * i + 1]);
+                        buffer[2 * i] = tx(m, x, y);
+                        buffer[2 * i + 1] = ty(m, x, y);
+                    }
+                    triangulation::ensure_ccw(&mut buffer[..n]);
+                    f.emit(&buffer[..n]);
+                }
+            }
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::num::Float;
+    use super::CachedShape;
+    use test_backend::RecordingGraphics;
+    use triangulation;
+    use { Context, Ellipse, Transformed };
+
+    #[test]
+    fn test_cached_shape() {
+        let rect = [0.0, 0.0, 40.0, 20.0];
+        let circle = CachedShape::new(|m, f|
+            triangulation::with_ellipse_tri_list(128, m, rect, |v| f(v)));
+
+        let c = Context::new();
+        for &c in [c, c.trans(10.0, 5.0).rot_deg(30.0).zoom(2.0)].iter() {
+            let (mut direct, mut cached) = (RecordingGraphics::new(), RecordingGraphics::new());
+            Ellipse::new([1.0; 4]).draw(rect, &c, &mut direct);
+            circle.draw([1.0; 4], &c, &mut cached);
+            assert_eq!(direct.tri_lists[0].color, cached.tri_lists[0].color);
+            let (a, b) = (&direct.tri_lists[0].vertices, &cached.tri_lists[0].vertices);
+            assert_eq!(a.len(), b.len());
+            for (x, y) in a.iter().zip(b.iter()) {
+                assert!((x - y).abs() < 0.001);
+            }
+        }
+    }
+}
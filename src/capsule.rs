@@ -0,0 +1,182 @@
+//! Draw capsule
+
+use internal;
+use std::num::Float;
+use triangulation;
+use rectangle::Border;
+use Graphics;
+use Context;
+use Color;
+
+/// A filled rectangle with semicircular ends
+///
+/// The radius of the ends is half of the smaller side,
+/// so a square gives a circle.
+#[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Capsule {
+    /// The capsule color
+    #[cfg_attr(feature = "serde", serde(serialize_with = "::serde_color::serialize",
+        deserialize_with = "::serde_color::deserialize"))]
+    pub color: internal::Color,
+    /// The capsule border
+    pub border: Option<Border>,
+    /// The number of segments in each quarter circle
+    pub resolution: usize,
+}
+
+impl Capsule {
+    /// Creates a new capsule
+    pub fn new(color: internal::Color) -> Capsule {
+        Capsule {
+            color: color,
+            border: None,
+            resolution: 32,
+        }
+    }
+
+    /// Creates a new capsule border
+    pub fn border(
+        color: internal::Color,
+        radius: internal::Radius
+    ) -> Capsule {
+        Capsule {
+            color: [0.0; 4],
+            border: Some(Border {
+                    color: color,
+                    radius: radius,
+                }),
+            resolution: 32,
+        }
+    }
+
+    /// Draws the capsule inside a rectangle.
+    pub fn draw<B>(
+        &self,
+        rectangle: internal::Rectangle,
+        c: &Context,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        let radius = 0.5 * rectangle[2].min(rectangle[3]);
+        let fill = c.apply_alpha(self.color);
+        if !c.skips(fill) {
+            back_end.tri_list(
+                &c.draw_state,
+                &fill,
+                |f|
+            triangulation::with_round_rectangle_tri_list(
+                self.resolution,
+                c.transform,
+                rectangle,
+                radius,
+                |vertices| f.emit(vertices)
+            ));
+        }
+
+        if let Some(Border { color, radius: border_radius }) = self.border {
+            let color = c.apply_alpha(color);
+            if c.skips(color) { return; }
+            back_end.tri_list(
+                &c.draw_state,
+                &color,
+                |f|
+            triangulation::with_round_rectangle_border_tri_list(
+                self.resolution,
+                c.transform,
+                rectangle,
+                radius,
+                border_radius,
+                |vertices| f.emit(vertices)
+            ));
+        }
+    }
+
+    /// Draws the capsule between two points,
+    /// with semicircular ends of a radius around each point.
+    ///
+    /// Two equal points give a circle.
+    pub fn draw_between<B>(
+        &self,
+        from: internal::Vec2d,
+        to: internal::Vec2d,
+        radius: internal::Radius,
+        c: &Context,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        use RelativeTransform;
+
+        let (dx, dy) = (to[0] - from[0], to[1] - from[1]);
+        let len = (dx * dx + dy * dy).sqrt();
+        let c = c.trans(from[0], from[1]).orient(dx, dy);
+        self.draw([-radius, -radius, len + 2.0 * radius, 2.0 * radius], &c, back_end);
+    }
+}
+
+quack! {
+    cap: Capsule[]
+    get:
+        fn () -> Color [] { Color(cap.color) }
+    set:
+        fn (val: Color) [] { cap.color = val.0 }
+        fn (val: Border) [] { cap.border = Some(val) }
+    action:
+}
+
+#[cfg(test)]
+mod test {
+    use super::Capsule;
+    use internal::Scalar;
+    use std::num::Float;
+    use radians::Radians;
+    use test_backend::RecordingGraphics;
+    use Context;
+
+    fn area(rect: [Scalar; 4]) -> Scalar {
+        let mut g = RecordingGraphics::new();
+        let capsule = Capsule { resolution: 256, ..Capsule::new([1.0; 4]) };
+        capsule.draw(rect, &Context::new(), &mut g);
+        tri_list_area(&g)
+    }
+
+    fn tri_list_area(g: &RecordingGraphics) -> Scalar {
+        g.tri_lists[0].vertices.chunks(6).fold(0.0, |sum, t| {
+            let cross = (t[2] - t[0]) * (t[5] - t[1]) - (t[4] - t[0]) * (t[3] - t[1]);
+            sum + 0.5 * (cross as Scalar).abs()
+        })
+    }
+
+    #[test]
+    fn test_capsule_area() {
+        let pi: Scalar = Radians::_180();
+        // Horizontal, vertical and a circle.
+        for &(rect, expected) in [
+            ([0.0, 0.0, 4.0, 2.0], 4.0 + pi),
+            ([0.0, 0.0, 2.0, 6.0], 8.0 + pi),
+            ([0.0, 0.0, 2.0, 2.0], pi),
+        ].iter() {
+            assert!((area(rect) - expected).abs() < 0.01 * expected);
+        }
+    }
+
+    #[test]
+    fn test_draw_between() {
+        let pi: Scalar = Radians::_180();
+        let capsule = Capsule { resolution: 256, ..Capsule::new([1.0; 4]) };
+        let mut g = RecordingGraphics::new();
+        capsule.draw_between([1.0, 1.0], [1.0, 1.0], 0.5, &Context::new(), &mut g);
+        assert!((tri_list_area(&g) - 0.25 * pi).abs() < 0.01 * 0.25 * pi);
+        let max_dist = g.tri_lists[0].vertices.chunks(2).fold(0.0, |d, v| {
+            let (x, y) = (v[0] as Scalar - 1.0, v[1] as Scalar - 1.0);
+            d.max((x * x + y * y).sqrt())
+        });
+        assert!((max_dist - 0.5).abs() < 0.0001);
+
+        let mut g = RecordingGraphics::new();
+        capsule.draw_between([0.0, 0.0], [3.0, 4.0], 1.0, &Context::new(), &mut g);
+        assert!((tri_list_area(&g) - (10.0 + pi)).abs() < 0.01 * (10.0 + pi));
+    }
+}
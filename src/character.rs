@@ -7,31 +7,31 @@ use ImageSize;
 #[derive(Clone)]
 pub struct Character<T: ImageSize> {
     /// The offset of character.
-    pub offset: [f64; 2],
+    pub offset: [internal::Scalar; 2],
     /// The size of character, including space.
-    pub size: [f64; 2],
+    pub size: [internal::Scalar; 2],
     /// The texture of the character.
     pub texture: T,
 }
 
 impl<T: ImageSize> Character<T> {
     /// The left offset.
-    pub fn left(&self) -> f64 {
+    pub fn left(&self) -> internal::Scalar {
         self.offset[0]
     }
 
     /// The top offset.
-    pub fn top(&self) -> f64 {
+    pub fn top(&self) -> internal::Scalar {
         self.offset[1]
     }
 
     /// Gets width of character, including space to the next one.
-    pub fn width(&self) -> f64 {
+    pub fn width(&self) -> internal::Scalar {
         self.size[0]
     }
 
     /// Sets height of character, including space to the next one.
-    pub fn height(&self) -> f64 {
+    pub fn height(&self) -> internal::Scalar {
         self.size[1]
     }
 }
@@ -40,12 +40,67 @@ impl<T: ImageSize> Character<T> {
 pub trait CharacterCache {
     /// The textyre type associated with the character cache.
     type Texture: ImageSize;
+    /// The error returned when a character can not be loaded.
+    ///
+    /// Caches that never fail can use `()`.
+    type Error;
 
     /// Get reference to character.
     fn character(
         &mut self, 
         font_size: internal::FontSize, 
         ch: char
-    ) -> &Character<<Self as CharacterCache>::Texture>;
+    ) -> Result<&Character<<Self as CharacterCache>::Texture>,
+                <Self as CharacterCache>::Error>;
+
+    /// Returns the kerning adjustment between two adjacent characters.
+    ///
+    /// The adjustment is added to the advance of the left character.
+    /// Negative values move the characters closer together.
+    /// The default implementation returns zero.
+    fn kerning(
+        &mut self,
+        _font_size: internal::FontSize,
+        _left: char,
+        _right: char
+    ) -> internal::Scalar {
+        0.0
+    }
+
+    /// Returns the id of a shared texture, like a font atlas,
+    /// and the region of a character in it.
+    ///
+    /// The texture of the character must be the shared texture.
+    /// `Text` draws consecutive characters with the same id
+    /// and color with one call to `tri_list_uv`.
+    /// The default implementation returns `None`,
+    /// which draws every character with its whole texture.
+    fn atlas_region(
+        &mut self,
+        _font_size: internal::FontSize,
+        _ch: char
+    ) -> Option<(usize, internal::SourceRectangle)> {
+        None
+    }
+
+    /// Computes the width of a text, the sum of the character widths
+    /// and the kerning between them.
+    fn width(
+        &mut self,
+        font_size: internal::FontSize,
+        text: &str
+    ) -> Result<internal::Scalar, <Self as CharacterCache>::Error> {
+        let mut width = 0.0;
+        let mut previous = None;
+        for ch in text.chars() {
+            if let Some(left) = previous {
+                width += self.kerning(font_size, left, ch);
+            }
+            let character = try!(self.character(font_size, ch));
+            width += character.width();
+            previous = Some(ch);
+        }
+        Ok(width)
+    }
 }
 
@@ -1,9 +1,42 @@
 //! Helper methods for colors
 
+use std::vec::Vec;
 use internal;
+use std::num::Float;
 
-pub const WHITE: internal::Color = [1.0; 4];
+/// Black, `#000000`
 pub const BLACK: internal::Color = [0.0, 0.0, 0.0, 1.0];
+/// Silver, `#C0C0C0`
+pub const SILVER: internal::Color = [0.7529412, 0.7529412, 0.7529412, 1.0];
+/// Gray, `#808080`
+pub const GRAY: internal::Color = [0.5019608, 0.5019608, 0.5019608, 1.0];
+/// White, `#FFFFFF`
+pub const WHITE: internal::Color = [1.0; 4];
+/// Maroon, `#800000`
+pub const MAROON: internal::Color = [0.5019608, 0.0, 0.0, 1.0];
+/// Red, `#FF0000`
+pub const RED: internal::Color = [1.0, 0.0, 0.0, 1.0];
+/// Purple, `#800080`
+pub const PURPLE: internal::Color = [0.5019608, 0.0, 0.5019608, 1.0];
+/// Fuchsia, `#FF00FF`
+pub const FUCHSIA: internal::Color = [1.0, 0.0, 1.0, 1.0];
+/// Green, `#008000`
+pub const GREEN: internal::Color = [0.0, 0.5019608, 0.0, 1.0];
+/// Lime, `#00FF00`
+pub const LIME: internal::Color = [0.0, 1.0, 0.0, 1.0];
+/// Olive, `#808000`
+pub const OLIVE: internal::Color = [0.5019608, 0.5019608, 0.0, 1.0];
+/// Yellow, `#FFFF00`
+pub const YELLOW: internal::Color = [1.0, 1.0, 0.0, 1.0];
+/// Navy, `#000080`
+pub const NAVY: internal::Color = [0.0, 0.0, 0.5019608, 1.0];
+/// Blue, `#0000FF`
+pub const BLUE: internal::Color = [0.0, 0.0, 1.0, 1.0];
+/// Teal, `#008080`
+pub const TEAL: internal::Color = [0.0, 0.5019608, 0.5019608, 1.0];
+/// Aqua, `#00FFFF`
+pub const AQUA: internal::Color = [0.0, 1.0, 1.0, 1.0];
+/// Fully transparent black
 pub const TRANSPARENT: internal::Color = [0.0; 4];
 
 /// Returns a grey color
@@ -16,20 +49,317 @@ pub fn alpha(f: internal::ColorComponent) -> internal::Color {
     [1.0, 1.0, 1.0, f]
 }
 
+/// Returns the color with the alpha replaced.
+pub fn with_alpha(c: internal::Color, a: internal::ColorComponent) -> internal::Color {
+    [c[0], c[1], c[2], a]
+}
+
+/// Returns the color with the alpha multiplied by a factor.
+pub fn fade(c: internal::Color, factor: internal::ColorComponent) -> internal::Color {
+    [c[0], c[1], c[2], c[3] * factor]
+}
+
 /// Converts from hexadecimal color format
+///
+/// Panics if the string is not a valid hexadecimal color.
 pub fn hex(hex: &str) -> internal::Color {
+    try_hex(hex).unwrap()
+}
+
+/// Converts from hexadecimal color format,
+/// returning `None` if the string is not a valid color.
+pub fn try_hex(hex: &str) -> Option<internal::Color> {
     use read_color::rgb_maybe_a;
 
-    let (rgb, a) = rgb_maybe_a(&mut hex.chars()).unwrap();
+    let (rgb, a) = match rgb_maybe_a(&mut hex.chars()) {
+        Some(x) => x,
+        None => return None
+    };
     let color = match a {
         None => [rgb[0], rgb[1], rgb[2], 255],
         Some(a) => [rgb[0], rgb[1], rgb[2], a]
     };
     let inv_255 = 1.0f32 / 255.0f32;
-    [
+    Some([
         color[0] as f32 * inv_255,
         color[1] as f32 * inv_255,
         color[2] as f32 * inv_255,
         color[3] as f32 * inv_255
+    ])
+}
+
+/// Converts a color from gamma corrected sRGB to linear color space.
+///
+/// The alpha channel is left unchanged.
+pub fn gamma_srgb_to_linear(c: internal::Color) -> internal::Color {
+    fn component(f: internal::ColorComponent) -> internal::ColorComponent {
+        if f <= 0.04045 {
+            f / 12.92
+        } else {
+            ((f + 0.055) / 1.055).powf(2.4)
+        }
+    }
+    [component(c[0]), component(c[1]), component(c[2]), c[3]]
+}
+
+/// Converts a color from linear to gamma corrected sRGB color space.
+///
+/// The alpha channel is left unchanged.
+pub fn linear_to_gamma_srgb(c: internal::Color) -> internal::Color {
+    fn component(f: internal::ColorComponent) -> internal::ColorComponent {
+        if f <= 0.0031308 {
+            f * 12.92
+        } else {
+            1.055 * f.powf(1.0 / 2.4) - 0.055
+        }
+    }
+    [component(c[0]), component(c[1]), component(c[2]), c[3]]
+}
+
+/// Returns the red, green and blue components of a fully saturated hue.
+///
+/// The hue is in degrees, and wraps around at 360.
+fn hue_rgb(h: internal::ColorComponent) -> [internal::ColorComponent; 3] {
+    let h = (h % 360.0 + 360.0) % 360.0 / 60.0;
+    let x = 1.0 - (h % 2.0 - 1.0).abs();
+    match h as u32 {
+        0 => [1.0, x, 0.0],
+        1 => [x, 1.0, 0.0],
+        2 => [0.0, 1.0, x],
+        3 => [0.0, x, 1.0],
+        4 => [x, 0.0, 1.0],
+        _ => [1.0, 0.0, x],
+    }
+}
+
+/// Converts from hue, saturation, lightness and alpha.
+///
+/// The hue is in degrees from 0 to 360, the other components from 0 to 1.
+pub fn hsl(
+    h: internal::ColorComponent,
+    s: internal::ColorComponent,
+    l: internal::ColorComponent,
+    a: internal::ColorComponent
+) -> internal::Color {
+    let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let m = l - 0.5 * chroma;
+    let rgb = hue_rgb(h);
+    [rgb[0] * chroma + m, rgb[1] * chroma + m, rgb[2] * chroma + m, a]
+}
+
+/// Converts to hue, saturation, lightness and alpha.
+///
+/// The hue is in degrees from 0 to 360, and zero for grays.
+/// See `hsl`.
+pub fn to_hsl(c: internal::Color) -> (
+    internal::ColorComponent,
+    internal::ColorComponent,
+    internal::ColorComponent,
+    internal::ColorComponent
+) {
+    let (r, g, b) = (c[0], c[1], c[2]);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = 0.5 * (max + min);
+    let d = max - min;
+    if d == 0.0 { return (0.0, 0.0, l, c[3]); }
+
+    let s = d / (1.0 - (2.0 * l - 1.0).abs());
+    let h = if max == r {
+        60.0 * ((g - b) / d)
+    } else if max == g {
+        60.0 * ((b - r) / d + 2.0)
+    } else {
+        60.0 * ((r - g) / d + 4.0)
+    };
+    (if h < 0.0 { h + 360.0 } else { h }, s, l, c[3])
+}
+
+/// Interpolates linearly between two colors, component by component.
+///
+/// The factor `t` is clamped to the interval between 0 and 1.
+pub fn lerp(
+    a: internal::Color,
+    b: internal::Color,
+    t: internal::ColorComponent
+) -> internal::Color {
+    let t = if t < 0.0 { 0.0 } else if t > 1.0 { 1.0 } else { t };
+    [
+        a[0] + (b[0] - a[0]) * t,
+        a[1] + (b[1] - a[1]) * t,
+        a[2] + (b[2] - a[2]) * t,
+        a[3] + (b[3] - a[3]) * t
     ]
 }
+
+/// A gradient described by color stops.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Gradient {
+    /// The color stops as (position, color), sorted by position.
+    pub stops: Vec<(f32, internal::Color)>,
+    /// Whether to interpolate in linear color space.
+    ///
+    /// When set, the stop colors are treated as sRGB and
+    /// converted to linear space before interpolating.
+    pub linear: bool,
+}
+
+impl Gradient {
+    /// Creates a new gradient, sorting the stops by position.
+    ///
+    /// Stops with infinite or NaN positions are dropped.
+    pub fn new(mut stops: Vec<(f32, internal::Color)>) -> Gradient {
+        stops.retain(|&(t, _)| t.is_finite());
+        // Finite positions always compare.
+        stops.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        Gradient {
+            stops: stops,
+            linear: false,
+        }
+    }
+
+    /// Creates a new gradient that interpolates in linear color space.
+    pub fn linear(stops: Vec<(f32, internal::Color)>) -> Gradient {
+        Gradient {
+            linear: true,
+            ..Gradient::new(stops)
+        }
+    }
+
+    /// Returns the gradient with the alpha of the stops multiplied by a factor.
+    pub fn fade(&self, factor: internal::ColorComponent) -> Gradient {
+        Gradient {
+            stops: self.stops.iter().map(|&(t, c)| (t, fade(c, factor))).collect(),
+            linear: self.linear,
+        }
+    }
+
+    /// Samples the gradient at a position.
+    ///
+    /// Positions before the first stop or after the last stop
+    /// are clamped to the endpoint colors.
+    /// Returns a transparent color if there are no stops.
+    pub fn sample(&self, t: f32) -> internal::Color {
+        let n = self.stops.len();
+        if n == 0 { return TRANSPARENT; }
+
+        let (first_pos, first_color) = self.stops[0];
+        if t <= first_pos { return first_color; }
+        let (last_pos, last_color) = self.stops[n - 1];
+        if t >= last_pos { return last_color; }
+
+        for i in 0..n - 1 {
+            let (p0, c0) = self.stops[i];
+            let (p1, c1) = self.stops[i + 1];
+            if t >= p1 { continue; }
+
+            let f = if p1 == p0 { 1.0 } else { (t - p0) / (p1 - p0) };
+            return if self.linear {
+                linear_to_gamma_srgb(lerp(
+                    gamma_srgb_to_linear(c0),
+                    gamma_srgb_to_linear(c1),
+                    f
+                ))
+            } else {
+                lerp(c0, c1, f)
+            };
+        }
+        last_color
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hsl() {
+        assert_eq!(hsl(0.0, 1.0, 0.5, 1.0), RED);
+        assert_eq!(hsl(120.0, 1.0, 0.5, 1.0), LIME);
+        assert_eq!(hsl(240.0, 1.0, 0.5, 1.0), BLUE);
+        assert_eq!(hsl(60.0, 1.0, 0.5, 1.0), YELLOW);
+        assert_eq!(hsl(360.0 + 300.0, 1.0, 0.5, 0.5), [1.0, 0.0, 1.0, 0.5]);
+        assert_eq!(hsl(200.0, 0.0, 0.25, 1.0), [0.25, 0.25, 0.25, 1.0]);
+
+        assert_eq!(to_hsl(RED), (0.0, 1.0, 0.5, 1.0));
+        assert_eq!(to_hsl(LIME), (120.0, 1.0, 0.5, 1.0));
+        assert_eq!(to_hsl(BLUE), (240.0, 1.0, 0.5, 1.0));
+        assert_eq!(to_hsl(FUCHSIA), (300.0, 1.0, 0.5, 1.0));
+        assert_eq!(to_hsl(grey(0.25)), (0.0, 0.0, 0.25, 1.0));
+    }
+
+    #[test]
+    fn test_named_colors() {
+        assert_eq!(RED, [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(TRANSPARENT[3], 0.0);
+        for &(c, h) in [
+            (BLACK, "000000"), (SILVER, "C0C0C0"), (GRAY, "808080"),
+            (WHITE, "FFFFFF"), (MAROON, "800000"), (RED, "FF0000"),
+            (PURPLE, "800080"), (FUCHSIA, "FF00FF"), (GREEN, "008000"),
+            (LIME, "00FF00"), (OLIVE, "808000"), (YELLOW, "FFFF00"),
+            (NAVY, "000080"), (BLUE, "0000FF"), (TEAL, "008080"),
+            (AQUA, "00FFFF"),
+        ].iter() {
+            let expected = hex(h);
+            for i in 0..4 {
+                assert!((c[i] - expected[i]).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_with_alpha() {
+        let c = [0.25, 0.5, 0.75, 0.5];
+        assert_eq!(with_alpha(c, 0.1), [0.25, 0.5, 0.75, 0.1]);
+        assert_eq!(fade(c, 0.5), [0.25, 0.5, 0.75, 0.25]);
+    }
+
+    #[test]
+    fn test_context_alpha() {
+        use { Context, Image };
+        use test_backend::{ RecordingGraphics, Texture };
+
+        let mut g = RecordingGraphics::new();
+        Image::colored(with_alpha(WHITE, 0.8)).draw(&Texture, &Context::new().alpha(0.5), &mut g);
+        assert!((g.tri_lists_uv[0].color[3] - 0.4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_lerp() {
+        assert_eq!(lerp(BLACK, WHITE, 0.5), [0.5, 0.5, 0.5, 1.0]);
+        assert_eq!(lerp(BLACK, WHITE, -1.0), BLACK);
+        assert_eq!(lerp(BLACK, WHITE, 2.0), WHITE);
+    }
+
+    #[test]
+    fn test_gradient() {
+        use std::num::Float;
+
+        let red = [1.0, 0.0, 0.0, 1.0];
+        let green = [0.0, 1.0, 0.0, 1.0];
+        let blue = [0.0, 0.0, 1.0, 1.0];
+        let gradient = Gradient::new(vec![(1.0, blue), (0.0, red), (0.5, green)]);
+        assert_eq!(gradient.sample(0.0), red);
+        assert_eq!(gradient.sample(0.5), green);
+        assert_eq!(gradient.sample(1.0), blue);
+        assert_eq!(gradient.sample(0.25), [0.5, 0.5, 0.0, 1.0]);
+        assert_eq!(gradient.sample(-1.0), red);
+        assert_eq!(gradient.sample(2.0), blue);
+
+        let nan: f32 = Float::nan();
+        let inf: f32 = Float::infinity();
+        let gradient = Gradient::new(vec![(nan, blue), (1.0, blue), (-inf, green), (0.0, red)]);
+        assert_eq!(gradient.stops, vec![(0.0, red), (1.0, blue)]);
+        assert_eq!(Gradient::new(vec![(nan, red)]).sample(0.5), TRANSPARENT);
+    }
+
+    #[test]
+    fn test_linear_gradient() {
+        let gradient = Gradient::linear(vec![(0.0, BLACK), (1.0, WHITE)]);
+        assert_eq!(gradient.sample(0.0), BLACK);
+        assert_eq!(gradient.sample(1.0), WHITE);
+        // Linear interpolation is brighter than sRGB at the midpoint.
+        assert!(gradient.sample(0.5)[0] > 0.5);
+    }
+}
@@ -0,0 +1,201 @@
+//! Record draw commands and replay them later
+//!
+//! A `CommandList` can be filled on a worker thread,
+//! then sent to the render thread and replayed against a real back-end.
+
+use std::vec::Vec;
+use draw_state::DrawState;
+use { AttributeEmitter, Emitter, Graphics };
+use ImageSize;
+
+/// Refers to a texture while recording.
+///
+/// The `id` is an index into the textures passed to
+/// `CommandList::replay_with_textures`.
+/// The size is used by `ImageSize`, so it should be the size
+/// of the texture the id refers to.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct TextureHandle {
+    /// The index of the texture when replaying
+    pub id: usize,
+    /// The width of the texture in pixels
+    pub width: u32,
+    /// The height of the texture in pixels
+    pub height: u32,
+}
+
+impl ImageSize for TextureHandle {
+    fn get_size(&self) -> (u32, u32) { (self.width, self.height) }
+}
+
+/// A recorded call to the back-end.
+#[derive(Clone)]
+pub enum Command {
+    /// A call to `clear`.
+    Clear([f32; 4]),
+    /// A call to `clear_stencil`.
+    ClearStencil(u8),
+    /// A call to `set_viewport`.
+    SetViewport([u32; 4]),
+    /// A call to `tri_list`, with the vertices of all chunks.
+    TriList(DrawState, [f32; 4], Vec<f32>),
+    /// A call to `tri_list_uv`, with the texture id,
+    /// the vertices and the texture coordinates of all chunks.
+    TriListUv(DrawState, [f32; 4], usize, Vec<f32>, Vec<f32>),
+    /// A call to `tri_list_c`, with the vertices and colors of all chunks.
+    TriListC(DrawState, Vec<f32>, Vec<f32>),
+}
+
+/// A back-end that records commands to replay them on another back-end.
+///
+/// Colors per vertex are recorded as they are,
+/// and converted by the back-end that replays them,
+/// so `has_vertex_colors` returns `true`.
+#[derive(Clone)]
+pub struct CommandList {
+    /// The recorded commands, in order
+    pub commands: Vec<Command>,
+}
+
+impl CommandList {
+    /// Creates an empty command list.
+    pub fn new() -> CommandList {
+        CommandList { commands: Vec::new() }
+    }
+
+    /// Removes all commands, keeping the allocated memory.
+    pub fn reset(&mut self) {
+        self.commands.clear();
+    }
+
+    /// Issues the recorded commands on a back-end.
+    ///
+    /// Commands using textures are skipped,
+    /// use `replay_with_textures` to draw them.
+    pub fn replay<B>(&self, g: &mut B) where B: Graphics {
+        self.replay_with_textures(&[], g);
+    }
+
+    /// Issues the recorded commands on a back-end,
+    /// looking up texture ids in `textures`.
+    ///
+    /// Commands with a texture id out of range are skipped.
+    pub fn replay_with_textures<B>(
+        &self,
+        textures: &[&<B as Graphics>::Texture],
+        g: &mut B
+    )
+        where B: Graphics
+    {
+        for command in self.commands.iter() {
+            match *command {
+                Command::Clear(color) => g.clear(color),
+                Command::ClearStencil(value) => g.clear_stencil(value),
+                Command::SetViewport(rect) => g.set_viewport(rect),
+                Command::TriList(ref draw_state, ref color, ref vertices) => {
+                    g.tri_list(draw_state, color, |f| f.emit(&vertices[..]));
+                }
+                Command::TriListUv(ref draw_state, ref color, id, ref vertices, ref uvs) => {
+                    if let Some(texture) = textures.get(id) {
+                        g.tri_list_uv(draw_state, color, *texture,
+                            |f| f.emit(&vertices[..], &uvs[..]));
+                    }
+                }
+                Command::TriListC(ref draw_state, ref vertices, ref colors) => {
+                    g.tri_list_c(draw_state, |f| f.emit(&vertices[..], &colors[..]));
+                }
+            }
+        }
+    }
+}
+
+impl Graphics for CommandList {
+    type Texture = TextureHandle;
+
+    fn clear(&mut self, color: [f32; 4]) {
+        self.commands.push(Command::Clear(color));
+    }
+
+    fn clear_stencil(&mut self, value: u8) {
+        self.commands.push(Command::ClearStencil(value));
+    }
+
+    fn set_viewport(&mut self, rect: [u32; 4]) {
+        self.commands.push(Command::SetViewport(rect));
+    }
+
+    fn tri_list<F>(&mut self, draw_state: &DrawState, color: &[f32; 4], mut f: F)
+        where F: FnMut(&mut Emitter)
+    {
+        let mut vertices = Vec::new();
+        f(&mut Emitter::new(&mut vertices));
+        self.commands.push(Command::TriList(draw_state.clone(), *color, vertices));
+    }
+
+    fn tri_list_uv<F>(
+        &mut self,
+        draw_state: &DrawState,
+        color: &[f32; 4],
+        texture: &TextureHandle,
+        mut f: F
+    ) where F: FnMut(&mut AttributeEmitter)
+    {
+        let mut vertices = Vec::new();
+        let mut uvs = Vec::new();
+        f(&mut AttributeEmitter::new(&mut vertices, &mut uvs));
+        self.commands.push(Command::TriListUv(
+            draw_state.clone(), *color, texture.id, vertices, uvs));
+    }
+
+    fn has_vertex_colors(&self) -> bool { true }
+
+    fn tri_list_c<F>(&mut self, draw_state: &DrawState, mut f: F)
+        where F: FnMut(&mut AttributeEmitter)
+    {
+        let mut vertices = Vec::new();
+        let mut colors = Vec::new();
+        f(&mut AttributeEmitter::new(&mut vertices, &mut colors));
+        self.commands.push(Command::TriListC(draw_state.clone(), vertices, colors));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ CommandList, TextureHandle };
+    use test_backend::{ RecordingGraphics, Texture };
+    use { Context, Graphics, Image, Rectangle };
+
+    #[test]
+    fn test_replay() {
+        let c = Context::abs(100.0, 100.0);
+        let mut list = CommandList::new();
+        list.clear([1.0; 4]);
+        list.clear([0.0, 0.0, 0.0, 1.0]);
+        Rectangle::new([1.0, 0.0, 0.0, 1.0]).draw([10.0, 10.0, 20.0, 20.0], &c, &mut list);
+        let handle = TextureHandle { id: 0, width: 256, height: 256 };
+        Image::new().draw(&handle, &c, &mut list);
+
+        let mut direct = RecordingGraphics::new();
+        direct.clear([1.0; 4]);
+        direct.clear([0.0, 0.0, 0.0, 1.0]);
+        Rectangle::new([1.0, 0.0, 0.0, 1.0]).draw([10.0, 10.0, 20.0, 20.0], &c, &mut direct);
+        Image::new().draw(&Texture, &c, &mut direct);
+
+        let mut replayed = RecordingGraphics::new();
+        list.replay_with_textures(&[&Texture], &mut replayed);
+        assert_eq!(replayed.clears, direct.clears);
+        assert_eq!(replayed.tri_lists.len(), 1);
+        assert_eq!(replayed.tri_lists[0].color, direct.tri_lists[0].color);
+        assert_eq!(replayed.tri_lists[0].vertices, direct.tri_lists[0].vertices);
+        assert_eq!(replayed.tri_lists_uv.len(), 1);
+        assert_eq!(replayed.tri_lists_uv[0].vertices, direct.tri_lists_uv[0].vertices);
+        assert_eq!(replayed.tri_lists_uv[0].uvs, direct.tri_lists_uv[0].uvs);
+
+        // Without textures, only the commands without textures are replayed.
+        let mut replayed = RecordingGraphics::new();
+        list.replay(&mut replayed);
+        assert_eq!(replayed.clears.len(), 2);
+        assert_eq!(replayed.tri_lists.len(), 1);
+        assert_eq!(replayed.tri_lists_uv.len(), 0);
+    }
+}
@@ -1,18 +1,34 @@
 //! Transformation context
 
-use draw_state::DrawState;
+use draw_state::{ self, DrawState };
+use draw_state::block::{ Blend, CullFace, RasterMethod };
+use std::num::Float;
+use blend;
+use stencil::Stencil;
+use color;
+use Graphics;
+use Line;
+use internal::{ Color, ColorComponent };
+use math::transform_pos;
 use vecmath::{
     identity,
+    invert,
+    multiply,
+    rotate_radians,
+    translate,
     Matrix2d,
-    Scalar
+    Scalar,
+    Vec2d
 };
 
 /// Transform property
 #[derive(Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Transform(pub Matrix2d);
 
 /// View transform property
 #[derive(Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ViewTransform(pub Matrix2d);
 
 /// Drawing 2d context.
@@ -24,6 +40,8 @@ pub struct Context {
     pub transform: Matrix2d,
     /// Current draw state settings.
     pub draw_state: DrawState,
+    /// Opacity multiplied with the alpha of colors.
+    pub alpha: ColorComponent,
 }
 
 quack! {
@@ -37,9 +55,17 @@ quack! {
     action:
 }
 
+quack! {
+    m: Matrix2d[]
+    get:
+        fn () -> Transform [] { Transform(*m) }
+    set:
+        fn (val: Transform) [] { *m = val.0 }
+    action:
+}
+
 
 fn default_draw_state() -> DrawState {
-    use draw_state::block::{ CullFace, RasterMethod };
     use draw_state::BlendPreset;
 
     let mut draw_state = DrawState::new();
@@ -47,6 +73,13 @@ fn default_draw_state() -> DrawState {
     draw_state.blend(BlendPreset::Alpha)
 }
 
+/// Returns the scale from pixels to normalized device coordinates,
+/// or zero for an empty size, which collapses drawing instead of
+/// producing infinite or NaN vertices.
+fn pixel_scale(size: Scalar) -> Scalar {
+    if size == 0.0 { 0.0 } else { 2.0 / size }
+}
+
 impl Context {
     /// Creates a new drawing context.
     #[inline(always)]
@@ -55,6 +88,7 @@ impl Context {
             view: identity(),
             transform: identity(),
             draw_state: default_draw_state(),
+            alpha: 1.0,
         }
     }
 
@@ -68,24 +102,398 @@ impl Context {
     /// with origin in the upper left corner
     /// and x axis pointing to the right
     /// and y axis pointing down.
+    ///
+    /// A zero width or height collapses everything drawn
+    /// to a line or a point in the center, which draws nothing.
     #[inline(always)]
     pub fn abs(w: Scalar, h: Scalar) -> Context {
-        let sx = 2.0 / w;
-        let sy = -2.0 / h;
+        let sx = pixel_scale(w);
+        let sy = -pixel_scale(h);
         let mat = [[ sx, 0.0, -1.0 ],
                    [ 0.0,  sy, 1.0 ]];
         Context {
             view: mat,
             transform: mat,
             draw_state: default_draw_state(),
+            alpha: 1.0,
+        }
+    }
+
+    /// Creates a new drawing context from a pixel viewport [x, y, w, h].
+    ///
+    /// The coordinates are pixels of the render target,
+    /// with origin in the upper left corner
+    /// and x axis pointing to the right
+    /// and y axis pointing down.
+    /// The upper left corner of the viewport is at [x, y],
+    /// which makes it possible to render to a sub-viewport
+    /// using the same coordinates as the whole render target.
+    /// An empty viewport draws nothing, like `abs`.
+    #[inline(always)]
+    pub fn from_viewport(viewport: [u32; 4]) -> Context {
+        let (x, y) = (viewport[0] as Scalar, viewport[1] as Scalar);
+        let (w, h) = (viewport[2] as Scalar, viewport[3] as Scalar);
+        let sx = pixel_scale(w);
+        let sy = -pixel_scale(h);
+        let mat = [[ sx, 0.0, -1.0 - sx * x ],
+                   [ 0.0,  sy, 1.0 - sy * y ]];
+        Context {
+            view: mat,
+            transform: mat,
+            draw_state: default_draw_state(),
+            alpha: 1.0,
+        }
+    }
+
+    /// Creates a new drawing context from a 4x4 projection matrix.
+    ///
+    /// The matrix is column major, `proj[column][row]`,
+    /// as used by OpenGL and most 3D math libraries.
+    /// Only orthographic projections are supported,
+    /// since perspective division is not available in 2D.
+    /// The 2D positions are projected with z = 0,
+    /// so the z column and the depth row are ignored.
+    #[inline(always)]
+    pub fn from_mat4(proj: [[f32; 4]; 4]) -> Context {
+        let p = |col: usize, row: usize| proj[col][row] as Scalar;
+        let mat = [[ p(0, 0), p(1, 0), p(3, 0) ],
+                   [ p(0, 1), p(1, 1), p(3, 1) ]];
+        Context {
+            view: mat,
+            transform: mat,
+            draw_state: default_draw_state(),
+            alpha: 1.0,
+        }
+    }
+
+    /// Creates a new drawing context from a draw state and a transform,
+    /// for callers that do not keep a context.
+    ///
+    /// The transform is also used as the view, at full opacity.
+    #[inline(always)]
+    pub fn from_state(draw_state: &DrawState, transform: Matrix2d) -> Context {
+        Context {
+            view: transform,
+            transform: transform,
+            draw_state: *draw_state,
+            alpha: 1.0,
+        }
+    }
+
+    /// Returns a context with the opacity multiplied by a factor.
+    ///
+    /// Nested calls multiply, so two factors of 0.5 give 0.25.
+    /// Primitives drawn with the context multiply the alpha of their colors
+    /// with the opacity, and are skipped when the result is zero,
+    /// see `skips`.
+    #[inline(always)]
+    pub fn alpha(&self, factor: ColorComponent) -> Context {
+        Context {
+            alpha: self.alpha * factor,
+            ..*self
+        }
+    }
+
+    /// Returns true if drawing with the color has no visible effect.
+    ///
+    /// Primitives use this to skip drawing transparent colors.
+    /// A color with zero alpha is only skipped with alpha blending,
+    /// since other blend modes, like additive blending,
+    /// or no blending at all, may still change the render target.
+    #[inline(always)]
+    pub fn skips(&self, color: Color) -> bool {
+        use draw_state::BlendPreset;
+
+        color[3] == 0.0
+        && self.draw_state.blend == DrawState::new().blend(BlendPreset::Alpha).blend
+    }
+
+    /// Returns a color with the alpha multiplied by the opacity.
+    #[inline(always)]
+    pub fn apply_alpha(&self, color: Color) -> Color {
+        [color[0], color[1], color[2], color[3] * self.alpha]
+    }
+
+    /// Converts a position in view coordinates to local coordinates.
+    ///
+    /// For a context from `Context::abs`, these are the pixels of the window,
+    /// like the position of the mouse cursor. Use this for hit testing
+    /// with `Rectangle::contains` and similar methods.
+    pub fn inverse_transform_pos(&self, pos: Vec2d) -> Vec2d {
+        transform_pos(multiply(invert(self.transform), self.view), pos)
+    }
+
+    /// Returns true if a rectangle [x, y, w, h] in local coordinates
+    /// is outside the view, so drawing inside it has no visible effect.
+    ///
+    /// The corners are transformed to normalized device coordinates
+    /// and their bounding box is tested against the unit square,
+    /// so rotated rectangles near the edges may not be culled.
+    /// The scissor rectangle is ignored.
+    pub fn culls(&self, rect: [Scalar; 4]) -> bool {
+        let (x, y, w, h) = (rect[0], rect[1], rect[2], rect[3]);
+        let corners = [[x, y], [x + w, y], [x, y + h], [x + w, y + h]];
+        let mut min: [Scalar; 2] = [Float::infinity(); 2];
+        let mut max: [Scalar; 2] = [Float::neg_infinity(); 2];
+        for &corner in corners.iter() {
+            let p = transform_pos(self.transform, corner);
+            for i in 0..2 {
+                min[i] = min[i].min(p[i]);
+                max[i] = max[i].max(p[i]);
+            }
+        }
+        max[0] < -1.0 || min[0] > 1.0 || max[1] < -1.0 || min[1] > 1.0
+    }
+
+    /// Converts a width in device pixels to local coordinates.
+    ///
+    /// Uses the average scale of the transform relative to the view,
+    /// which is exact for uniform scaling.
+    pub fn local_width(&self, pixels: Scalar) -> Scalar {
+        let scale = |m: Matrix2d| (m[0][0] * m[1][1] - m[0][1] * m[1][0]).abs().sqrt();
+        let s = scale(self.transform);
+        if s == 0.0 { 0.0 } else { pixels * scale(self.view) / s }
+    }
+
+    /// Returns a context that clips to a rectangle [x, y, w, h] in pixels.
+    ///
+    /// See `draw_state::set_scissor_rect` for the rounding.
+    #[inline(always)]
+    pub fn scissor(&self, rect: [Scalar; 4]) -> Context {
+        Context {
+            draw_state: draw_state::scissor_rect(self.draw_state, rect),
+            ..*self
+        }
+    }
+
+    /// Returns a context that clips to a rectangle [x, y, w, h] in pixels.
+    ///
+    /// The clip travels with the draw state to every primitive
+    /// drawn with the context.
+    /// Nested clips intersect with the current scissor rectangle.
+    #[inline(always)]
+    pub fn clip_rect(&self, rect: [u32; 4]) -> Context {
+        let mut draw_state = self.draw_state;
+        draw_state::intersect_scissor_rect(&mut draw_state, rect);
+        Context {
+            draw_state: draw_state,
+            ..*self
+        }
+    }
+
+    /// Returns a context that does not clip.
+    #[inline(always)]
+    pub fn no_clip(&self) -> Context {
+        let mut draw_state = self.draw_state;
+        draw_state.scissor = None;
+        Context {
+            draw_state: draw_state,
+            ..*self
+        }
+    }
+
+    /// Returns a context that culls triangles facing away.
+    ///
+    /// Sets the raster method to fill with the cull face.
+    /// Which side is the front depends on the winding order
+    /// in `draw_state.primitive.front_face`, which is counter-clockwise
+    /// by default, as seen in normalized device coordinates.
+    /// The triangulation of this library is counter-clockwise
+    /// in normalized device coordinates, so `CullFace::Back`
+    /// keeps all shapes drawn by the built-in primitives.
+    #[inline(always)]
+    pub fn cull(&self, face: CullFace) -> Context {
+        let mut draw_state = self.draw_state;
+        draw_state.primitive.method = RasterMethod::Fill(face);
+        Context {
+            draw_state: draw_state,
+            ..*self
+        }
+    }
+
+    /// Returns a context that draws triangles facing both ways.
+    #[inline(always)]
+    pub fn no_cull(&self) -> Context {
+        self.cull(CullFace::Nothing)
+    }
+
+    /// Returns a context using the stencil buffer.
+    ///
+    /// See the `stencil` module for the typical usage.
+    #[inline(always)]
+    pub fn stencil(&self, stencil: Stencil) -> Context {
+        Context {
+            draw_state: stencil.apply(self.draw_state),
+            ..*self
+        }
+    }
+
+    /// Returns a context with a blend setting, or no blending for `None`.
+    ///
+    /// See the `blend` module for common settings.
+    #[inline(always)]
+    pub fn blend(&self, blend: Option<Blend>) -> Context {
+        let mut draw_state = self.draw_state;
+        draw_state.blend = blend;
+        Context {
+            draw_state: draw_state,
+            ..*self
+        }
+    }
+
+    /// Returns a context that mixes colors by their alpha.
+    ///
+    /// This is the default, see `blend::alpha`.
+    /// Named to not collide with `alpha`, which sets the opacity.
+    #[inline(always)]
+    pub fn alpha_blend(&self) -> Context {
+        self.blend(Some(blend::alpha()))
+    }
+
+    /// Returns a context that adds colors, see `blend::additive`.
+    #[inline(always)]
+    pub fn additive(&self) -> Context {
+        self.blend(Some(blend::additive()))
+    }
+
+    /// Returns a context that multiplies colors, see `blend::multiply`.
+    #[inline(always)]
+    pub fn multiply(&self) -> Context {
+        self.blend(Some(blend::multiply()))
+    }
+
+    /// Returns a context that screens colors, see `blend::screen`.
+    #[inline(always)]
+    pub fn screen(&self) -> Context {
+        self.blend(Some(blend::screen()))
+    }
+
+    /// Returns a context that replaces the render target with the colors,
+    /// without blending.
+    ///
+    /// The alpha is written as it is, and transparent colors are drawn.
+    #[inline(always)]
+    pub fn replace(&self) -> Context {
+        self.blend(None)
+    }
+
+    /// Draws the local x axis as a red line and the y axis as a lime line
+    /// from the origin, for debugging transforms.
+    ///
+    /// The axes follow the current transform.
+    /// The radius of the lines is 2% of the length.
+    pub fn draw_axes<B>(&self, length: Scalar, back_end: &mut B)
+        where B: Graphics
+    {
+        let radius = 0.02 * length;
+        Line::new(color::RED, radius).draw([0.0, 0.0, length, 0.0], self, back_end);
+        Line::new(color::LIME, radius).draw([0.0, 0.0, 0.0, length], self, back_end);
+    }
+
+    /// Applies a transform in view coordinates after the current one.
+    ///
+    /// Computes `view * transform * inverse(view) * current`,
+    /// so the transform is applied last, after all local transforms,
+    /// in the coordinates of the view.
+    /// This is useful for effects like a screen shake on a nested transform.
+    /// The view must be invertible.
+    #[inline(always)]
+    pub fn prepend_view_transform(&self, transform: Matrix2d) -> Context {
+        let view_transform = multiply(multiply(self.view, transform), invert(self.view));
+        Context {
+            transform: multiply(view_transform, self.transform),
+            ..*self
+        }
+    }
+
+    /// Returns the context of a child in the local coordinates of this one,
+    /// for scene graphs.
+    ///
+    /// The transform of the child is applied before the current transform,
+    /// so the child should start from `Context::new`, not from a view.
+    /// The view of this context is kept, and the opacities multiply.
+    /// The draw state of the child replaces the current one,
+    /// so a child from `Context::new` uses alpha blending without clipping.
+    pub fn compose(&self, child: &Context) -> Context {
+        Context {
+            view: self.view,
+            transform: multiply(self.transform, child.transform),
+            draw_state: child.draw_state,
+            alpha: self.alpha * child.alpha,
+        }
+    }
+
+    /// Returns a context between this and another one, for animations.
+    ///
+    /// The transform is interpolated with `math::interpolate_transform`,
+    /// and the opacity linearly. The view and draw state are kept.
+    pub fn interpolated_towards(&self, other: &Context, t: Scalar) -> Context {
+        use math::interpolate_transform;
+
+        Context {
+            transform: interpolate_transform(self.transform, other.transform, t),
+            alpha: self.alpha + (other.alpha - self.alpha) * t as ColorComponent,
+            ..*self
+        }
+    }
+
+    /// Returns a context at the origin of the current transform,
+    /// drawing at a fixed size in view coordinates.
+    ///
+    /// The rotation, scale and shear of the transform relative to the view
+    /// are removed, so zooming a world context does not change the size
+    /// of HUD elements or labels drawn above objects with the result.
+    /// For a context from `Context::abs`, one unit is one pixel.
+    /// To draw from the origin of the view, use `view` instead.
+    /// The view must be invertible.
+    #[inline(always)]
+    pub fn in_screen_space(&self) -> Context {
+        let origin = transform_pos(multiply(invert(self.view), self.transform), [0.0, 0.0]);
+        Context {
+            transform: multiply(self.view, translate(origin)),
+            ..*self
         }
     }
+
+    /// Translates x and y in view coordinates after the current transform.
+    ///
+    /// See `prepend_view_transform` for the multiplication order.
+    #[inline(always)]
+    pub fn trans_view(&self, x: Scalar, y: Scalar) -> Context {
+        self.prepend_view_transform(translate([x, y]))
+    }
+
+    /// Rotates radians around the view origin after the current transform.
+    ///
+    /// See `prepend_view_transform` for the multiplication order.
+    #[inline(always)]
+    pub fn rot_rad_view(&self, angle: Scalar) -> Context {
+        self.prepend_view_transform(rotate_radians(angle))
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::Context;
     use std::num::Float;
+    use vecmath::{ Scalar, EPSILON };
+
+    #[test]
+    fn test_rot_about() {
+        use math::transform_pos;
+        use RelativeTransform;
+
+        // The center of a 20x10 image at [30, 40].
+        let center = [40.0, 45.0];
+        let c = Context::abs(100.0, 100.0);
+        let d = c.rot_deg_about(center, 90.0);
+        let (a, b) = (transform_pos(c.transform, center), transform_pos(d.transform, center));
+        assert!((a[0] - b[0]).abs() < EPSILON && (a[1] - b[1]).abs() < EPSILON);
+        // The top left corner swings around the center.
+        let p = transform_pos(d.transform, [30.0, 40.0]);
+        let q = transform_pos(c.transform, [45.0, 35.0]);
+        assert!((p[0] - q[0]).abs() < EPSILON && (p[1] - q[1]).abs() < EPSILON);
+    }
 
     #[test]
     fn test_context() {
@@ -110,6 +518,332 @@ mod test {
         assert!((transform[0][1] + 1.0).abs() < 0.00001);
     }
 
+    #[test]
+    fn test_rot_rad() {
+        use RelativeTransform;
+        use radians::Radians;
+
+        let c = Context::new();
+        let pi: Scalar = Radians::_180();
+        let a = c.rot_rad(pi / 2.0).transform;
+        let b = c.rot_deg(90.0).transform;
+        for i in 0..2 {
+            for j in 0..3 {
+                assert!((a[i][j] - b[i][j]).abs() < 0.00001);
+            }
+        }
+    }
+
+    #[test]
+    fn test_trans_abs() {
+        use { AbsoluteTransform, RelativeTransform, RelativeViewTransform };
+
+        let c = Context::new().trans(5.0, 7.0).store_view();
+        let d = c.trans(20.0, 40.0).rot_deg(30.0).zoom(3.0);
+        let m = d.trans_abs(100.0, 100.0).transform;
+        assert_eq!(m, c.trans_abs(100.0, 100.0).transform);
+        assert!((m[0][2] - (c.view[0][2] + 100.0)).abs() < 0.00001);
+        assert!((m[1][2] - (c.view[1][2] + 100.0)).abs() < 0.00001);
+        assert!((m[0][0] - 1.0).abs() < 0.00001);
+    }
+
+    #[test]
+    fn test_alpha() {
+        use RelativeTransform;
+        use Rectangle;
+        use test_backend::RecordingGraphics;
+
+        let c = Context::new().alpha(0.5);
+        let d = c.alpha(0.5).trans(10.0, 10.0).rot_deg(45.0).scale(2.0, 2.0);
+        assert_eq!(d.alpha, 0.25);
+        assert_eq!(d.apply_alpha([1.0; 4]), [1.0, 1.0, 1.0, 0.25]);
+
+        let mut g = RecordingGraphics::new();
+        Rectangle::new([1.0; 4]).draw([0.0, 0.0, 1.0, 1.0], &d, &mut g);
+        assert_eq!(g.tri_lists[0].color[3], 0.25);
+        Rectangle::new([1.0; 4]).draw([0.0, 0.0, 1.0, 1.0], &d.alpha(0.0), &mut g);
+        assert_eq!(g.tri_lists.len(), 1);
+    }
+
+    #[test]
+    fn test_inverse_transform_pos() {
+        use Transformed;
+
+        let c = Context::abs(100.0, 100.0).trans(10.0, 20.0).zoom(2.0);
+        let p = c.inverse_transform_pos([30.0, 40.0]);
+        assert!((p[0] - 10.0).abs() < 0.00001 && (p[1] - 10.0).abs() < 0.00001);
+    }
+
+    #[test]
+    fn test_scissor() {
+        let c = Context::new().scissor([0.5, 0.5, 10.2, 10.2]);
+        let r = c.draw_state.scissor.unwrap();
+        assert_eq!([r.x, r.y, r.w, r.h], [0, 0, 11, 11]);
+    }
+
+    #[test]
+    fn test_cull() {
+        use draw_state::block::{ CullFace, RasterMethod };
+
+        let c = Context::new().cull(CullFace::Back);
+        match c.draw_state.primitive.method {
+            RasterMethod::Fill(CullFace::Back) => {}
+            _ => panic!("Expected culling of back faces")
+        }
+        match c.no_cull().draw_state.primitive.method {
+            RasterMethod::Fill(CullFace::Nothing) => {}
+            _ => panic!("Expected no culling")
+        }
+    }
+
+    #[test]
+    fn test_blend() {
+        use blend;
+        use draw_state::block::{ CullFace, RasterMethod };
+
+        let c = Context::new();
+        assert!(c.alpha_blend().draw_state.blend == c.draw_state.blend);
+        assert!(c.additive().draw_state.blend == Some(blend::additive()));
+        assert!(c.multiply().draw_state.blend == Some(blend::multiply()));
+        assert!(c.screen().draw_state.blend == Some(blend::screen()));
+        assert!(c.replace().draw_state.blend.is_none());
+        assert!(c.additive().alpha_blend().draw_state.blend == Some(blend::alpha()));
+        for d in [c.additive(), c.multiply(), c.screen(), c.replace()].iter() {
+            match d.draw_state.primitive.method {
+                RasterMethod::Fill(CullFace::Nothing) => {}
+                _ => panic!("Expected fill")
+            }
+        }
+        // Transparent colors are only skipped with alpha blending.
+        assert!(c.skips([0.0; 4]));
+        assert!(!c.additive().skips([0.0; 4]));
+        assert!(!c.replace().skips([0.0; 4]));
+    }
+
+    #[test]
+    fn test_in_screen_space() {
+        use RelativeTransform;
+        use math::transform_pos;
+        use vecmath::{ get_scale, invert, multiply };
+
+        let c = Context::abs(100.0, 100.0).trans(10.0, 20.0).rot_deg(30.0).zoom(2.0);
+        let s = c.in_screen_space();
+        let local = multiply(invert(s.view), s.transform);
+        let scale = get_scale(local);
+        assert!((scale[0] - 1.0).abs() < 0.00001);
+        assert!((scale[1] - 1.0).abs() < 0.00001);
+        assert!(local[0][1].abs() < 0.00001 && local[1][0].abs() < 0.00001);
+        let origin = transform_pos(local, [0.0, 0.0]);
+        assert!((origin[0] - 10.0).abs() < 0.0001);
+        assert!((origin[1] - 20.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_compose() {
+        use RelativeTransform;
+        use draw_state::block::{ CullFace, RasterMethod };
+
+        let parent = Context::new().trans(10.0, 0.0).alpha(0.5);
+        let child = Context::new().trans(0.0, 5.0).cull(CullFace::Back);
+        let c = parent.compose(&child);
+        assert_eq!([c.transform[0][2], c.transform[1][2]], [10.0, 5.0]);
+        assert_eq!(c.view, parent.view);
+        assert_eq!(c.alpha, 0.5);
+        match c.draw_state.primitive.method {
+            RasterMethod::Fill(CullFace::Back) => {}
+            _ => panic!("Expected the draw state of the child")
+        }
+    }
+
+    #[test]
+    fn test_interpolated_towards() {
+        use RelativeTransform;
+
+        let a = Context::new();
+        let b = Context::new().trans(10.0, 0.0).alpha(0.5);
+        let c = a.interpolated_towards(&b, 0.5);
+        assert!((c.transform[0][2] - 5.0).abs() < 0.00001);
+        assert_eq!(c.alpha, 0.75);
+    }
+
+    #[test]
+    fn test_culls() {
+        use RelativeTransform;
+
+        let c = Context::abs(100.0, 100.0);
+        assert!(!c.culls([10.0, 10.0, 10.0, 10.0]));
+        assert!(!c.culls([-5.0, -5.0, 10.0, 10.0]));
+        assert!(c.culls([-20.0, 10.0, 10.0, 10.0]));
+        assert!(c.culls([10.0, 101.0, 10.0, 10.0]));
+        assert!(!c.trans(-20.0, 0.0).culls([15.0, 10.0, 10.0, 10.0]));
+    }
+
+    #[test]
+    fn test_trans_pos() {
+        use RelativeTransform;
+
+        let c = Context::new();
+        assert_eq!(c.trans_pos([20.0, 40.0]).transform, c.trans(20.0, 40.0).transform);
+        assert_eq!(c.scale_pos([2.0, 3.0]).transform, c.scale(2.0, 3.0).transform);
+    }
+
+    #[test]
+    fn test_transformed() {
+        use { RelativeTransform, Transformed };
+
+        let c = Context::abs(800.0, 600.0);
+        let d = c.trans(10.0, 20.0).rot_deg(30.0).scale(2.0, 3.0).shear([0.5, 0.0]);
+        let m = c.transform.trans(10.0, 20.0).rot_deg(30.0).scale(2.0, 3.0).shear([0.5, 0.0]);
+        for (a, b) in m.iter().zip(d.transform.iter()) {
+            for (x, y) in a.iter().zip(b.iter()) {
+                assert!((x - y).abs() < EPSILON);
+            }
+        }
+        assert_eq!(c.rot_rad(0.5).transform, c.transform.rot_rad(0.5));
+    }
+
+    #[test]
+    fn test_matrix_relative_transform() {
+        use RelativeTransform;
+
+        let c = Context::abs(800.0, 600.0);
+        let d = c.trans(10.0, 5.0).rot_deg(45.0).flip_h().orient(1.0, 2.0);
+        let m = c.transform.trans(10.0, 5.0).rot_deg(45.0).flip_h().orient(1.0, 2.0);
+        let e = Context { transform: m, ..c };
+        assert_eq!(e.transform, d.transform);
+        assert_eq!(e.view, d.view);
+        assert_eq!(c.transform.zoom(2.0).shear([1.0, 0.0]),
+            c.zoom(2.0).shear([1.0, 0.0]).transform);
+    }
+
+    #[test]
+    fn test_orient() {
+        use RelativeTransform;
+        use math::transform_pos;
+
+        let c = Context::new();
+        let p = transform_pos(c.orient(0.0, 5.0).transform, [1.0, 0.0]);
+        assert!(p[0].abs() < EPSILON);
+        assert!((p[1] - 1.0).abs() < EPSILON);
+
+        let p = transform_pos(c.trans(10.0, 20.0).orient(3.0, 4.0).transform, [5.0, 0.0]);
+        assert!((p[0] - 13.0).abs() < EPSILON);
+        assert!((p[1] - 24.0).abs() < EPSILON);
+
+        let d = c.trans(10.0, 20.0);
+        assert_eq!(d.orient(0.0, 0.0).transform, d.transform);
+    }
+
+    #[test]
+    fn test_trans_view() {
+        use RelativeTransform;
+        use math::{ transform_pos, transform_vec };
+
+        let c = Context::abs(800.0, 600.0);
+        let d = c.trans(100.0, 50.0).rot_deg(90.0).zoom(2.0);
+        let e = d.trans_view(10.0, 0.0);
+        let a = transform_pos(d.transform, [3.0, 4.0]);
+        let b = transform_pos(e.transform, [3.0, 4.0]);
+        let offset = transform_vec(c.view, [10.0, 0.0]);
+        assert!((b[0] - a[0] - offset[0]).abs() < EPSILON);
+        assert!((b[1] - a[1] - offset[1]).abs() < EPSILON);
+
+        // Rotating around the view origin in view coordinates
+        // moves a point at (100, 0) to (0, 100) for a y-down view.
+        let e = c.trans(100.0, 0.0).rot_rad_view(0.5 * ::std::f64::consts::PI as Scalar);
+        let p = transform_pos(e.transform, [0.0, 0.0]);
+        let q = transform_pos(c.view, [0.0, 100.0]);
+        assert!((p[0] - q[0]).abs() < EPSILON);
+        assert!((p[1] - q[1]).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_store_view() {
+        use { RelativeTransform, RelativeViewTransform };
+        use draw_state::block::{ CullFace, RasterMethod };
+
+        let c = Context::abs(800.0, 600.0).cull(CullFace::Back);
+        let camera = c.trans(100.0, 50.0).zoom(2.0);
+        let ui = camera.rot_deg(30.0).view();
+        assert_eq!(ui.transform, c.transform);
+        assert_eq!(ui.view, c.view);
+        match ui.draw_state.primitive.method {
+            RasterMethod::Fill(CullFace::Back) => {}
+            _ => panic!("Expected the draw state to be kept")
+        }
+
+        let stored = camera.store_view();
+        let d = stored.trans(10.0, 10.0).rot_deg(45.0).view();
+        assert_eq!(d.transform, camera.transform);
+        assert_eq!(d.view, camera.transform);
+        match d.draw_state.primitive.method {
+            RasterMethod::Fill(CullFace::Back) => {}
+            _ => panic!("Expected the draw state to be kept")
+        }
+    }
+
+    #[test]
+    fn test_clip_rect() {
+        let c = Context::abs(800.0, 600.0).clip_rect([0, 0, 400, 300]).clip_rect([200, 100, 400, 400]);
+        let r = c.draw_state.scissor.unwrap();
+        assert_eq!([r.x, r.y, r.w, r.h], [200, 100, 200, 200]);
+        assert!(c.no_clip().draw_state.scissor.is_none());
+    }
+
+    #[test]
+    fn test_from_mat4() {
+        // An orthographic projection with left = 0, right = w,
+        // top = 0, bottom = h, near = -1 and far = 1.
+        let (w, h) = (800.0, 600.0);
+        let ortho = [
+            [2.0 / w, 0.0, 0.0, 0.0],
+            [0.0, -2.0 / h, 0.0, 0.0],
+            [0.0, 0.0, -1.0, 0.0],
+            [-1.0, 1.0, 0.0, 1.0]
+        ];
+        let c = Context::from_mat4(ortho);
+        let d = Context::abs(w as Scalar, h as Scalar);
+        for (a, b) in c.transform.iter().zip(d.transform.iter()) {
+            for (x, y) in a.iter().zip(b.iter()) {
+                assert!((x - y).abs() < EPSILON);
+            }
+        }
+        assert_eq!(c.view, c.transform);
+    }
+
+    #[test]
+    fn test_draw_axes() {
+        use RelativeTransform;
+        use color::{ LIME, RED };
+        use test_backend::RecordingGraphics;
+
+        let c = Context::abs(100.0, 100.0).trans(50.0, 50.0).rot_deg(90.0);
+        let mut g = RecordingGraphics::new();
+        c.draw_axes(10.0, &mut g);
+        assert_eq!(g.tri_lists.len(), 2);
+        assert_eq!(g.tri_lists[0].color, RED);
+        assert_eq!(g.tri_lists[1].color, LIME);
+        // The rotated x axis points down in view coordinates.
+        let v = &g.tri_lists[0].vertices;
+        let min_y = v.chunks(2).fold(::std::f32::MAX, |y, p| y.min(p[1]));
+        assert!((min_y - -0.2).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_from_viewport() {
+        let c = Context::from_viewport([0, 0, 800, 600]);
+        let d = Context::abs(800.0, 600.0);
+        assert_eq!(c.view, d.view);
+        assert_eq!(c.transform, d.transform);
+
+        // The upper left corner of a sub-viewport maps to
+        // the upper left corner of normalized coordinates.
+        let c = Context::from_viewport([400, 300, 400, 300]);
+        let m = c.transform;
+        assert!((m[0][0] * 400.0 + m[0][2] + 1.0).abs() < 0.00001);
+        assert!((m[1][1] * 300.0 + m[1][2] - 1.0).abs() < 0.00001);
+    }
+
     #[test]
     fn test_scale() {
         use RelativeTransform;
@@ -1,12 +1,13 @@
 //! Least square deforming of a 2D grid.
 
+use std::vec::Vec;
 use {
     Line,
     Graphics,
     Context,
 };
 use std::num::Float;
-use triangulation::{tx, ty};
+use triangulation::{ ensure_ccw_uv, tx, ty };
 use vecmath::{ Scalar, Vec2d };
 use internal;
 
@@ -194,9 +195,8 @@ impl DeformGrid {
         where B: Graphics
     {
         let mat = c.transform;
-        let color = [1.0; 4];
-        let a = color[3];
-        if a == 0.0 { return; }
+        let color = c.apply_alpha([1.0; 4]);
+        if c.skips(color) { return; }
         let buf_len = 360;
         let mut vertices: [f32; 720] = [0.0; 720];
         let mut uvs: [f32; 720] = [0.0; 720];
@@ -205,11 +205,12 @@ impl DeformGrid {
         let uv_align = 2;
         for &ind in self.indices.iter() {
             if offset >= buf_len {
+                ensure_ccw_uv(&mut vertices, &mut uvs);
                 back_end.tri_list_uv(
                     &c.draw_state,
                     &color,
                     texture,
-                    |f| f(&vertices, &uvs)
+                    |f| f.emit(&vertices, &uvs)
                 );
                 offset = 0;
             }
@@ -224,11 +225,15 @@ impl DeformGrid {
             offset += 1;
         }
         if offset > 0 {
+            ensure_ccw_uv(
+                &mut vertices[..offset * vertex_align],
+                &mut uvs[..offset * uv_align]
+            );
             back_end.tri_list_uv(
                 &c.draw_state,
                 &color,
                 texture,
-                |f| f(
+                |f| f.emit(
                     &vertices[..offset * vertex_align],
                     &uvs[..offset * uv_align]
                 )
@@ -0,0 +1,90 @@
+//! Draw state settings and helpers.
+//!
+//! Reexports the draw state library.
+
+use std::num::Float;
+use internal::Scalar;
+
+pub use draw_state_lib::*;
+use draw_state_lib::target::Rect;
+
+/// Sets the scissor rectangle from [x, y, w, h] in pixels.
+///
+/// Rounds the origin down and the far edge up,
+/// so content inside the rectangle is never clipped.
+/// Negative sizes are clamped to zero.
+pub fn set_scissor_rect(ds: &mut DrawState, rect: [Scalar; 4]) {
+    let clamp = |v: Scalar| v.max(0.0).min(::std::u16::MAX as Scalar) as u16;
+    let (w, h) = (rect[2].max(0.0), rect[3].max(0.0));
+    let (x1, y1) = (rect[0].floor(), rect[1].floor());
+    let (x2, y2) = ((rect[0] + w).ceil(), (rect[1] + h).ceil());
+    let (x, y) = (clamp(x1), clamp(y1));
+    ds.scissor = Some(Rect {
+        x: x,
+        y: y,
+        w: clamp(x2) - x,
+        h: clamp(y2) - y,
+    });
+}
+
+/// Returns the draw state with the scissor rectangle from [x, y, w, h] in pixels.
+///
+/// See `set_scissor_rect`.
+pub fn scissor_rect(mut ds: DrawState, rect: [Scalar; 4]) -> DrawState {
+    set_scissor_rect(&mut ds, rect);
+    ds
+}
+
+/// Intersects the scissor rectangle with [x, y, w, h] in pixels.
+///
+/// Sets the scissor rectangle if there is none.
+/// Rectangles that do not overlap give an empty scissor rectangle.
+pub fn intersect_scissor_rect(ds: &mut DrawState, rect: [u32; 4]) {
+    let clamp = |v: u32| if v > ::std::u16::MAX as u32 { ::std::u16::MAX } else { v as u16 };
+    let (mut x1, mut y1) = (clamp(rect[0]), clamp(rect[1]));
+    let mut x2 = clamp(rect[0].saturating_add(rect[2]));
+    let mut y2 = clamp(rect[1].saturating_add(rect[3]));
+    if let Some(r) = ds.scissor {
+        x1 = ::std::cmp::max(x1, r.x);
+        y1 = ::std::cmp::max(y1, r.y);
+        x2 = ::std::cmp::min(x2, r.x.saturating_add(r.w));
+        y2 = ::std::cmp::min(y2, r.y.saturating_add(r.h));
+    }
+    ds.scissor = Some(Rect {
+        x: x1,
+        y: y1,
+        w: x2.saturating_sub(x1),
+        h: y2.saturating_sub(y1),
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ intersect_scissor_rect, scissor_rect, DrawState };
+    use vecmath::Scalar;
+
+    fn scissor(rect: [Scalar; 4]) -> [u16; 4] {
+        let r = scissor_rect(DrawState::new(), rect).scissor.unwrap();
+        [r.x, r.y, r.w, r.h]
+    }
+
+    #[test]
+    fn test_scissor_rect() {
+        assert_eq!(scissor([0.5, 0.5, 10.2, 10.2]), [0, 0, 11, 11]);
+        assert_eq!(scissor([2.0, 3.0, 4.0, 5.0]), [2, 3, 4, 5]);
+        assert_eq!(scissor([2.0, 3.0, -4.0, 5.0]), [2, 3, 0, 5]);
+        assert_eq!(scissor([-2.5, 0.0, 4.0, 1.0]), [0, 0, 2, 1]);
+    }
+
+    #[test]
+    fn test_intersect_scissor_rect() {
+        let mut ds = DrawState::new();
+        intersect_scissor_rect(&mut ds, [10, 10, 100, 50]);
+        intersect_scissor_rect(&mut ds, [50, 0, 100, 30]);
+        let r = ds.scissor.unwrap();
+        assert_eq!([r.x, r.y, r.w, r.h], [50, 10, 60, 20]);
+        intersect_scissor_rect(&mut ds, [0, 0, 5, 5]);
+        let r = ds.scissor.unwrap();
+        assert_eq!([r.w, r.h], [0, 0]);
+    }
+}
@@ -3,16 +3,23 @@
 pub use rectangle::centered;
 pub use rectangle::centered_square as circle;
 
+use color;
 use internal;
 use triangulation;
+use triangulation::Resolution;
+use vecmath::margin_rectangle;
+use DrawState;
 use Graphics;
 use Context;
 use Color;
 
 /// Ellipse border
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Border {
     /// The border color
+    #[cfg_attr(feature = "serde", serde(serialize_with = "::serde_color::serialize",
+        deserialize_with = "::serde_color::deserialize"))]
     pub color: internal::Color,
     /// The border radius
     pub radius: internal::Radius,
@@ -23,12 +30,23 @@ pub struct Border {
 pub struct MaybeBorder(pub Option<Border>);
 
 /// An ellipse with filled color
-#[derive(Copy, Clone)]
+///
+/// Create it with `new`, `border` or `Default` and the builder methods.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Ellipse {
     /// The ellipse color
+    #[cfg_attr(feature = "serde", serde(serialize_with = "::serde_color::serialize",
+        deserialize_with = "::serde_color::deserialize"))]
     pub color: internal::Color,
     /// The ellipse border
     pub border: Option<Border>,
+    /// The width of the feathered edge, if anti-aliased
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub antialias: Option<internal::Width>,
+    /// The number of segments in the full ellipse
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub resolution: Resolution,
 }
 
 impl Ellipse {
@@ -36,7 +54,9 @@ impl Ellipse {
     pub fn new(color: internal::Color) -> Ellipse {
         Ellipse {
             color: color,
-            border: None
+            border: None,
+            antialias: None,
+            resolution: Resolution::Fixed(128),
         }
     }
 
@@ -50,10 +70,55 @@ impl Ellipse {
             border: Some(Border {
                     color: color,
                     radius: radius,
-                })
+                }),
+            antialias: None,
+            resolution: Resolution::Fixed(128),
         }
     }
 
+    /// Adds a feathered edge of some width, in device pixels,
+    /// outside the ellipse, or outside the border if there is one.
+    ///
+    /// See `triangulation::with_polygon_feather_tri_list_c`.
+    pub fn antialias(mut self, width: internal::Width) -> Ellipse {
+        self.antialias = Some(width);
+        self
+    }
+
+    /// Sets the number of segments in the full ellipse.
+    ///
+    /// `Resolution::Adaptive` uses the larger radius of the ellipse
+    /// on the screen, so small ellipses use fewer triangles
+    /// and large ones do not show facets.
+    pub fn resolution(mut self, resolution: Resolution) -> Ellipse {
+        self.resolution = resolution;
+        self
+    }
+
+    /// Returns the number of segments used to draw the ellipse.
+    pub fn segments(&self, rectangle: internal::Rectangle, c: &Context) -> usize {
+        use std::num::Float;
+
+        let radius = rectangle[2].abs().max(rectangle[3].abs()) / 2.0;
+        self.resolution.segments(radius / c.local_width(1.0))
+    }
+
+    /// Returns true if a point in local coordinates is inside the ellipse,
+    /// or on its edge.
+    ///
+    /// The border is included. See `Context::inverse_transform_pos`.
+    pub fn contains(&self, rectangle: internal::Rectangle, point: internal::Vec2d) -> bool {
+        let rect = match self.border {
+            Some(Border { radius, .. }) => margin_rectangle(rectangle, -radius),
+            None => rectangle
+        };
+        let (rx, ry) = (0.5 * rect[2], 0.5 * rect[3]);
+        if rx == 0.0 || ry == 0.0 { return false; }
+        let dx = (point[0] - rect[0] - rx) / rx;
+        let dy = (point[1] - rect[1] - ry) / ry;
+        dx * dx + dy * dy <= 1.0
+    }
+
     /// Draws the ellipse.
     pub fn draw<B>(
         &self,
@@ -63,31 +128,84 @@ impl Ellipse {
     )
         where B: Graphics
     {
-        back_end.tri_list(
-            &c.draw_state,
-            &self.color,
-            |f|
-        triangulation::with_ellipse_tri_list(
-            128,
-            c.transform,
-            rectangle,
-            |vertices| f(vertices)
-        ));
-
-        if let Some(Border { color, radius: border_radius }) = self.border {
+        let n = self.segments(rectangle, c);
+        let fill = c.apply_alpha(self.color);
+        if !c.skips(fill) {
             back_end.tri_list(
                 &c.draw_state,
-                &color,
+                &fill,
                 |f|
-            triangulation::with_ellipse_border_tri_list(
-                128,
+            triangulation::with_ellipse_tri_list(
+                n,
                 c.transform,
                 rectangle,
-                border_radius,
-                |vertices| f(vertices)
+                |vertices| f.emit(vertices)
+            ));
+        }
+
+        if let Some(Border { color, radius: border_radius }) = self.border {
+            let color = c.apply_alpha(color);
+            if !c.skips(color) {
+                back_end.tri_list(
+                    &c.draw_state,
+                    &color,
+                    |f|
+                triangulation::with_ellipse_border_tri_list(
+                    n,
+                    c.transform,
+                    rectangle,
+                    border_radius,
+                    |vertices| f.emit(vertices)
+                ));
+            }
+        }
+
+        if let Some(width) = self.antialias {
+            if !back_end.has_vertex_colors() { return; }
+            let width = c.local_width(width);
+            let (edge, color) = match self.border {
+                Some(Border { color, radius }) =>
+                    (margin_rectangle(rectangle, -radius), color),
+                None => (rectangle, self.color)
+            };
+            let color = c.apply_alpha(color);
+            if c.skips(color) { return; }
+            back_end.tri_list_c(
+                &c.draw_state,
+                |f|
+            triangulation::with_ellipse_feather_tri_list_c(
+                n,
+                c.transform,
+                edge,
+                width,
+                color,
+                |vertices, colors| f.emit(vertices, colors)
             ));
         }
     }
+
+    /// Draws the ellipse with a draw state and transform,
+    /// for callers without a context.
+    ///
+    /// See `Context::from_state`.
+    pub fn draw_with_state<B>(
+        &self,
+        rectangle: internal::Rectangle,
+        draw_state: &DrawState,
+        transform: internal::Matrix2d,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        self.draw(rectangle, &Context::from_state(draw_state, transform), back_end);
+    }
+}
+
+impl Default for Ellipse {
+    /// A white ellipse without border.
+    fn default() -> Ellipse {
+        Ellipse::new(color::WHITE)
+    }
 }
 
 quack! {
@@ -115,4 +233,53 @@ mod test {
             .set(Color([0.0; 4]))
             .set(Border { color: [1.0; 4], radius: 3.0 });
     }
+
+    #[test]
+    fn test_default() {
+        use triangulation::Resolution;
+
+        let e = Ellipse::default();
+        assert_eq!(e.color, [1.0; 4]);
+        assert_eq!(e.border, None);
+        assert_eq!(e.antialias, None);
+        assert_eq!(e.resolution, Resolution::Fixed(128));
+        // A border alone has a transparent fill.
+        let b = Ellipse::border([1.0; 4], 2.0);
+        assert_eq!(b.color, [0.0; 4]);
+        assert_eq!(b.border, Some(Border { color: [1.0; 4], radius: 2.0 }));
+    }
+
+    #[test]
+    fn test_contains() {
+        let circle = Ellipse::new([1.0; 4]);
+        let rect = [0.0, 0.0, 20.0, 20.0];
+        assert!(circle.contains(rect, [10.0, 10.0]));
+        assert!(circle.contains(rect, [20.0, 10.0]));
+        assert!(circle.contains(rect, [10.0, 0.0]));
+        assert!(!circle.contains(rect, [1.0, 1.0]));
+        assert!(!circle.contains(rect, [20.5, 10.0]));
+        assert!(Ellipse::border([1.0; 4], 1.0).contains(rect, [20.5, 10.0]));
+    }
+
+    #[test]
+    fn test_adaptive_resolution() {
+        use test_backend::RecordingGraphics;
+        use triangulation::{ Resolution, MAX_SEGMENTS, MIN_SEGMENTS };
+        use { Context, Transformed };
+
+        let c = Context::abs(100.0, 100.0);
+        let ellipse = Ellipse::new([1.0; 4]).resolution(Resolution::Adaptive(0.25));
+        let rect = [0.0, 0.0, 20.0, 20.0];
+        let segments = |c: &Context| ellipse.segments(rect, c);
+        assert!(segments(&c) < segments(&c.zoom(2.0)));
+        assert!(segments(&c.zoom(2.0)) < segments(&c.zoom(8.0)));
+        assert_eq!(segments(&c.zoom(0.001)), MIN_SEGMENTS);
+        assert_eq!(segments(&c.zoom(10000.0)), MAX_SEGMENTS);
+        assert_eq!(Ellipse::new([1.0; 4]).segments(rect, &c.zoom(8.0)), 128);
+
+        let (mut small, mut large) = (RecordingGraphics::new(), RecordingGraphics::new());
+        ellipse.draw(rect, &c, &mut small);
+        ellipse.draw(rect, &c.zoom(8.0), &mut large);
+        assert!(small.vertex_count() < large.vertex_count());
+    }
 }
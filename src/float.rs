@@ -0,0 +1,93 @@
+//! Float functions for builds without `std`
+//!
+//! This crate calls float functions through `std::num::Float`.
+//! Without the `std` feature, that path refers to the trait below,
+//! which provides the functions the crate uses, computed by `libm`.
+
+use libm;
+
+/// The float functions used by this crate.
+pub trait Float: Copy {
+    /// Returns positive infinity.
+    fn infinity() -> Self;
+    /// Returns negative infinity.
+    fn neg_infinity() -> Self;
+    /// Returns not a number.
+    fn nan() -> Self;
+    /// Returns the largest finite value.
+    fn max_value() -> Self;
+    /// Returns `true` if the value is not a number.
+    fn is_nan(self) -> bool;
+    /// Returns `true` if the value is neither infinite nor not a number.
+    fn is_finite(self) -> bool;
+    /// Returns the absolute value.
+    fn abs(self) -> Self;
+    /// Returns `1.0` with the sign of the value, or not a number.
+    fn signum(self) -> Self;
+    /// Returns the smaller value, ignoring not a number.
+    fn min(self, other: Self) -> Self;
+    /// Returns the larger value, ignoring not a number.
+    fn max(self, other: Self) -> Self;
+    /// Rounds down.
+    fn floor(self) -> Self;
+    /// Rounds up.
+    fn ceil(self) -> Self;
+    /// Rounds to the nearest integer, and half-way cases away from zero.
+    fn round(self) -> Self;
+    /// Returns the square root.
+    fn sqrt(self) -> Self;
+    /// Raises to an integer power.
+    fn powi(self, n: i32) -> Self;
+    /// Raises to a float power.
+    fn powf(self, n: Self) -> Self;
+    /// Returns the sine, in radians.
+    fn sin(self) -> Self;
+    /// Returns the cosine, in radians.
+    fn cos(self) -> Self;
+    /// Returns the tangent, in radians.
+    fn tan(self) -> Self;
+    /// Returns the arc cosine, in radians.
+    fn acos(self) -> Self;
+    /// Returns the angle of the point `(other, self)`, in radians.
+    fn atan2(self, other: Self) -> Self;
+}
+
+macro_rules! impl_float {
+    (
+        $t:ident,
+        $fabs:ident, $copysign:ident, $fmin:ident, $fmax:ident,
+        $floor:ident, $ceil:ident, $round:ident, $sqrt:ident, $pow:ident,
+        $sin:ident, $cos:ident, $tan:ident, $acos:ident, $atan2:ident
+    ) => {
+        impl Float for $t {
+            fn infinity() -> $t { ::core::$t::INFINITY }
+            fn neg_infinity() -> $t { ::core::$t::NEG_INFINITY }
+            fn nan() -> $t { ::core::$t::NAN }
+            fn max_value() -> $t { ::core::$t::MAX }
+            fn is_nan(self) -> bool { self != self }
+            fn is_finite(self) -> bool { !self.is_nan() && self.abs() != ::core::$t::INFINITY }
+            fn abs(self) -> $t { libm::$fabs(self) }
+            fn signum(self) -> $t {
+                if self.is_nan() { self } else { libm::$copysign(1.0, self) }
+            }
+            fn min(self, other: $t) -> $t { libm::$fmin(self, other) }
+            fn max(self, other: $t) -> $t { libm::$fmax(self, other) }
+            fn floor(self) -> $t { libm::$floor(self) }
+            fn ceil(self) -> $t { libm::$ceil(self) }
+            fn round(self) -> $t { libm::$round(self) }
+            fn sqrt(self) -> $t { libm::$sqrt(self) }
+            fn powi(self, n: i32) -> $t { libm::$pow(self, n as $t) }
+            fn powf(self, n: $t) -> $t { libm::$pow(self, n) }
+            fn sin(self) -> $t { libm::$sin(self) }
+            fn cos(self) -> $t { libm::$cos(self) }
+            fn tan(self) -> $t { libm::$tan(self) }
+            fn acos(self) -> $t { libm::$acos(self) }
+            fn atan2(self, other: $t) -> $t { libm::$atan2(self, other) }
+        }
+    }
+}
+
+impl_float!(f64, fabs, copysign, fmin, fmax, floor, ceil, round, sqrt, pow,
+    sin, cos, tan, acos, atan2);
+impl_float!(f32, fabsf, copysignf, fminf, fmaxf, floorf, ceilf, roundf, sqrtf, powf,
+    sinf, cosf, tanf, acosf, atan2f);
@@ -1,5 +1,81 @@
+use std::vec::Vec;
 use ImageSize;
 use draw_state::DrawState;
+use triangulation;
+
+/// Receives the vertices of a draw call, two components per vertex.
+///
+/// The back-end creates it from its own buffer and reads the buffer
+/// after the draw call returns, so passing vertices is a plain copy
+/// without dynamic dispatch.
+pub struct Emitter<'a> {
+    vertices: &'a mut Vec<f32>,
+}
+
+impl<'a> Emitter<'a> {
+    /// Creates an emitter that appends to a buffer of the back-end.
+    pub fn new(vertices: &'a mut Vec<f32>) -> Emitter<'a> {
+        Emitter { vertices: vertices }
+    }
+
+    /// Appends a list of triangles.
+    #[inline(always)]
+    pub fn emit(&mut self, vertices: &[f32]) {
+        self.vertices.extend(vertices.iter().map(|x| *x));
+    }
+
+    /// Appends a strip of triangles, converted to a list.
+    ///
+    /// See `Graphics::tri_strip` for the layout of the strip.
+    #[inline(always)]
+    pub fn emit_strip(&mut self, strip: &[f32]) {
+        let buffer = &mut *self.vertices;
+        triangulation::tri_strip_to_tri_list(strip, |vertices| {
+            buffer.extend(vertices.iter().map(|x| *x))
+        });
+    }
+}
+
+/// Receives the vertices of a draw call with an attribute per vertex,
+/// texture coordinates for `tri_list_uv` and colors for `tri_list_c`.
+///
+/// Works like `Emitter`, with a second buffer for the attributes.
+pub struct AttributeEmitter<'a> {
+    vertices: &'a mut Vec<f32>,
+    attributes: &'a mut Vec<f32>,
+}
+
+impl<'a> AttributeEmitter<'a> {
+    /// Creates an emitter that appends to buffers of the back-end.
+    pub fn new(
+        vertices: &'a mut Vec<f32>,
+        attributes: &'a mut Vec<f32>
+    ) -> AttributeEmitter<'a> {
+        AttributeEmitter { vertices: vertices, attributes: attributes }
+    }
+
+    /// Appends a list of triangles with their attributes.
+    #[inline(always)]
+    pub fn emit(&mut self, vertices: &[f32], attributes: &[f32]) {
+        self.vertices.extend(vertices.iter().map(|x| *x));
+        self.attributes.extend(attributes.iter().map(|x| *x));
+    }
+
+    /// Appends a strip of triangles with their attributes,
+    /// converted to a list.
+    #[inline(always)]
+    pub fn emit_strip(&mut self, strip: &[f32], attributes: &[f32]) {
+        let (buffer, buffer_attributes) = (&mut *self.vertices, &mut *self.attributes);
+        triangulation::tri_strip_uv_to_tri_list(strip, attributes, |vertices, attributes| {
+            buffer.extend(vertices.iter().map(|x| *x));
+            buffer_attributes.extend(attributes.iter().map(|x| *x));
+        });
+    }
+}
+
+/// Returned when a back-end does not support an operation.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Unsupported;
 
 /// Implemented by all graphics back-ends.
 pub trait Graphics {
@@ -9,19 +85,431 @@ pub trait Graphics {
     /// Clears background with a color.
     fn clear(&mut self, color: [f32; 4]);
 
+    /// Clears the stencil buffer with a value.
+    ///
+    /// See the `stencil` module for clipping with the stencil buffer.
+    /// The default implementation does nothing,
+    /// for back-ends without a stencil buffer.
+    fn clear_stencil(&mut self, _value: u8) {}
+
+    /// Records the pixel viewport as [x, y, w, h].
+    ///
+    /// The origin is in the upper left corner of the render target.
+    /// Use `Context::from_viewport` with the same rectangle to
+    /// draw in pixel coordinates of the render target.
+    ///
+    /// The default implementation ignores the viewport.
+    fn set_viewport(&mut self, _rect: [u32; 4]) {}
+
     /// Renders list of 2d triangles.
+    ///
+    /// The back-end calls `f` with an emitter of its buffer,
+    /// and draws the vertices in the buffer when `f` returns.
     fn tri_list<F>(&mut self, draw_state: &DrawState, color: &[f32; 4], f: F)
-        where F: FnMut(&mut FnMut(&[f32]));
+        where F: FnMut(&mut Emitter);
 
     /// Renders list of 2d triangles.
     ///
     /// A texture coordinate is assigned per vertex.
     /// The texture coordinates refers to the current texture.
+    ///
+    /// The color is a tint, multiplied with the texture colors,
+    /// so white draws the texture as it is.
+    /// Back-ends that can not multiply should at least
+    /// apply the alpha of the color, and ignore the rest.
     fn tri_list_uv<F>(
         &mut self,
         draw_state: &DrawState,
         color: &[f32; 4],
         texture: &<Self as Graphics>::Texture,
         f: F
-    ) where F: FnMut(&mut FnMut(&[f32], &[f32]));
+    ) where F: FnMut(&mut AttributeEmitter);
+
+    /// Renders list of 2d triangles with a color per vertex.
+    ///
+    /// The colors have four components per vertex, in the same order
+    /// as the vertices.
+    ///
+    /// The default implementation draws each triangle with the
+    /// average color of its vertices, using `tri_list` once for every
+    /// run of triangles with the same color.
+    /// Back-ends that support vertex colors should override it
+    /// for smooth color transitions, and `has_vertex_colors`.
+    fn tri_list_c<F>(&mut self, draw_state: &DrawState, mut f: F)
+        where F: FnMut(&mut AttributeEmitter)
+    {
+        let mut vertices = Vec::new();
+        let mut colors = Vec::new();
+        f(&mut AttributeEmitter::new(&mut vertices, &mut colors));
+        let n = ::std::cmp::min(vertices.len() / 6, colors.len() / 12);
+        let mut start = 0;
+        while start < n {
+            let color = triangle_color(&colors[start * 12..]);
+            let mut end = start + 1;
+            while end < n && triangle_color(&colors[end * 12..]) == color {
+                end += 1;
+            }
+            self.tri_list(draw_state, &color, |e| e.emit(&vertices[start * 6..end * 6]));
+            start = end;
+        }
+    }
+
+    /// Returns `true` if `tri_list_c` interpolates colors between vertices.
+    ///
+    /// Effects that only work with smooth colors, like feathered edges,
+    /// are skipped when this is `false`, which is the default.
+    fn has_vertex_colors(&self) -> bool { false }
+
+    /// Renders strip of 2d triangles.
+    ///
+    /// Each slice passed to `Emitter::emit_strip` is a separate strip,
+    /// where every vertex after the first two forms a triangle
+    /// with the two previous vertices.
+    /// Every second triangle is flipped to keep a consistent winding.
+    ///
+    /// The emitter converts the strips into triangle lists,
+    /// so the default implementation calls `tri_list`.
+    fn tri_strip<F>(&mut self, draw_state: &DrawState, color: &[f32; 4], f: F)
+        where F: FnMut(&mut Emitter)
+    {
+        self.tri_list(draw_state, color, f);
+    }
+
+    /// Renders strip of 2d triangles.
+    ///
+    /// A texture coordinate is assigned per vertex.
+    /// The texture coordinates refers to the current texture.
+    /// See `tri_strip` for the layout of the strips,
+    /// passed to `AttributeEmitter::emit_strip`.
+    fn tri_strip_uv<F>(
+        &mut self,
+        draw_state: &DrawState,
+        color: &[f32; 4],
+        texture: &<Self as Graphics>::Texture,
+        f: F
+    ) where F: FnMut(&mut AttributeEmitter)
+    {
+        self.tri_list_uv(draw_state, color, texture, f);
+    }
+
+    /// Draws to a texture instead of the current render target.
+    ///
+    /// Binds the texture as render target, runs the closure,
+    /// and restores the previous render target.
+    /// The `Context` used inside the closure must be sized
+    /// to the texture, for example with `Context::abs(w, h)`
+    /// using the size of the texture.
+    ///
+    /// The default implementation does not call the closure
+    /// and returns an error, for back-ends without offscreen rendering.
+    fn draw_to_texture<F>(
+        &mut self,
+        _texture: &mut <Self as Graphics>::Texture,
+        _f: F
+    ) -> Result<(), Unsupported>
+        where F: FnOnce(&mut Self)
+    {
+        Err(Unsupported)
+    }
+
+    /// Reads back the pixels of the render target in a region [x, y, w, h].
+    ///
+    /// The origin is in the upper left corner of the render target.
+    /// Returns RGBA with 8 bits per component, `w * h * 4` bytes,
+    /// with rows ordered top-down, starting at the row `y`.
+    ///
+    /// The default implementation returns an empty `Vec`,
+    /// for back-ends that can not read back pixels.
+    fn read_pixels(&mut self, _rect: [u32; 4]) -> Vec<u8> {
+        Vec::new()
+    }
+}
+
+/// Returns the average color of the three vertices of a triangle.
+fn triangle_color(colors: &[f32]) -> [f32; 4] {
+    let mut color = [0.0; 4];
+    for i in 0..4 {
+        color[i] = (colors[i] + colors[4 + i] + colors[8 + i]) / 3.0;
+    }
+    color
+}
+
+/// Draws shapes with a color each, using the same draw state.
+///
+/// The triangles of every shape are streamed from `triangulate`
+/// and copied into chunks that fit inside L1 cache,
+/// without collecting the shapes.
+/// Back-ends with `has_vertex_colors` get one call to `tri_list_c`,
+/// others get one call to `tri_list` for every run of the same color.
+pub fn tri_list_colored<B, I, S, T>(
+    back_end: &mut B,
+    draw_state: &DrawState,
+    iter: I,
+    mut triangulate: T
+)
+    where
+        B: Graphics,
+        I: Iterator<Item = (S, [f32; 4])>,
+        T: FnMut(S, &mut FnMut(&[f32]))
+{
+    let mut vertices: [f32; 720] = [0.0; 720];
+    let mut iter = iter.peekable();
+    if back_end.has_vertex_colors() {
+        let mut colors: [f32; 1440] = [0.0; 1440];
+        back_end.tri_list_c(draw_state, |f| {
+            let mut n = 0;
+            for (shape, color) in iter.by_ref() {
+                triangulate(shape, &mut |chunk: &[f32]| {
+                    for chunk in chunk.chunks(720) {
+                        // Buffer is full.
+                        if n + chunk.len() > vertices.len() {
+                            f.emit(&vertices[..n], &colors[..2 * n]);
+                            n = 0;
+                        }
+                        for (i, &x) in chunk.iter().enumerate() {
+                            vertices[n + i] = x;
+                        }
+                        for c in colors[2 * n..2 * (n + chunk.len())].chunks_mut(4) {
+                            for (c, &x) in c.iter_mut().zip(color.iter()) { *c = x; }
+                        }
+                        n += chunk.len();
+                    }
+                });
+            }
+            if n > 0 { f.emit(&vertices[..n], &colors[..2 * n]); }
+        });
+        return;
+    }
+
+    loop {
+        let color = match iter.peek() {
+            None => return,
+            Some(&(_, color)) => color
+        };
+        back_end.tri_list(draw_state, &color, |f| {
+            let mut n = 0;
+            while iter.peek().map(|&(_, next)| next == color) == Some(true) {
+                let (shape, _) = iter.next().unwrap();
+                triangulate(shape, &mut |chunk: &[f32]| {
+                    for chunk in chunk.chunks(720) {
+                        // Buffer is full.
+                        if n + chunk.len() > vertices.len() {
+                            f.emit(&vertices[..n]);
+                            n = 0;
+                        }
+                        for (i, &x) in chunk.iter().enumerate() {
+                            vertices[n + i] = x;
+                        }
+                        n += chunk.len();
+                    }
+                });
+            }
+            if n > 0 { f.emit(&vertices[..n]); }
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::num::Float;
+    use super::{ AttributeEmitter, Emitter, Graphics };
+    use draw_state::DrawState;
+    use { Context, ImageSize, Polygon, Rectangle };
+
+    struct Texture;
+
+    impl ImageSize for Texture {
+        fn get_size(&self) -> (u32, u32) { (1, 1) }
+    }
+
+    /// Records clears and draws, and fills whole pixels
+    /// whose centers are covered by triangles.
+    /// Pixels are mixed by alpha when blending is enabled.
+    struct Mock {
+        width: u32,
+        height: u32,
+        pixels: Vec<[u8; 4]>,
+        clears: usize,
+        draws: usize,
+    }
+
+    impl Mock {
+        fn new(width: u32, height: u32) -> Mock {
+            Mock {
+                width: width,
+                height: height,
+                pixels: vec![[0; 4]; (width * height) as usize],
+                clears: 0,
+                draws: 0,
+            }
+        }
+    }
+
+    fn to_rgba8(color: &[f32; 4]) -> [u8; 4] {
+        let c = |x: f32| (x.max(0.0).min(1.0) * 255.0) as u8;
+        [c(color[0]), c(color[1]), c(color[2]), c(color[3])]
+    }
+
+    impl Graphics for Mock {
+        type Texture = Texture;
+
+        fn clear(&mut self, color: [f32; 4]) {
+            self.clears += 1;
+            for p in self.pixels.iter_mut() {
+                *p = to_rgba8(&color);
+            }
+        }
+
+        fn tri_list<F>(&mut self, draw_state: &DrawState, color: &[f32; 4], mut f: F)
+            where F: FnMut(&mut Emitter)
+        {
+            self.draws += 1;
+            let (w, h) = (self.width, self.height);
+            let rgba = to_rgba8(color);
+            let blend = draw_state.blend.is_some();
+            let alpha = color[3].max(0.0).min(1.0);
+            let pixels = &mut self.pixels;
+            let mut vertices = Vec::new();
+            f(&mut Emitter::new(&mut vertices));
+            for t in vertices.chunks(6) {
+                if t.len() < 6 { break; }
+                for y in 0..h {
+                    for x in 0..w {
+                        // Pixel center in normalized device coordinates.
+                        let px = (x as f32 + 0.5) / w as f32 * 2.0 - 1.0;
+                        let py = 1.0 - (y as f32 + 0.5) / h as f32 * 2.0;
+                        let edge = |i: usize, j: usize| {
+                            (t[j] - t[i]) * (py - t[i + 1])
+                            - (t[j + 1] - t[i + 1]) * (px - t[i])
+                        };
+                        let (a, b, c) = (edge(0, 2), edge(2, 4), edge(4, 0));
+                        if (a >= 0.0 && b >= 0.0 && c >= 0.0)
+                        || (a <= 0.0 && b <= 0.0 && c <= 0.0) {
+                            let dst = pixels[(y * w + x) as usize];
+                            let mix = |i: usize| (rgba[i] as f32 * alpha
+                                + dst[i] as f32 * (1.0 - alpha) + 0.5) as u8;
+                            pixels[(y * w + x) as usize] = if !blend { rgba } else {
+                                [mix(0), mix(1), mix(2), mix(3)]
+                            };
+                        }
+                    }
+                }
+            }
+        }
+
+        fn tri_list_uv<F>(
+            &mut self,
+            draw_state: &DrawState,
+            color: &[f32; 4],
+            _texture: &Texture,
+            mut f: F
+        ) where F: FnMut(&mut AttributeEmitter)
+        {
+            let (mut vertices, mut uvs) = (Vec::new(), Vec::new());
+            f(&mut AttributeEmitter::new(&mut vertices, &mut uvs));
+            self.tri_list(draw_state, color, |e| e.emit(&vertices));
+        }
+
+        fn read_pixels(&mut self, rect: [u32; 4]) -> Vec<u8> {
+            let mut res = Vec::with_capacity((rect[2] * rect[3] * 4) as usize);
+            for y in rect[1]..rect[1] + rect[3] {
+                for x in rect[0]..rect[0] + rect[2] {
+                    res.extend(self.pixels[(y * self.width + x) as usize].iter().cloned());
+                }
+            }
+            res
+        }
+    }
+
+    struct NoReadback;
+
+    impl Graphics for NoReadback {
+        type Texture = Texture;
+
+        fn clear(&mut self, _color: [f32; 4]) {}
+
+        fn tri_list<F>(&mut self, _draw_state: &DrawState, _color: &[f32; 4], _f: F)
+            where F: FnMut(&mut Emitter) {}
+
+        fn tri_list_uv<F>(
+            &mut self,
+            _draw_state: &DrawState,
+            _color: &[f32; 4],
+            _texture: &Texture,
+            _f: F
+        ) where F: FnMut(&mut AttributeEmitter) {}
+    }
+
+    #[test]
+    fn test_read_pixels() {
+        let c = Context::abs(8.0, 8.0);
+        let mut g = Mock::new(8, 8);
+        g.clear([1.0; 4]);
+        Rectangle::new([1.0, 0.0, 0.0, 1.0])
+            .draw([2.0, 0.0, 4.0, 4.0], &c, &mut g);
+        assert_eq!(g.clears, 1);
+        assert_eq!(g.draws, 1);
+        // Top-down: the first row is inside the rectangle.
+        assert_eq!(g.read_pixels([4, 0, 1, 1]), vec![255, 0, 0, 255]);
+        assert_eq!(g.read_pixels([4, 6, 1, 1]), vec![255; 4]);
+        let region = g.read_pixels([0, 0, 8, 2]);
+        assert_eq!(region.len(), 8 * 2 * 4);
+        assert_eq!(&region[..4], &[255, 255, 255, 255][..]);
+        assert_eq!(&region[12..16], &[255, 0, 0, 255][..]);
+
+        assert!(NoReadback.read_pixels([0, 0, 1, 1]).is_empty());
+    }
+
+    #[test]
+    fn test_clear_rect() {
+        use clear_rect;
+
+        let c = Context::abs(8.0, 8.0);
+        let mut g = Mock::new(8, 8);
+        g.clear([1.0; 4]);
+        Rectangle::new([1.0, 0.0, 0.0, 0.5]).draw([0.0, 0.0, 8.0, 8.0], &c, &mut g);
+        assert_eq!(&g.read_pixels([2, 2, 1, 1])[..3], &[255, 128, 128][..]);
+        clear_rect([0.0, 0.0, 1.0, 0.5], [0.0, 0.0, 4.0, 4.0], &c, &mut g);
+        // Nothing drawn before shows through the cleared rectangle.
+        assert_eq!(g.read_pixels([2, 2, 1, 1]), vec![0, 0, 255, 127]);
+        assert_eq!(&g.read_pixels([6, 6, 1, 1])[..3], &[255, 128, 128][..]);
+    }
+
+    #[test]
+    fn test_tri_list_c() {
+        let c = Context::abs(8.0, 8.0);
+        let mut g = Mock::new(8, 8);
+        // Two triangles of one color, followed by one of another.
+        let vertices = [-1.0, -1.0, 1.0, -1.0, -1.0, 1.0,
+                        1.0, -1.0, 1.0, 1.0, -1.0, 1.0,
+                        -1.0, -1.0, 1.0, -1.0, -1.0, 1.0];
+        let mut colors = vec![1.0; 24];
+        colors.extend(vec![0.5; 12].into_iter());
+        g.tri_list_c(&c.draw_state, |f| f.emit(&vertices, &colors));
+        assert_eq!(g.draws, 2);
+        // Back-ends without vertex colors only draw the fill of feathered shapes.
+        Polygon::new([1.0; 4]).antialias(1.0)
+            .draw(&[[2.0, 2.0], [6.0, 2.0], [2.0, 6.0]], &c, &mut g);
+        assert_eq!(g.draws, 3);
+    }
+
+    #[test]
+    fn test_tri_list_colored() {
+        let c = Context::abs(8.0, 8.0);
+        let mut g = Mock::new(8, 8);
+        let red = [1.0, 0.0, 0.0, 1.0];
+        let blue = [0.0, 0.0, 1.0, 1.0];
+        let rects = vec![
+            ([0.0, 0.0, 2.0, 2.0], red),
+            ([2.0, 0.0, 2.0, 2.0], red),
+            ([4.0, 0.0, 2.0, 2.0], blue),
+            ([6.0, 0.0, 2.0, 2.0], red),
+        ];
+        Rectangle::draw_iter(rects.into_iter(), &c, &mut g);
+        // One draw for every run of the same color.
+        assert_eq!(g.draws, 3);
+        assert_eq!(g.read_pixels([3, 0, 1, 1]), vec![255, 0, 0, 255]);
+        assert_eq!(g.read_pixels([5, 0, 1, 1]), vec![0, 0, 255, 255]);
+        assert_eq!(g.read_pixels([7, 1, 1, 1]), vec![255, 0, 0, 255]);
+    }
 }
@@ -1,23 +1,64 @@
 //! Draw image
 
+use std::string::{ String, ToString };
+use std::vec::Vec;
 use internal;
+use std::cmp::min;
+use std::num::Float;
+use radians::Radians;
 use triangulation;
 use Graphics;
 use Color;
 use Context;
+use DrawState;
 use ImageSize;
 use Rect;
 use SrcRect;
 
 /// An image
-#[derive(Copy, Clone)]
+///
+/// Create it with `new`, `colored` or `Default` and the builder methods.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Image {
-    /// The color
+    /// The tint, multiplied with the texture colors, or white if none
+    #[cfg_attr(feature = "serde", serde(serialize_with = "::serde_color::option::serialize",
+        deserialize_with = "::serde_color::option::deserialize"))]
     pub color: Option<internal::Color>,
     /// The rectangle to draw image inside
     pub rectangle: Option<internal::Rectangle>,
     /// The image source rectangle
     pub source_rectangle: Option<internal::SourceRectangle>,
+    /// The offset of the source rectangle in pixels
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub src_offset: internal::Vec2d,
+    /// Whether the offset source rectangle may extend beyond the texture
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub wrap: bool,
+    /// The insets of the source rectangle in pixels, [left, top, right, bottom]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub trim: [internal::Scalar; 4],
+    /// The rotation in radians about the center of the rectangle
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rotation: internal::Scalar,
+    /// Whether to flip the texture coordinates horizontally
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub flip_uv_h: bool,
+    /// Whether to flip the texture coordinates vertically
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub flip_uv_v: bool,
+}
+
+/// How an image is scaled into a destination rectangle.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Fit {
+    /// Stretches the image to the destination rectangle
+    Fill,
+    /// Scales the image to fit inside, centered with letterboxing
+    Contain,
+    /// Scales the image to cover the rectangle, cropping the center
+    Cover,
 }
 
 impl Image {
@@ -27,16 +68,210 @@ impl Image {
             color: None,
             source_rectangle: None,
             rectangle: None,
+            src_offset: [0.0; 2],
+            wrap: false,
+            trim: [0.0; 4],
+            rotation: 0.0,
+            flip_uv_h: false,
+            flip_uv_v: false,
         }
     }
 
     /// Creates a new colored image
+    ///
+    /// See `tint`.
     pub fn colored(color: internal::Color) -> Image {
         Image {
             color: Some(color),
-            source_rectangle: None,
-            rectangle: None
+            ..Image::new()
+        }
+    }
+
+    /// Tints the image by a color.
+    ///
+    /// The texture colors are multiplied by the tint component-wise,
+    /// so white leaves the texture unchanged and the alpha
+    /// fades the image. The tint is passed unchanged to the back-end,
+    /// apart from the opacity of the context.
+    pub fn tint(mut self, color: internal::Color) -> Image {
+        self.color = Some(color);
+        self
+    }
+
+    /// Offsets the source rectangle in pixels, for scrolling textures.
+    ///
+    /// Without wrapping, the offset is limited to keep the source rectangle
+    /// inside the texture. With wrapping, the texture coordinates
+    /// may go outside 0 to 1, which requires the back-end
+    /// to sample the texture with a repeating wrap mode.
+    pub fn src_offset(mut self, offset: internal::Vec2d, wrap: bool) -> Image {
+        self.src_offset = offset;
+        self.wrap = wrap;
+        self
+    }
+
+    /// Shrinks the source rectangle by insets in pixels,
+    /// [left, top, right, bottom], for stripping padding of packed sprites.
+    ///
+    /// The insets are rounded to whole pixels and applied to the
+    /// source rectangle, or to the whole texture if there is none,
+    /// when the image is drawn or fitted.
+    /// Nothing is drawn when the insets exceed the source rectangle.
+    pub fn trim(mut self, margin: [internal::Scalar; 4]) -> Image {
+        self.trim = margin;
+        self
+    }
+
+    /// Rotates the image in radians about the center of its rectangle.
+    ///
+    /// The rectangle is the destination rectangle,
+    /// or the size of the source rectangle if there is none.
+    /// The rotation is applied before the transform of the context,
+    /// and does not affect the texture coordinates, so flipped
+    /// source rectangles stay flipped.
+    /// To rotate about another pivot, see `RelativeTransform::rot_rad_about`.
+    pub fn rot_rad_center(mut self, angle: internal::Scalar) -> Image {
+        self.rotation = angle;
+        self
+    }
+
+    /// Rotates the image in degrees about the center of its rectangle.
+    ///
+    /// See `rot_rad_center`.
+    pub fn rot_deg_center(self, angle: internal::Scalar) -> Image {
+        let pi: internal::Scalar = Radians::_180();
+        self.rot_rad_center(angle * pi / 180.0)
+    }
+
+    /// Flips the texture coordinates horizontally,
+    /// without changing the positions.
+    pub fn flip_uv_h(mut self) -> Image {
+        self.flip_uv_h = !self.flip_uv_h;
+        self
+    }
+
+    /// Flips the texture coordinates vertically,
+    /// without changing the positions.
+    ///
+    /// This is the usual fix for render target textures
+    /// that appear upside down.
+    pub fn flip_uv_v(mut self) -> Image {
+        self.flip_uv_v = !self.flip_uv_v;
+        self
+    }
+
+    /// Mirrors texture coordinates of a quad within their bounds,
+    /// for the flipped axes.
+    fn flip_uvs(&self, uvs: &mut [f32]) {
+        for &(flip, i) in [(self.flip_uv_h, 0), (self.flip_uv_v, 1)].iter() {
+            if !flip { continue; }
+            let (min, max) = uvs.chunks(2).fold((::std::f32::MAX, ::std::f32::MIN),
+                |(min, max), uv| (min.min(uv[i]), max.max(uv[i])));
+            for uv in uvs.chunks_mut(2) {
+                uv[i] = min + max - uv[i];
+            }
+        }
+    }
+
+    /// Returns the source rectangle shrunk by the insets,
+    /// using `full` if there is no source rectangle.
+    fn trimmed_source_rectangle(
+        &self,
+        full: internal::SourceRectangle
+    ) -> internal::SourceRectangle {
+        let src = self.source_rectangle.unwrap_or(full);
+        let inset = |i: usize| self.trim[i].round() as i32;
+        let (left, top) = (inset(0), inset(1));
+        let w = src[2] - left - inset(2);
+        let h = src[3] - top - inset(3);
+        [src[0] + left, src[1] + top, if w < 0 { 0 } else { w }, if h < 0 { 0 } else { h }]
+    }
+
+    /// Fits the image into a destination rectangle.
+    ///
+    /// Uses the aspect ratio of the source rectangle,
+    /// or of the whole texture if there is no source rectangle.
+    /// `Fit::Contain` sets a smaller rectangle centered in the destination,
+    /// while `Fit::Cover` crops the center of the source rectangle.
+    pub fn fit<I: ImageSize>(
+        mut self,
+        texture: &I,
+        dest: internal::Rectangle,
+        mode: Fit
+    ) -> Image {
+        use internal::Scalar;
+
+        let src = self.trimmed_source_rectangle(texture.full_source_rect());
+        let (sw, sh) = (src[2] as Scalar, src[3] as Scalar);
+        let (dw, dh) = (dest[2], dest[3]);
+        if sw <= 0.0 || sh <= 0.0 || dw <= 0.0 || dh <= 0.0 {
+            self.rectangle = Some(dest);
+            return self;
+        }
+
+        match mode {
+            Fit::Fill => {
+                self.rectangle = Some(dest);
+            }
+            Fit::Contain => {
+                let scale = (dw / sw).min(dh / sh);
+                let (w, h) = (sw * scale, sh * scale);
+                self.rectangle = Some([
+                    dest[0] + 0.5 * (dw - w),
+                    dest[1] + 0.5 * (dh - h),
+                    w,
+                    h
+                ]);
+            }
+            Fit::Cover => {
+                let scale = (dw / sw).max(dh / sh);
+                let (w, h) = ((dw / scale) as i32, (dh / scale) as i32);
+                self.source_rectangle = Some([
+                    src[0] + (src[2] - w) / 2,
+                    src[1] + (src[3] - h) / 2,
+                    w,
+                    h
+                ]);
+                // The cropped source rectangle is already trimmed.
+                self.trim = [0.0; 4];
+                self.rectangle = Some(dest);
+            }
         }
+        self
+    }
+
+    /// Sets the source rectangle to a frame of a sprite sheet.
+    ///
+    /// See `SpriteSheet::frame`.
+    /// The source rectangle is left unchanged if the sheet has no frames.
+    pub fn src_frame<I: ImageSize>(
+        mut self,
+        texture: &I,
+        frame_index: usize,
+        frame_size: [u32; 2]
+    ) -> Image {
+        let sheet = SpriteSheet::new(texture, frame_size);
+        if let Some(rect) = sheet.frame(frame_index) {
+            self.source_rectangle = Some(rect);
+        }
+        self
+    }
+
+    /// Sets the source rectangle to a frame of a sprite sheet,
+    /// or returns `None` if the index is out of range.
+    ///
+    /// See `SpriteSheet::try_frame`.
+    pub fn try_src_frame<I: ImageSize>(
+        mut self,
+        texture: &I,
+        frame_index: usize,
+        frame_size: [u32; 2]
+    ) -> Option<Image> {
+        let sheet = SpriteSheet::new(texture, frame_size);
+        sheet.try_frame(frame_index).map(|rect| {
+            self.source_rectangle = Some(rect);
+            self
+        })
     }
 
     /// Draws the image.
@@ -47,30 +282,462 @@ impl Image {
         back_end: &mut B
     )
         where B: Graphics
+    {
+        self.draw_atlas(texture, texture.full_source_rect(), c, back_end);
+    }
+
+    /// Draws the image with a draw state and transform,
+    /// for callers without a context.
+    ///
+    /// See `Context::from_state`.
+    pub fn draw_with_state<B>(
+        &self,
+        texture: &<B as Graphics>::Texture,
+        draw_state: &DrawState,
+        transform: internal::Matrix2d,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        self.draw(texture, &Context::from_state(draw_state, transform), back_end);
+    }
+
+    /// Draws the image from a region of a texture.
+    ///
+    /// The source rectangle is relative to the region,
+    /// and defaults to the whole region.
+    pub fn draw_region<B, R>(
+        &self,
+        region: &R,
+        c: &Context,
+        back_end: &mut B
+    )
+        where
+            B: Graphics,
+            R: TextureRegion<Texture = <B as Graphics>::Texture>
+    {
+        self.draw_atlas(region.texture(), region.region(), c, back_end);
+    }
+
+    /// Draws many instances of the image with a single call to the back-end.
+    ///
+    /// Each instance has a destination rectangle and a transform,
+    /// which is applied after the transform of the context.
+    /// The rotation, source offset and flips of the image apply
+    /// to every instance, the same as in `draw`.
+    /// The optional source rectangles are per instance,
+    /// and instances without one are not drawn.
+    /// Without them the source rectangle of the image is used.
+    pub fn draw_many<B>(
+        &self,
+        instances: &[(internal::Rectangle, internal::Matrix2d)],
+        source_rectangles: Option<&[internal::SourceRectangle]>,
+        texture: &<B as Graphics>::Texture,
+        c: &Context,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        use vecmath::multiply;
+
+        let n = match source_rectangles {
+            Some(source_rectangles) => min(source_rectangles.len(), instances.len()),
+            None => instances.len()
+        };
+        let color = c.apply_alpha(self.color.unwrap_or([1.0; 4]));
+        if n == 0 || c.skips(color) { return; }
+        let region = texture.full_source_rect();
+        let default_source_rectangle = self.trimmed_source_rectangle(region);
+        back_end.tri_list_uv(
+            &c.draw_state,
+            &color,
+            texture,
+            |f| {
+                let mut vertices: [f32; 720] = [0.0; 720];
+                let mut uvs: [f32; 720] = [0.0; 720];
+                let mut i = 0;
+                for (j, &(rectangle, transform)) in instances[..n].iter().enumerate() {
+                    let source_rectangle = match source_rectangles {
+                        Some(source_rectangles) => source_rectangles[j],
+                        None => default_source_rectangle
+                    };
+                    let (xy, uv) = self.quad_tri_list(texture, region, source_rectangle,
+                        rectangle, multiply(c.transform, transform));
+                    for k in 0..12 {
+                        vertices[i + k] = xy[k];
+                        uvs[i + k] = uv[k];
+                    }
+                    i += 12;
+                    if i >= vertices.len() {
+                        f.emit(&vertices[..], &uvs[..]);
+                        i = 0;
+                    }
+                }
+                if i > 0 {
+                    f.emit(&vertices[..i], &uvs[..i]);
+                }
+            }
+        );
+    }
+
+    /// Draws the image mapped onto a quadrilateral.
+    ///
+    /// The corners are in clockwise order starting from the top left:
+    /// top left, top right, bottom right, bottom left.
+    /// The corners of the source rectangle map to the same corners.
+    ///
+    /// Texture coordinates are interpolated per triangle,
+    /// which shows a seam along the diagonal for non-parallelogram quads.
+    /// Subdividing the quad into a grid of `subdivisions` x `subdivisions`
+    /// cells gives a better approximation of perspective.
+    /// Zero subdivisions is the same as one.
+    pub fn draw_quad<B>(
+        &self,
+        corners: [internal::Vec2d; 4],
+        subdivisions: u32,
+        texture: &<B as Graphics>::Texture,
+        c: &Context,
+        back_end: &mut B
+    )
+        where B: Graphics
     {
         use internal::Scalar;
 
-        let color = self.color.unwrap_or([1.0; 4]);
-        let source_rectangle = self.source_rectangle.unwrap_or({
-            let (w, h) = texture.get_size();
-            [0, 0, w as i32, h as i32]
-        });
+        let color = c.apply_alpha(self.color.unwrap_or([1.0; 4]));
+        if c.skips(color) { return; }
+        let source_rectangle = self.trimmed_source_rectangle(
+            texture.full_source_rect());
+        if source_rectangle[2] == 0 || source_rectangle[3] == 0 { return; }
+        let mut uv = triangulation::rect_tri_list_uv(texture, source_rectangle);
+        self.flip_uvs(&mut uv);
+        // Upper left and lower right texture coordinates.
+        let (u1, v1, u2, v2) = (uv[0], uv[1], uv[8], uv[9]);
+        let n = if subdivisions == 0 { 1 } else { subdivisions };
+        let (tl, tr, br, bl) = (corners[0], corners[1], corners[2], corners[3]);
+        let m = c.transform;
+        // Bilinear interpolation between the corners.
+        let point = |i: u32, j: u32| {
+            let (s, t) = (i as Scalar / n as Scalar, j as Scalar / n as Scalar);
+            let top = [tl[0] + (tr[0] - tl[0]) * s, tl[1] + (tr[1] - tl[1]) * s];
+            let bottom = [bl[0] + (br[0] - bl[0]) * s, bl[1] + (br[1] - bl[1]) * s];
+            let (x, y) = (
+                top[0] + (bottom[0] - top[0]) * t,
+                top[1] + (bottom[1] - top[1]) * t
+            );
+            ([triangulation::tx(m, x, y), triangulation::ty(m, x, y)],
+            [u1 + (u2 - u1) * s as f32, v1 + (v2 - v1) * t as f32])
+        };
+        back_end.tri_list_uv(
+            &c.draw_state,
+            &color,
+            texture,
+            |f| {
+                let mut vertices: [f32; 720] = [0.0; 720];
+                let mut uvs: [f32; 720] = [0.0; 720];
+                let mut k = 0;
+                for j in 0..n {
+                    for i in 0..n {
+                        let p1 = point(i, j);
+                        let p2 = point(i + 1, j);
+                        let p3 = point(i + 1, j + 1);
+                        let p4 = point(i, j + 1);
+                        for &(xy, uv) in [p1, p2, p4, p2, p3, p4].iter() {
+                            vertices[k] = xy[0];
+                            vertices[k + 1] = xy[1];
+                            uvs[k] = uv[0];
+                            uvs[k + 1] = uv[1];
+                            k += 2;
+                        }
+                        if k >= vertices.len() {
+                            triangulation::ensure_ccw_uv(&mut vertices, &mut uvs);
+                            f.emit(&vertices[..], &uvs[..]);
+                            k = 0;
+                        }
+                    }
+                }
+                if k > 0 {
+                    triangulation::ensure_ccw_uv(&mut vertices[..k], &mut uvs[..k]);
+                    f.emit(&vertices[..k], &uvs[..k]);
+                }
+            }
+        );
+    }
+
+    fn draw_atlas<B>(
+        &self,
+        texture: &<B as Graphics>::Texture,
+        region: internal::SourceRectangle,
+        c: &Context,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        use internal::Scalar;
+
+        let color = c.apply_alpha(self.color.unwrap_or([1.0; 4]));
+        if c.skips(color) { return; }
+        let source_rectangle = self.trimmed_source_rectangle(
+            [0, 0, region[2], region[3]]);
+        if source_rectangle[2] == 0 || source_rectangle[3] == 0 { return; }
         let rectangle = self.rectangle.unwrap_or([
             0.0,
             0.0,
             source_rectangle[2] as Scalar,
             source_rectangle[3] as Scalar
         ]);
+        let atlas_rectangle = [
+            region[0] + source_rectangle[0],
+            region[1] + source_rectangle[1],
+            source_rectangle[2],
+            source_rectangle[3]
+        ];
+        let (xy, uvs) = self.quad_tri_list(texture, region, atlas_rectangle,
+            rectangle, c.transform);
         back_end.tri_list_uv(
             &c.draw_state,
             &color,
             texture,
-            |f| f(
-                &triangulation::rect_tri_list_xy(c.transform, rectangle),
-                &triangulation::rect_tri_list_uv(texture, source_rectangle)
-            )
+            |f| f.emit(&xy, &uvs)
         );
     }
+
+    /// Returns the vertices and texture coordinates of the image
+    /// drawn in a rectangle from a source rectangle in a region,
+    /// with the rotation, source offset and flips of the image.
+    fn quad_tri_list<I: ImageSize>(
+        &self,
+        texture: &I,
+        region: internal::SourceRectangle,
+        atlas_rectangle: internal::SourceRectangle,
+        rectangle: internal::Rectangle,
+        transform: internal::Matrix2d
+    ) -> ([f32; 12], [f32; 12]) {
+        use internal::Scalar;
+
+        let mut uvs = triangulation::rect_tri_list_uv(texture, atlas_rectangle);
+        if self.src_offset != [0.0; 2] {
+            let (mut dx, mut dy) = (self.src_offset[0], self.src_offset[1]);
+            if !self.wrap {
+                let clamp = |d: Scalar, start: i32, size: i32, region_start: i32, region_size: i32| {
+                    let min = (region_start - start) as Scalar;
+                    let max = (region_start + region_size - start - size) as Scalar;
+                    if min > max { 0.0 } else { d.max(min).min(max) }
+                };
+                dx = clamp(dx, atlas_rectangle[0], atlas_rectangle[2], region[0], region[2]);
+                dy = clamp(dy, atlas_rectangle[1], atlas_rectangle[3], region[1], region[3]);
+            }
+            let (w, h) = texture.get_size();
+            let (du, dv) = ((dx / w as Scalar) as f32, (dy / h as Scalar) as f32);
+            for uv in uvs.chunks_mut(2) {
+                uv[0] += du;
+                uv[1] += dv;
+            }
+        }
+        self.flip_uvs(&mut uvs);
+        let transform = if self.rotation == 0.0 {
+            transform
+        } else {
+            use vecmath::{ multiply, rotate_radians, translate };
+
+            let (cx, cy) = (rectangle[0] + 0.5 * rectangle[2], rectangle[1] + 0.5 * rectangle[3]);
+            let pivot = multiply(translate([cx, cy]), rotate_radians(self.rotation));
+            multiply(transform, multiply(pivot, translate([-cx, -cy])))
+        };
+        let mut xy = triangulation::rect_tri_list_xy(transform, rectangle);
+        triangulation::ensure_ccw_uv(&mut xy, &mut uvs);
+        (xy, uvs)
+    }
+}
+
+/// Helper methods for the size of textures.
+pub trait ImageSizeExt: ImageSize {
+    /// Returns the width divided by the height.
+    ///
+    /// Returns `0.0` when the height is zero.
+    fn aspect_ratio(&self) -> internal::Scalar {
+        let (w, h) = self.get_size();
+        if h == 0 { 0.0 } else { w as internal::Scalar / h as internal::Scalar }
+    }
+
+    /// Returns the size as scalars.
+    fn size_f(&self) -> [internal::Scalar; 2] {
+        let (w, h) = self.get_size();
+        [w as internal::Scalar, h as internal::Scalar]
+    }
+
+    /// Returns the source rectangle covering the whole texture.
+    fn full_source_rect(&self) -> internal::SourceRectangle {
+        let (w, h) = self.get_size();
+        [0, 0, w as i32, h as i32]
+    }
+}
+
+impl<T: ImageSize> ImageSizeExt for T {}
+
+/// Implemented by regions of a texture, such as sprites in an atlas.
+pub trait TextureRegion {
+    /// The texture containing the region.
+    type Texture: ImageSize;
+
+    /// Returns the texture containing the region.
+    fn texture(&self) -> &<Self as TextureRegion>::Texture;
+
+    /// Returns the region in pixels as [x, y, w, h].
+    fn region(&self) -> internal::SourceRectangle;
+}
+
+/// A region of a texture.
+///
+/// The size is the size of the region,
+/// so texture coordinates from 0 to 1 of the sub texture
+/// map to the region of the texture.
+pub struct SubTexture<'a, I: 'a> {
+    /// The texture containing the region
+    pub texture: &'a I,
+    /// The region in pixels
+    pub region: internal::SourceRectangle,
+}
+
+impl<'a, I: 'a> Copy for SubTexture<'a, I> {}
+
+impl<'a, I: 'a> Clone for SubTexture<'a, I> {
+    fn clone(&self) -> SubTexture<'a, I> { *self }
+}
+
+impl<'a, I: ImageSize + 'a> SubTexture<'a, I> {
+    /// Creates a new sub texture.
+    pub fn new(texture: &'a I, region: internal::SourceRectangle) -> SubTexture<'a, I> {
+        SubTexture {
+            texture: texture,
+            region: region,
+        }
+    }
+}
+
+impl<'a, I: 'a> ImageSize for SubTexture<'a, I> {
+    fn get_size(&self) -> (u32, u32) {
+        (self.region[2] as u32, self.region[3] as u32)
+    }
+}
+
+impl<'a, I: ImageSize + 'a> TextureRegion for SubTexture<'a, I> {
+    type Texture = I;
+
+    fn texture(&self) -> &I { self.texture }
+
+    fn region(&self) -> internal::SourceRectangle { self.region }
+}
+
+/// Computes source rectangles of frames in a sprite sheet.
+///
+/// The frames are packed in row-major order, starting in the upper left corner.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SpriteSheet {
+    /// The size of each frame in pixels
+    pub frame_size: [u32; 2],
+    /// The number of frames per row
+    pub columns: u32,
+    /// The number of rows
+    pub rows: u32,
+}
+
+impl SpriteSheet {
+    /// Creates a sprite sheet from a texture and the frame size.
+    ///
+    /// Frames that do not fit completely inside the texture are ignored.
+    pub fn new<I: ImageSize>(texture: &I, frame_size: [u32; 2]) -> SpriteSheet {
+        let (w, h) = texture.get_size();
+        SpriteSheet {
+            frame_size: frame_size,
+            columns: if frame_size[0] == 0 { 0 } else { w / frame_size[0] },
+            rows: if frame_size[1] == 0 { 0 } else { h / frame_size[1] },
+        }
+    }
+
+    /// Returns the number of frames.
+    pub fn len(&self) -> usize {
+        self.columns as usize * self.rows as usize
+    }
+
+    /// Returns the source rectangle of a frame,
+    /// or `None` if the index is out of range.
+    pub fn try_frame(&self, frame_index: usize) -> Option<internal::SourceRectangle> {
+        if frame_index >= self.len() { return None; }
+
+        let columns = self.columns as usize;
+        let (x, y) = (frame_index % columns, frame_index / columns);
+        let (w, h) = (self.frame_size[0] as i32, self.frame_size[1] as i32);
+        Some([x as i32 * w, y as i32 * h, w, h])
+    }
+
+    /// Returns the source rectangle of the cell at a column and row,
+    /// or `None` if the cell is outside the sheet.
+    pub fn cell(&self, column: u32, row: u32) -> Option<internal::SourceRectangle> {
+        if column >= self.columns || row >= self.rows { return None; }
+
+        self.try_frame((row * self.columns + column) as usize)
+    }
+
+    /// Returns the source rectangle of a frame.
+    ///
+    /// Indices out of range wrap around, which is useful for looping animations.
+    /// Returns `None` if the sheet has no frames.
+    pub fn frame(&self, frame_index: usize) -> Option<internal::SourceRectangle> {
+        if self.len() == 0 { return None; }
+
+        self.try_frame(frame_index % self.len())
+    }
+}
+
+/// Computes the source rectangles of a uniform grid of cells in a texture.
+pub fn grid_atlas<I: ImageSize>(texture: &I, cell_w: u32, cell_h: u32) -> SpriteSheet {
+    SpriteSheet::new(texture, [cell_w, cell_h])
+}
+
+/// Named regions of a texture.
+pub struct SpriteAtlas<'a, I: 'a> {
+    /// The texture containing the sprites
+    pub texture: &'a I,
+    /// The names and source rectangles of the sprites
+    pub sprites: Vec<(String, internal::SourceRectangle)>,
+}
+
+impl<'a, I: ImageSize + 'a> SpriteAtlas<'a, I> {
+    /// Creates an empty sprite atlas.
+    pub fn new(texture: &'a I) -> SpriteAtlas<'a, I> {
+        SpriteAtlas {
+            texture: texture,
+            sprites: Vec::new(),
+        }
+    }
+
+    /// Adds a named sprite.
+    pub fn sprite_rect(
+        mut self,
+        name: &str,
+        source_rectangle: internal::SourceRectangle
+    ) -> SpriteAtlas<'a, I> {
+        self.sprites.push((name.to_string(), source_rectangle));
+        self
+    }
+
+    /// Returns an image drawing the named sprite,
+    /// or `None` if there is no sprite with the name.
+    pub fn sprite(&self, name: &str) -> Option<Image> {
+        self.sprites.iter()
+            .find(|&&(ref n, _)| &n[..] == name)
+            .map(|&(_, source_rectangle)| Image {
+                source_rectangle: Some(source_rectangle),
+                ..Image::new()
+            })
+    }
+}
+
+impl Default for Image {
+    /// An image without color, drawn with the size of the texture.
+    fn default() -> Image {
+        Image::new()
+    }
 }
 
 quack! {
@@ -85,11 +752,85 @@ quack! {
 
 #[cfg(test)]
 mod test {
-    use super::Image;
+    use super::{ grid_atlas, Fit, Image, ImageSizeExt };
+    use super::{ SpriteAtlas, SpriteSheet, SubTexture };
+    use ImageSize;
+    use Context;
+    use test_backend::{ self, RecordingGraphics };
     use Color;
     use Rect;
     use SrcRect;
     use quack::Set;
+    use internal::Scalar;
+
+    #[test]
+    fn test_default() {
+        let img = Image::default();
+        assert_eq!(img.color, None);
+        assert_eq!(img.rectangle, None);
+        assert_eq!(img.source_rectangle, None);
+        assert_eq!((img.src_offset, img.wrap), ([0.0; 2], false));
+        assert_eq!((img.trim, img.rotation), ([0.0; 4], 0.0));
+        assert!(!img.flip_uv_h && !img.flip_uv_v);
+        // Without a rectangle, the image covers the size of the texture.
+        let mut g = RecordingGraphics::new();
+        img.draw(&test_backend::Texture, &Context::abs(256.0, 256.0), &mut g);
+        for &x in g.tri_lists_uv[0].vertices.iter() {
+            assert!(x == -1.0 || x == 1.0);
+        }
+    }
+
+    #[test]
+    fn test_tint() {
+        let texture = test_backend::Texture;
+        let c = Context::new();
+        let mut g = RecordingGraphics::new();
+        Image::new().tint([1.0, 0.0, 0.0, 1.0]).draw(&texture, &c, &mut g);
+        Image::new().draw(&texture, &c, &mut g);
+        assert_eq!(g.tri_lists_uv[0].color, [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(g.tri_lists_uv[1].color, [1.0; 4]);
+        assert_eq!(Image::new().tint([1.0; 4]), Image::colored([1.0; 4]));
+    }
+
+    #[test]
+    fn test_draw_with_state() {
+        use draw_state;
+        use vecmath::identity;
+
+        let texture = test_backend::Texture;
+        let ds = draw_state::scissor_rect(Context::new().draw_state, [1.0, 2.0, 3.0, 4.0]);
+        let mut g = RecordingGraphics::new();
+        Image::new().draw_with_state(&texture, &ds, identity(), &mut g);
+        Image::new().draw(&texture, &Context::new().scissor([1.0, 2.0, 3.0, 4.0]), &mut g);
+        assert_eq!(g.tri_lists_uv.len(), 2);
+        for t in g.tri_lists_uv.iter() {
+            let r = t.draw_state.scissor.unwrap();
+            assert_eq!([r.x, r.y, r.w, r.h], [1, 2, 3, 4]);
+        }
+        assert_eq!(g.tri_lists_uv[0].vertices, g.tri_lists_uv[1].vertices);
+    }
+
+    #[test]
+    fn test_draw_state() {
+        use vecmath::identity;
+
+        // Every way of drawing an image uses the draw state of the context.
+        let texture = test_backend::Texture;
+        let c = Context::new().scissor([1.0, 2.0, 3.0, 4.0]).additive();
+        let mut g = RecordingGraphics::new();
+        Image::new().draw(&texture, &c, &mut g);
+        Image::new().draw_many(&[([0.0, 0.0, 1.0, 1.0], identity())], None,
+            &texture, &c, &mut g);
+        Image::new().draw_quad([[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]], 2,
+            &texture, &c, &mut g);
+        Image::new().draw_region(&SubTexture::new(&texture, [0, 0, 8, 8]), &c, &mut g);
+        assert_eq!(g.tri_lists_uv.len(), 4);
+        for t in g.tri_lists_uv.iter() {
+            let r = t.draw_state.scissor.unwrap();
+            assert_eq!([r.x, r.y, r.w, r.h], [1, 2, 3, 4]);
+            assert!(t.draw_state.blend == c.draw_state.blend);
+        }
+    }
 
     #[test]
     fn test_image() {
@@ -98,4 +839,276 @@ mod test {
             .set(Rect([0.0, 0.0, 100.0, 100.0]))
             .set(SrcRect([0, 0, 32, 32]));
     }
+
+    struct Texture;
+
+    impl ImageSize for Texture {
+        fn get_size(&self) -> (u32, u32) { (64, 32) }
+    }
+
+    struct Photo(u32, u32);
+
+    impl ImageSize for Photo {
+        fn get_size(&self) -> (u32, u32) { (self.0, self.1) }
+    }
+
+    #[test]
+    fn test_image_size_ext() {
+        let photo = Photo(200, 100);
+        assert_eq!(photo.aspect_ratio(), 2.0);
+        assert_eq!(photo.size_f(), [200.0, 100.0]);
+        assert_eq!(photo.full_source_rect(), [0, 0, 200, 100]);
+        assert_eq!(Photo(200, 0).aspect_ratio(), 0.0);
+    }
+
+    #[test]
+    fn test_fit() {
+        let photo = Photo(200, 100);
+        let dest = [0.0, 0.0, 100.0, 100.0];
+
+        let img = Image::new().fit(&photo, dest, Fit::Fill);
+        assert_eq!(img.rectangle, Some(dest));
+        assert_eq!(img.source_rectangle, None);
+
+        let img = Image::new().fit(&photo, dest, Fit::Contain);
+        assert_eq!(img.rectangle, Some([0.0, 25.0, 100.0, 50.0]));
+        assert_eq!(img.source_rectangle, None);
+
+        let img = Image::new().fit(&photo, dest, Fit::Cover);
+        assert_eq!(img.rectangle, Some(dest));
+        assert_eq!(img.source_rectangle, Some([50, 0, 100, 100]));
+    }
+
+    #[test]
+    fn test_trim() {
+        let texture = test_backend::Texture;
+        let c = Context::abs(256.0, 256.0);
+        let mut g = RecordingGraphics::new();
+        Image::new()
+            .set(SrcRect([0, 0, 64, 64]))
+            .trim([2.0, 4.0, 6.0, 8.0])
+            .draw(&texture, &c, &mut g);
+        let mut h = RecordingGraphics::new();
+        Image::new()
+            .set(SrcRect([2, 4, 56, 52]))
+            .draw(&texture, &c, &mut h);
+        assert_eq!(g.tri_lists_uv[0].uvs, h.tri_lists_uv[0].uvs);
+        assert_eq!(g.tri_lists_uv[0].vertices, h.tri_lists_uv[0].vertices);
+
+        // Insets exceeding the source rectangle skip drawing.
+        let mut g = RecordingGraphics::new();
+        Image::new().trim([200.0, 0.0, 100.0, 0.0]).draw(&texture, &c, &mut g);
+        assert_eq!(g.tri_lists_uv.len(), 0);
+
+        let img = Image::new().trim([0.0, 0.0, 100.0, 0.0])
+            .fit(&Photo(200, 100), [0.0, 0.0, 100.0, 100.0], Fit::Cover);
+        assert_eq!(img.source_rectangle, Some([0, 0, 100, 100]));
+        assert_eq!(img.trim, [0.0; 4]);
+    }
+
+    #[test]
+    fn test_rot_center() {
+        let texture = test_backend::Texture;
+        let c = Context::new();
+        let mut g = RecordingGraphics::new();
+        Image::new().set(Rect([10.0, 20.0, 30.0, 40.0])).rot_deg_center(180.0)
+            .draw(&texture, &c, &mut g);
+        let t = &g.tri_lists_uv[0];
+        // The vertex sampling the top left of the texture
+        // is at the bottom right corner.
+        let i = t.uvs.chunks(2).position(|uv| uv[0] == 0.0 && uv[1] == 0.0).unwrap();
+        assert!((t.vertices[2 * i] - 40.0).abs() < 0.0001);
+        assert!((t.vertices[2 * i + 1] - 60.0).abs() < 0.0001);
+        let (min_x, max_x) = t.vertices.chunks(2)
+            .fold((::std::f32::MAX, ::std::f32::MIN), |(a, b), v| (a.min(v[0]), b.max(v[0])));
+        assert!((min_x - 10.0).abs() < 0.0001 && (max_x - 40.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_flip_uv() {
+        let texture = test_backend::Texture;
+        let c = Context::new();
+        let mut g = RecordingGraphics::new();
+        Image::new().set(SrcRect([0, 0, 128, 64])).draw(&texture, &c, &mut g);
+        Image::new().set(SrcRect([0, 0, 128, 64])).flip_uv_v().draw(&texture, &c, &mut g);
+        Image::new().set(SrcRect([0, 0, 128, 64])).flip_uv_h().draw(&texture, &c, &mut g);
+        let (a, v, h) = (&g.tri_lists_uv[0], &g.tri_lists_uv[1], &g.tri_lists_uv[2]);
+        assert_eq!(a.vertices, v.vertices);
+        assert_eq!(a.vertices, h.vertices);
+        for ((p, q), r) in a.uvs.chunks(2).zip(v.uvs.chunks(2)).zip(h.uvs.chunks(2)) {
+            assert_eq!(q[0], p[0]);
+            assert_eq!(q[1], 0.25 - p[1]);
+            assert_eq!(r[0], 0.5 - p[0]);
+            assert_eq!(r[1], p[1]);
+        }
+    }
+
+    #[test]
+    fn test_sprite_sheet() {
+        let sheet = SpriteSheet::new(&Texture, [16, 16]);
+        assert_eq!(sheet.len(), 8);
+        assert_eq!(sheet.frame(0), Some([0, 0, 16, 16]));
+        assert_eq!(sheet.frame(3), Some([48, 0, 16, 16]));
+        assert_eq!(sheet.frame(4), Some([0, 16, 16, 16]));
+        assert_eq!(sheet.frame(7), Some([48, 16, 16, 16]));
+        assert_eq!(sheet.frame(8), Some([0, 0, 16, 16]));
+        assert_eq!(sheet.try_frame(7), Some([48, 16, 16, 16]));
+        assert_eq!(sheet.try_frame(8), None);
+
+        let img = Image::new().src_frame(&Texture, 5, [16, 16]);
+        assert_eq!(img.source_rectangle, Some([16, 16, 16, 16]));
+        let img = Image::new().try_src_frame(&Texture, 5, [16, 16]);
+        assert_eq!(img.unwrap().source_rectangle, Some([16, 16, 16, 16]));
+        assert!(Image::new().try_src_frame(&Texture, 8, [16, 16]).is_none());
+
+        // Empty sheets, with a zero frame size or frames larger than the texture.
+        for &size in [[0, 16], [16, 0], [1024, 1024]].iter() {
+            let sheet = SpriteSheet::new(&Texture, size);
+            assert_eq!(sheet.len(), 0);
+            assert_eq!(sheet.frame(3), None);
+            let img = Image { source_rectangle: Some([1, 2, 3, 4]), ..Image::new() };
+            let img = img.src_frame(&Texture, 3, size);
+            assert_eq!(img.source_rectangle, Some([1, 2, 3, 4]));
+            assert!(Image::new().try_src_frame(&Texture, 0, size).is_none());
+        }
+    }
+
+    #[test]
+    fn test_sprite_atlas() {
+        let texture = test_backend::Texture;
+        let grid = grid_atlas(&texture, 32, 32);
+        assert_eq!(grid.cell(1, 0), Some([32, 0, 32, 32]));
+        assert_eq!(grid.cell(0, 8), None);
+
+        let atlas = SpriteAtlas::new(&texture)
+            .sprite_rect("player", [0, 0, 16, 24])
+            .sprite_rect("coin", [16, 0, 8, 8]);
+        assert_eq!(atlas.sprite("coin").unwrap().source_rectangle, Some([16, 0, 8, 8]));
+        assert!(atlas.sprite("enemy").is_none());
+    }
+
+    #[test]
+    fn test_draw_many() {
+        use vecmath::translate;
+        use RelativeTransform;
+
+        let c = Context::abs(100.0, 100.0);
+        let instances: Vec<_> = (0..150)
+            .map(|i| ([0.0, 0.0, 4.0, 4.0], translate([i as Scalar, 0.0])))
+            .collect();
+
+        let mut naive = RecordingGraphics::new();
+        for &(rect, transform) in instances.iter() {
+            let c = c.append_transform(transform);
+            Image::new().set(Rect(rect)).draw(&test_backend::Texture, &c, &mut naive);
+        }
+        assert_eq!(naive.tri_lists_uv.len(), 150);
+
+        let mut g = RecordingGraphics::new();
+        Image::new().draw_many(&instances[..], None, &test_backend::Texture, &c, &mut g);
+        assert_eq!(g.tri_lists_uv.len(), 1);
+        assert_eq!(g.vertex_count(), 150 * 6);
+
+        let sources = vec![[0, 0, 128, 128]; 150];
+        let mut g = RecordingGraphics::new();
+        Image::new().draw_many(&instances[..], Some(&sources[..]),
+            &test_backend::Texture, &c, &mut g);
+        assert_eq!(g.tri_lists_uv[0].uvs[2], 0.5);
+        // Same vertices as drawing one at a time.
+        let naive_vertices: Vec<f32> = naive.tri_lists_uv.iter()
+            .flat_map(|t| t.vertices.iter().map(|x| *x))
+            .collect();
+        assert_eq!(g.tri_lists_uv[0].vertices, naive_vertices);
+
+        // Rotation, source offsets and flips are the same as drawing one at a time.
+        let image = Image::new().rot_deg_center(30.0).src_offset([8.0, 4.0], true).flip_uv_h();
+        let mut naive = RecordingGraphics::new();
+        for &(rect, transform) in instances.iter() {
+            let c = c.append_transform(transform);
+            image.set(Rect(rect)).draw(&test_backend::Texture, &c, &mut naive);
+        }
+        let mut g = RecordingGraphics::new();
+        image.draw_many(&instances[..], None, &test_backend::Texture, &c, &mut g);
+        let naive_uvs: Vec<f32> = naive.tri_lists_uv.iter()
+            .flat_map(|t| t.uvs.iter().map(|x| *x))
+            .collect();
+        let naive_vertices: Vec<f32> = naive.tri_lists_uv.iter()
+            .flat_map(|t| t.vertices.iter().map(|x| *x))
+            .collect();
+        assert_eq!(g.tri_lists_uv[0].uvs, naive_uvs);
+        assert_eq!(g.tri_lists_uv[0].vertices, naive_vertices);
+
+        // Instances without a source rectangle are skipped.
+        let mut g = RecordingGraphics::new();
+        Image::new().draw_many(&instances[..], Some(&sources[..10]),
+            &test_backend::Texture, &c, &mut g);
+        assert_eq!(g.vertex_count(), 10 * 6);
+        Image::new().draw_many(&instances[..], Some(&sources[..0]),
+            &test_backend::Texture, &c, &mut g);
+        assert_eq!(g.tri_lists_uv.len(), 1);
+    }
+
+    #[test]
+    fn test_draw_quad() {
+        let c = Context::abs(2.0, 2.0);
+        let mut g = RecordingGraphics::new();
+        Image::new()
+            .set(SrcRect([0, 0, 128, 256]))
+            .draw_quad([[0.0, 0.0], [2.0, 0.0], [1.5, 2.0], [0.5, 2.0]],
+                0, &test_backend::Texture, &c, &mut g);
+        let t = &g.tri_lists_uv[0];
+        assert_eq!(t.vertices.len(), 12);
+        // Top left, bottom left, top right and top right, bottom left, bottom right,
+        // which is counter-clockwise in normalized device coordinates.
+        assert_eq!(t.vertices, vec![
+            -1.0, 1.0,  -0.5, -1.0,  1.0, 1.0,
+            1.0, 1.0,  -0.5, -1.0,  0.5, -1.0
+        ]);
+        assert_eq!(t.uvs, vec![
+            0.0, 0.0,  0.0, 1.0,  0.5, 0.0,
+            0.5, 0.0,  0.0, 1.0,  0.5, 1.0
+        ]);
+
+        let mut g = RecordingGraphics::new();
+        Image::new().draw_quad([[0.0, 0.0], [2.0, 0.0], [1.5, 2.0], [0.5, 2.0]],
+            4, &test_backend::Texture, &c, &mut g);
+        assert_eq!(g.vertex_count(), 4 * 4 * 6);
+    }
+
+    #[test]
+    fn test_src_offset() {
+        let c = Context::abs(100.0, 100.0);
+        let uvs = |img: Image| {
+            let mut g = RecordingGraphics::new();
+            img.draw(&test_backend::Texture, &c, &mut g);
+            g.tri_lists_uv[0].uvs.clone()
+        };
+
+        let img = Image::new().set(SrcRect([0, 0, 128, 128]));
+        let base = uvs(img);
+        let offset = uvs(img.src_offset([64.0, 32.0], false));
+        for (a, b) in base.chunks(2).zip(offset.chunks(2)) {
+            assert_eq!(b[0] - a[0], 0.25);
+            assert_eq!(b[1] - a[1], 0.125);
+        }
+
+        // Without wrapping the source rectangle stays inside the texture.
+        let full = uvs(Image::new());
+        assert_eq!(uvs(Image::new().src_offset([128.0, 0.0], false)), full);
+        let wrapped = uvs(Image::new().src_offset([128.0, 0.0], true));
+        assert_eq!(&wrapped[..6], &[0.5, 0.0, 0.5, 1.0, 1.5, 0.0][..]);
+    }
+
+    #[test]
+    fn test_sub_texture() {
+        let atlas = test_backend::Texture;
+        let sub = SubTexture::new(&atlas, [64, 0, 32, 32]);
+        assert_eq!(sub.get_size(), (32, 32));
+
+        let c = Context::abs(100.0, 100.0);
+        let mut g = RecordingGraphics::new();
+        Image::new().draw_region(&sub, &c, &mut g);
+        let uvs = &g.tri_lists_uv[0].uvs;
+        assert_eq!(&uvs[..6], &[0.25, 0.0, 0.25, 0.125, 0.375, 0.0][..]);
+    }
 }
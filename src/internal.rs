@@ -2,6 +2,7 @@
 //! Contains type aliases used in this library
 
 pub use vecmath::{ Scalar, Vec2d };
+pub use vecmath::{ compose, decompose };
 
 /// The type used for area.
 pub type Area = Scalar;
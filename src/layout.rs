@@ -0,0 +1,66 @@
+//! Computes rectangles for laying out elements.
+
+use internal::{ Rectangle, Scalar, Vec2d };
+
+/// Returns an iterator over the cells of a grid.
+///
+/// Yields `(col, row, rectangle)` row by row, starting at `origin`.
+/// The iterator is empty when `cols` or `rows` is zero.
+pub fn grid(origin: Vec2d, cell_size: Vec2d, cols: u32, rows: u32) -> GridRects {
+    GridRects {
+        origin: origin,
+        cell_size: cell_size,
+        cols: cols,
+        rows: rows,
+        index: 0,
+    }
+}
+
+/// Iterates through the cells of a grid as (u32, u32, Rectangle).
+#[derive(Copy, Clone)]
+pub struct GridRects {
+    origin: Vec2d,
+    cell_size: Vec2d,
+    cols: u32,
+    rows: u32,
+    index: u64,
+}
+
+impl Iterator for GridRects {
+    type Item = (u32, u32, Rectangle);
+
+    fn next(&mut self) -> Option<(u32, u32, Rectangle)> {
+        let cols = self.cols as u64;
+        if self.index >= cols * self.rows as u64 {
+            return None;
+        }
+        let col = (self.index % cols) as u32;
+        let row = (self.index / cols) as u32;
+        self.index += 1;
+        let (w, h) = (self.cell_size[0], self.cell_size[1]);
+        Some((col, row, [
+            self.origin[0] + col as Scalar * w,
+            self.origin[1] + row as Scalar * h,
+            w,
+            h
+        ]))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::grid;
+
+    #[test]
+    fn test_grid() {
+        let cells: Vec<_> = grid([10.0, 20.0], [16.0, 8.0], 2, 2).collect();
+        assert_eq!(cells, vec![
+            (0, 0, [10.0, 20.0, 16.0, 8.0]),
+            (1, 0, [26.0, 20.0, 16.0, 8.0]),
+            (0, 1, [10.0, 28.0, 16.0, 8.0]),
+            (1, 1, [26.0, 28.0, 16.0, 8.0]),
+        ]);
+        assert_eq!(grid([0.0, 0.0], [1.0, 1.0], 0, 3).count(), 0);
+        assert_eq!(grid([0.0, 0.0], [1.0, 1.0], 3, 0).count(), 0);
+    }
+}
@@ -1,8 +1,21 @@
 #![crate_name = "graphics"]
 #![deny(missing_docs)]
 #![deny(missing_copy_implementations)]
+#![cfg_attr(feature = "serde_macros", feature(custom_derive, plugin))]
+#![cfg_attr(feature = "serde_macros", plugin(serde_macros))]
+#![cfg_attr(feature = "simd", feature(core_simd))]
+#![cfg_attr(not(feature = "std"), no_std)]
 
 //! A library for 2D graphics that works with multiple back-ends.
+//!
+//! Shapes such as `Rectangle` and `Ellipse` are created with their
+//! constructors or `Default` and configured with builder methods.
+//! Fields may be added to them in later versions, which breaks
+//! struct literals.
+//!
+//! Without the default `std` feature, the crate builds with `no_std`
+//! and `alloc`, and needs the `libm` feature for float functions.
+//! The `svg` and `serialize` features require `std`.
 
 extern crate "vecmath" as vecmath_lib;
 extern crate texture;
@@ -10,25 +23,66 @@ extern crate read_color;
 #[macro_use]
 extern crate quack;
 extern crate interpolation;
-extern crate draw_state;
+extern crate "draw_state" as draw_state_lib;
+// Exactly one of the `scalar-f32` and `scalar-f64` features selects
+// the type of `Scalar`. These crates do not exist, so the build
+// fails with their names when both or neither are enabled.
+#[cfg(all(feature = "scalar-f32", feature = "scalar-f64"))]
+extern crate the_scalar_f32_and_scalar_f64_features_are_mutually_exclusive;
+#[cfg(not(any(feature = "scalar-f32", feature = "scalar-f64")))]
+extern crate enable_the_scalar_f32_or_scalar_f64_feature;
+#[cfg(not(feature = "std"))]
+#[macro_use]
+extern crate alloc;
+#[cfg(feature = "libm")]
+extern crate libm;
+// Builds without `std` compute float functions with `libm`.
+#[cfg(not(any(feature = "std", feature = "libm")))]
+extern crate enable_the_std_or_libm_feature;
+#[cfg(feature = "lyon")]
+extern crate lyon_tessellation;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde_json"))]
+extern crate serde_json;
 
 pub use texture::ImageSize;
 pub use draw_state::DrawState;
 
 pub use graphics::Graphics;
+/// Deprecated alias of `Graphics`, kept for compatibility.
+///
+/// All primitives take `B: Graphics`, and `Image` passes the texture
+/// and color to `tri_list_uv`, so back-ends only need to rename
+/// `BackEnd` to `Graphics`. This alias will be removed in a later release.
+#[deprecated(note = "use Graphics")]
 pub use graphics::Graphics as BackEnd;
+pub use graphics::Unsupported;
+pub use graphics::{ AttributeEmitter, Emitter };
 pub use relative::{
+    AbsoluteTransform,
     RelativeColor,
     RelativeRectangle,
     RelativeSourceRectangle,
     RelativeTransform,
     RelativeViewTransform,
 };
+/// Chains transforms on a `Matrix2d` or a `Context`.
+pub use relative::RelativeTransform as Transformed;
 pub use rectangle::Rectangle;
 pub use line::Line;
+pub use line::PixelLine;
 pub use ellipse::Ellipse;
 pub use image::Image;
+pub use image::ImageSizeExt;
 pub use polygon::Polygon;
+pub use pattern::Checkerboard;
+pub use ring::Ring;
+pub use capsule::Capsule;
+pub use cached::CachedShape;
+pub use command_list::CommandList;
+pub use sector::Sector;
+pub use shape::Shape;
 
 pub use context::Context as Context;
 
@@ -40,6 +94,26 @@ pub static BACK_END_MAX_VERTEX_COUNT: usize = 1024;
 mod graphics;
 mod relative;
 
+// Without `std`, the `std` paths of this crate refer to `core`
+// and `alloc`, and `std::num::Float` to the `libm` functions.
+#[cfg(not(feature = "std"))]
+mod std {
+    pub use core::{ cmp, f32, f64, marker, ops, u16 };
+    #[cfg(feature = "simd")]
+    pub use core::simd;
+    pub use alloc::{ boxed, string, vec };
+
+    pub mod num {
+        pub use float::Float;
+    }
+}
+#[cfg(not(feature = "std"))]
+mod float;
+
+pub mod blend;
+pub mod cached;
+pub mod capsule;
+pub mod command_list;
 pub mod character;
 pub mod context;
 pub mod color;
@@ -47,15 +121,37 @@ pub mod polygon;
 pub mod line;
 pub mod ellipse;
 pub mod rectangle;
+pub mod ring;
+pub mod sector;
+pub mod shape;
+pub mod spline;
+pub mod stencil;
 pub mod image;
 pub mod internal;
+pub mod layout;
+pub mod math;
 pub mod modular_index;
+pub mod path;
+pub mod pattern;
 pub mod text;
 pub mod triangulation;
 pub mod vecmath;
 pub mod deform;
+pub mod draw_state;
 pub mod grid;
 
+#[cfg(feature = "svg")]
+pub mod svg;
+
+#[cfg(feature = "serde")]
+mod serde_color;
+
+#[cfg(feature = "lyon")]
+mod lyon;
+
+#[cfg(any(test, feature = "testing"))]
+pub mod test_backend;
+
 pub mod radians {
     //! Reexport radians helper trait from vecmath
 
@@ -68,10 +164,12 @@ pub struct Color(pub internal::Color);
 
 /// A rectangle property
 #[derive(Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Rect(pub internal::Rectangle);
 
 /// A source rectangle property
 #[derive(Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SrcRect(pub internal::SourceRectangle);
 
 /// Clears the screen.
@@ -83,6 +181,22 @@ pub fn clear<B>(
     back_end.clear(color);
 }
 
+/// Clears a rectangle with a color.
+///
+/// Draws the rectangle without blending, so the color replaces
+/// what was drawn before, including the alpha, like `clear`.
+/// The rectangle follows the transform and clipping of the context.
+pub fn clear_rect<B>(
+    color: internal::Color,
+    rect: internal::Rectangle,
+    c: &Context,
+    back_end: &mut B
+)
+    where B: Graphics
+{
+    Rectangle::new(color).draw(rect, &c.replace(), back_end);
+}
+
 /// Draws image.
 pub fn image<B>(
     image: &<B as Graphics>::Texture,
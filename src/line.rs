@@ -2,12 +2,17 @@
 
 use internal;
 use triangulation;
+use color;
+use color::Gradient;
+use std::num::Float;
+use DrawState;
 use Graphics;
 use Context;
 use Color;
 
 /// The shape of the line
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Shape {
     /// Square edges
     Square,
@@ -17,6 +22,25 @@ pub enum Shape {
     Bevel,
 }
 
+/// How the segments of a polyline meet
+///
+/// See `triangulation::with_polyline_tri_list`.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Join {
+    /// Sharp corners, beveled when sharper than `MITER_LIMIT`
+    Miter,
+    /// Cut corners
+    Bevel,
+    /// Round corners
+    Round,
+}
+
+/// The longest miter of a `Join::Miter`, in line radii.
+///
+/// Sharper corners are beveled instead, like the default of SVG.
+pub const MITER_LIMIT: internal::Scalar = 4.0;
+
 /// The line border radius
 #[derive(Copy)]
 pub struct Radius(pub internal::Radius);
@@ -26,14 +50,22 @@ pub struct Radius(pub internal::Radius);
 pub struct Width(pub internal::Width);
 
 /// A colored line with a default border radius
-#[derive(Copy, Clone)]
+///
+/// Create it with `new`, `round` or `Default` and the builder methods.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Line {
     /// The line color
+    #[cfg_attr(feature = "serde", serde(serialize_with = "::serde_color::serialize",
+        deserialize_with = "::serde_color::deserialize"))]
     pub color: internal::Color,
     /// The line radius
     pub radius: internal::Radius,
     /// The line shape
     pub shape: Shape,
+    /// The width of the feathered edge, if anti-aliased
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub antialias: Option<internal::Width>,
 }
 
 impl Line {
@@ -43,6 +75,7 @@ impl Line {
             color: color,
             radius: radius,
             shape: Shape::Square,
+            antialias: None,
         }
     }
 
@@ -52,6 +85,25 @@ impl Line {
             color: color,
             radius: radius,
             shape: Shape::Round,
+            antialias: None,
+        }
+    }
+
+    /// Adds a feathered edge of some width, in device pixels,
+    /// outside the line.
+    ///
+    /// See `triangulation::with_polygon_feather_tri_list_c`.
+    pub fn antialias(mut self, width: internal::Width) -> Line {
+        self.antialias = Some(width);
+        self
+    }
+
+    /// Returns the resolution of the caps for the shape.
+    fn resolution_cap(&self) -> usize {
+        match self.shape {
+            Shape::Square => 2,
+            Shape::Round => 64,
+            Shape::Bevel => 3,
         }
     }
 
@@ -64,47 +116,115 @@ impl Line {
     )
         where B: Graphics
     {
-        match self.shape {
-            Shape::Square => {
-                back_end.tri_list(
-                    &c.draw_state,
-                    &self.color,
-                    |f|
-                triangulation::with_round_border_line_tri_list(
-                    2,
-                    c.transform,
-                    line,
-                    self.radius,
-                    |vertices| f(vertices)
-                ));
-            }
-            Shape::Round => {
-                back_end.tri_list(
-                    &c.draw_state,
-                    &self.color,
-                    |f|
-                triangulation::with_round_border_line_tri_list(
-                    64,
-                    c.transform,
-                    line,
-                    self.radius,
-                    |vertices| f(vertices)
-                ));
-            }
-            Shape::Bevel => {
-                back_end.tri_list(
-                    &c.draw_state,
-                    &self.color,
-                    |f|
-                triangulation::with_round_border_line_tri_list(
-                    3,
-                    c.transform,
-                    line,
-                    self.radius,
-                    |vertices| f(vertices)
-                ));
-            }
+        let color = c.apply_alpha(self.color);
+        if c.skips(color) { return; }
+        let resolution_cap = self.resolution_cap();
+        back_end.tri_list(
+            &c.draw_state,
+            &color,
+            |f|
+        triangulation::with_round_border_line_tri_list(
+            resolution_cap,
+            c.transform,
+            line,
+            self.radius,
+            |vertices| f.emit(vertices)
+        ));
+
+        if let Some(width) = self.antialias {
+            if !back_end.has_vertex_colors() { return; }
+            let width = c.local_width(width);
+            back_end.tri_list_c(
+                &c.draw_state,
+                |f|
+            triangulation::with_round_border_line_feather_tri_list_c(
+                resolution_cap,
+                c.transform,
+                line,
+                self.radius,
+                width,
+                color,
+                |vertices, colors| f.emit(vertices, colors)
+            ));
+        }
+    }
+
+    /// Draws the line with a draw state and transform,
+    /// for callers without a context.
+    ///
+    /// See `Context::from_state`.
+    pub fn draw_with_state<B>(
+        &self,
+        line: internal::Line,
+        draw_state: &DrawState,
+        transform: internal::Matrix2d,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        self.draw(line, &Context::from_state(draw_state, transform), back_end);
+    }
+
+    /// Draws many lines with a color each, using the same draw state.
+    ///
+    /// The radius and shape of this line are used for all of them,
+    /// but not the color or the feathered edge.
+    /// The lines are streamed through as few calls to the back-end
+    /// as possible, see `graphics::tri_list_colored`.
+    pub fn draw_iter<I, B>(&self, iter: I, c: &Context, back_end: &mut B)
+        where
+            I: IntoIterator<Item = (internal::Line, internal::Color)>,
+            B: Graphics
+    {
+        use graphics::tri_list_colored;
+
+        let resolution_cap = self.resolution_cap();
+        let iter = iter.into_iter()
+            .map(|(line, color)| (line, c.apply_alpha(color)))
+            .filter(|&(_, color)| !c.skips(color));
+        tri_list_colored(back_end, &c.draw_state, iter, |line, f| {
+            triangulation::with_round_border_line_tri_list(
+                resolution_cap,
+                c.transform,
+                line,
+                self.radius,
+                |vertices| f.emit(vertices)
+            )
+        });
+    }
+
+    /// Draws the line with square ends, filled with a gradient
+    /// from the start to the end, instead of the color.
+    ///
+    /// See `triangulation::with_gradient_rect_tri_list_c`.
+    pub fn draw_gradient<B>(
+        &self,
+        gradient: &Gradient,
+        line: internal::Line,
+        c: &Context,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        use vecmath::{ multiply, orient, translate };
+
+        if gradient.stops.iter().all(|&(_, color)| c.skips(c.apply_alpha(color))) {
+            return;
         }
+        let gradient = gradient.fade(c.alpha);
+        let (dx, dy) = (line[2] - line[0], line[3] - line[1]);
+        let len = (dx * dx + dy * dy).sqrt();
+        let m = multiply(c.transform, translate([line[0], line[1]]));
+        let m = multiply(m, orient(dx, dy));
+        back_end.tri_list_c(
+            &c.draw_state,
+            |f|
+        triangulation::with_gradient_rect_tri_list_c(
+            m,
+            [0.0, -self.radius, len, 2.0 * self.radius],
+            &gradient,
+            |vertices, colors| f.emit(vertices, colors)
+        ));
     }
 
     /// Draws an arrow
@@ -130,6 +250,77 @@ impl Line {
     }
 }
 
+impl Default for Line {
+    /// A white line of width 1 with square ends.
+    fn default() -> Line {
+        Line::new(color::WHITE, 0.5)
+    }
+}
+
+/// A line of one pixel thickness, snapped to the pixel grid
+///
+/// The end points are rounded to the nearest pixel,
+/// and the pixels in between are chosen with Bresenham's algorithm,
+/// so horizontal and vertical lines are exactly one pixel thick
+/// without blurred edges.
+///
+/// The pixels are the coordinates of `Context::view`, as with the views
+/// of `Context::abs` and `Context::from_viewport`.
+/// A view that does not map whole units to pixels gives blurred lines.
+#[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PixelLine {
+    /// The line color
+    #[cfg_attr(feature = "serde", serde(serialize_with = "::serde_color::serialize",
+        deserialize_with = "::serde_color::deserialize"))]
+    pub color: internal::Color,
+}
+
+impl PixelLine {
+    /// Creates a new pixel line
+    pub fn new(color: internal::Color) -> PixelLine {
+        PixelLine {
+            color: color,
+        }
+    }
+
+    /// Draws the line.
+    ///
+    /// The end points are transformed to view coordinates and rounded,
+    /// so the view must be invertible.
+    /// See `triangulation::with_pixel_line_tri_list`.
+    pub fn draw<B>(
+        &self,
+        line: internal::Line,
+        c: &Context,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        use math::transform_pos;
+        use vecmath::{ invert, multiply };
+
+        let color = c.apply_alpha(self.color);
+        if c.skips(color) { return; }
+        let m = multiply(invert(c.view), c.transform);
+        let pixel = |x: internal::Scalar, y: internal::Scalar| {
+            let p = transform_pos(m, [x, y]);
+            [p[0].round() as i64, p[1].round() as i64]
+        };
+        let (from, to) = (pixel(line[0], line[1]), pixel(line[2], line[3]));
+        back_end.tri_list(
+            &c.draw_state,
+            &color,
+            |f|
+        triangulation::with_pixel_line_tri_list(
+            c.view,
+            from,
+            to,
+            |vertices| f.emit(vertices)
+        ));
+    }
+}
+
 quack! {
     l: Line[]
     get:
@@ -164,4 +355,65 @@ mod test {
             .hue_deg(1.0);
         let Color(_) = _line.get();
     }
+
+    #[test]
+    fn test_default() {
+        let line = Line::default();
+        assert_eq!(line.color, [1.0; 4]);
+        // A width of 1.
+        let Radius(radius) = line.get();
+        assert_eq!(radius, 0.5);
+        assert_eq!(line.shape, Shape::Square);
+        assert_eq!(line.antialias, None);
+        assert_eq!(Line::round([1.0; 4], 0.5).shape, Shape::Round);
+    }
+
+    #[test]
+    fn test_draw_iter() {
+        use Context;
+        use test_backend::RecordingGraphics;
+
+        let c = Context::new();
+        let line = Line::new([1.0; 4], 0.5);
+        let lines = vec![
+            ([0.0, 0.0, 1.0, 0.0], [1.0, 0.0, 0.0, 1.0]),
+            ([0.0, 1.0, 1.0, 1.0], [0.0, 0.0, 0.0, 0.0]),
+            ([0.0, 2.0, 1.0, 2.0], [0.0, 0.0, 1.0, 1.0]),
+        ];
+        let mut g = RecordingGraphics::new();
+        line.draw_iter(lines.into_iter(), &c, &mut g);
+        // The transparent line is skipped.
+        assert_eq!(g.tri_lists_c.len(), 1);
+        let t = &g.tri_lists_c[0];
+        assert_eq!(t.vertices.len(), 2 * 12);
+        assert_eq!(&t.colors[..4], &[1.0, 0.0, 0.0, 1.0][..]);
+        assert_eq!(&t.colors[t.colors.len() - 4..], &[0.0, 0.0, 1.0, 1.0][..]);
+    }
+
+    #[test]
+    fn test_pixel_line() {
+        use std::num::Float;
+        use Context;
+        use super::PixelLine;
+        use test_backend::RecordingGraphics;
+
+        let c = Context::abs(100.0, 100.0);
+        let mut g = RecordingGraphics::new();
+        PixelLine::new([1.0; 4]).draw([10.2, 20.4, 50.7, 20.4], &c, &mut g);
+        let vertices = &g.tri_lists[0].vertices;
+        // One quad from pixel 10 to pixel 51.
+        assert_eq!(vertices.len(), 12);
+        // Back to pixels, with the y axis pointing down.
+        let pixels: Vec<(f32, f32)> = vertices.chunks(2)
+            .map(|v| ((v[0] + 1.0) * 50.0, (1.0 - v[1]) * 50.0))
+            .collect();
+        let min_y = pixels.iter().fold(100.0, |m: f32, p| m.min(p.1));
+        let max_y = pixels.iter().fold(0.0, |m: f32, p| m.max(p.1));
+        assert!((min_y - 20.0).abs() < 0.0001);
+        assert!((max_y - min_y - 1.0).abs() < 0.0001);
+        let min_x = pixels.iter().fold(100.0, |m: f32, p| m.min(p.0));
+        let max_x = pixels.iter().fold(0.0, |m: f32, p| m.max(p.0));
+        assert!((min_x - 10.0).abs() < 0.0001);
+        assert!((max_x - 52.0).abs() < 0.0001);
+    }
 }
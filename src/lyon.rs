@@ -0,0 +1,261 @@
+//! Tessellation of paths by `lyon_tessellation`
+//!
+//! Used by `Path` with the `lyon` feature. The output is streamed
+//! as tri lists in chunks, like the built-in tessellators,
+//! which are used instead when lyon fails.
+
+use std::num::Float;
+use lyon_tessellation::{
+    BuffersBuilder,
+    FillOptions,
+    FillTessellator,
+    FillVertex,
+    LineCap,
+    LineJoin,
+    StrokeOptions,
+    StrokeTessellator,
+    StrokeVertex,
+    VertexBuffers,
+};
+use lyon_tessellation::FillRule as LyonFillRule;
+use lyon_tessellation::math::point;
+use lyon_tessellation::path::Path as LyonPath;
+
+use line::{ Join, Shape, MITER_LIMIT };
+use radians::Radians;
+use triangulation::{ self, tx, ty, FillRule };
+use vecmath::{ Matrix2d, Scalar, Vec2d };
+
+/// The flattening tolerance of fills, in local coordinates.
+///
+/// Paths only contain line segments, so this is only used for
+/// the curves lyon adds itself, which fills do not have.
+const FILL_TOLERANCE: f32 = 0.1;
+
+/// Builds a lyon path, or returns `None` if a point is not finite.
+fn build<'a, I>(subpaths: I) -> Option<LyonPath>
+    where I: Iterator<Item = (&'a [Vec2d], bool)>
+{
+    let mut builder = LyonPath::builder();
+    for (points, closed) in subpaths {
+        if points.iter().any(|p| !p[0].is_finite() || !p[1].is_finite()) {
+            return None;
+        }
+        let mut points = points.iter();
+        if let Some(p) = points.next() {
+            builder.begin(point(p[0] as f32, p[1] as f32));
+            for p in points {
+                builder.line_to(point(p[0] as f32, p[1] as f32));
+            }
+            builder.end(closed);
+        }
+    }
+    Some(builder.build())
+}
+
+/// Transforms the triangles and streams them as tri list.
+fn stream<F>(m: Matrix2d, buffers: &VertexBuffers<[f32; 2], u32>, mut f: F)
+    where F: FnMut(&[f32])
+{
+    let mut vertices: [f32; 720] = [0.0; 720];
+    let mut n = 0;
+    for &i in buffers.indices.iter() {
+        let p = buffers.vertices[i as usize];
+        let (x, y) = (p[0] as Scalar, p[1] as Scalar);
+        vertices[n] = tx(m, x, y);
+        vertices[n + 1] = ty(m, x, y);
+        n += 2;
+        // Buffer is full.
+        if n == vertices.len() {
+            triangulation::ensure_ccw(&mut vertices);
+            f(&vertices);
+            n = 0;
+        }
+    }
+    if n > 0 {
+        triangulation::ensure_ccw(&mut vertices[..n]);
+        f(&vertices[..n]);
+    }
+}
+
+/// Streams the fill of polygons as tri list.
+///
+/// Returns `false` without streaming anything when lyon fails.
+pub fn fill_tri_list<'a, I, F>(m: Matrix2d, polygons: I, fill_rule: FillRule, f: F) -> bool
+    where
+        I: Iterator<Item = &'a [Vec2d]>,
+        F: FnMut(&[f32])
+{
+    let path = match build(polygons.map(|points| (points, true))) {
+        None => return false,
+        Some(path) => path
+    };
+    let fill_rule = match fill_rule {
+        FillRule::NonZero => LyonFillRule::NonZero,
+        FillRule::EvenOdd => LyonFillRule::EvenOdd,
+    };
+    let options = FillOptions::tolerance(FILL_TOLERANCE).with_fill_rule(fill_rule);
+    let mut buffers: VertexBuffers<[f32; 2], u32> = VertexBuffers::new();
+    let res = FillTessellator::new().tessellate_path(
+        &path,
+        &options,
+        &mut BuffersBuilder::new(&mut buffers, |v: FillVertex| v.position().to_array())
+    );
+    if res.is_err() { return false; }
+    stream(m, &buffers, f);
+    true
+}
+
+/// Streams polylines with a radius as tri list.
+///
+/// The options match `triangulation::with_polyline_tri_list`:
+/// round joins and caps are flattened as closely as circles with
+/// `resolution` segments, and miters are limited by `MITER_LIMIT`.
+/// Lyon has no pointed caps, so `Shape::Bevel` ends with round caps,
+/// which cover the bevel.
+/// Returns `false` without streaming anything when lyon fails.
+pub fn stroke_tri_list<'a, I, F>(
+    resolution: usize,
+    m: Matrix2d,
+    polylines: I,
+    radius: Scalar,
+    cap: Shape,
+    join: Join,
+    f: F
+) -> bool
+    where
+        I: Iterator<Item = (&'a [Vec2d], bool)>,
+        F: FnMut(&[f32])
+{
+    if !(radius > 0.0) { return true; }
+    let path = match build(polylines) {
+        None => return false,
+        Some(path) => path
+    };
+    let cap = match cap {
+        Shape::Square => LineCap::Butt,
+        Shape::Round | Shape::Bevel => LineCap::Round,
+    };
+    let join = match join {
+        Join::Miter => LineJoin::Miter,
+        Join::Bevel => LineJoin::Bevel,
+        Join::Round => LineJoin::Round,
+    };
+    // The distance between a circle and its chords with `resolution` segments.
+    let half: Scalar = Radians::_180();
+    let tolerance = radius * (1.0 - (half / resolution as Scalar).cos());
+    let options = StrokeOptions::tolerance(tolerance as f32)
+        .with_line_width(2.0 * radius as f32)
+        .with_line_cap(cap)
+        .with_line_join(join)
+        .with_miter_limit(MITER_LIMIT as f32);
+    let mut buffers: VertexBuffers<[f32; 2], u32> = VertexBuffers::new();
+    let res = StrokeTessellator::new().tessellate_path(
+        &path,
+        &options,
+        &mut BuffersBuilder::new(&mut buffers, |v: StrokeVertex| v.position().to_array())
+    );
+    if res.is_err() { return false; }
+    stream(m, &buffers, f);
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use std::num::Float;
+    use super::{ fill_tri_list, stroke_tri_list };
+    use line::{ Join, Shape };
+    use triangulation::{ self, FillRule };
+    use vecmath::{ identity, Scalar, Vec2d };
+
+    /// Counts the samples on a grid over [0, 40] x [0, 40]
+    /// that are inside a triangle.
+    fn coverage(vertices: &[f32]) -> usize {
+        let mut count = 0;
+        for i in 0..160 {
+            for j in 0..160 {
+                let (x, y) = ((i as f32 + 0.5) * 0.25, (j as f32 + 0.5) * 0.25);
+                if vertices.chunks(6).any(|t| {
+                    let edge = |a: usize, b: usize| {
+                        (t[b] - t[a]) * (y - t[a + 1]) - (t[b + 1] - t[a + 1]) * (x - t[a])
+                    };
+                    let (e0, e1, e2) = (edge(0, 2), edge(2, 4), edge(4, 0));
+                    (e0 >= 0.0 && e1 >= 0.0 && e2 >= 0.0)
+                    || (e0 <= 0.0 && e1 <= 0.0 && e2 <= 0.0)
+                }) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// Checks that the coverages differ by at most 2%.
+    fn assert_equivalent(builtin: &[f32], lyon: &[f32]) {
+        let (a, b) = (coverage(builtin), coverage(lyon));
+        assert!(a > 0);
+        let diff = if a > b { a - b } else { b - a };
+        assert!(diff * 50 <= a, "built-in covers {}, lyon covers {}", a, b);
+    }
+
+    #[test]
+    fn test_fill() {
+        let arrow = [[2.0, 2.0], [38.0, 2.0], [20.0, 12.0], [38.0, 38.0], [2.0, 38.0]];
+        let star: Vec<Vec2d> = (0..5).map(|i| {
+            let angle = i as Scalar * 4.0 * 3.14159265 / 5.0;
+            [20.0 + 18.0 * angle.sin(), 20.0 - 18.0 * angle.cos()]
+        }).collect();
+        let outer = [[2.0, 2.0], [38.0, 2.0], [38.0, 38.0], [2.0, 38.0]];
+        let hole = [[10.0, 10.0], [10.0, 30.0], [30.0, 30.0], [30.0, 10.0]];
+        let paths = vec![vec![&arrow[..]], vec![&star[..]], vec![&outer[..], &hole[..]]];
+        for polygons in paths.iter() {
+            let polygons = &polygons[..];
+            for &fill_rule in [FillRule::NonZero, FillRule::EvenOdd].iter() {
+                let mut builtin = Vec::new();
+                triangulation::with_polygons_tri_list(identity(), polygons, fill_rule,
+                    |v| builtin.extend(v.iter().map(|x| *x)));
+                let mut lyon = Vec::new();
+                assert!(fill_tri_list(identity(), polygons.iter().map(|p| *p), fill_rule,
+                    |v| lyon.extend(v.iter().map(|x| *x))));
+                assert_equivalent(&builtin, &lyon);
+            }
+        }
+    }
+
+    #[test]
+    fn test_stroke() {
+        let zigzag = [[4.0, 30.0], [12.0, 8.0], [20.0, 30.0], [28.0, 8.0], [36.0, 30.0]];
+        let square = [[8.0, 8.0], [32.0, 8.0], [32.0, 32.0], [8.0, 32.0]];
+        for &(points, closed) in [(&zigzag[..], false), (&square[..], true)].iter() {
+            for &join in [Join::Miter, Join::Bevel, Join::Round].iter() {
+                for &cap in [Shape::Square, Shape::Round].iter() {
+                    let mut builtin = Vec::new();
+                    triangulation::with_polyline_tri_list(128, identity(), points, closed,
+                        2.0, cap, join, |v| builtin.extend(v.iter().map(|x| *x)));
+                    let mut lyon = Vec::new();
+                    assert!(stroke_tri_list(128, identity(), Some((points, closed)).into_iter(),
+                        2.0, cap, join, |v| lyon.extend(v.iter().map(|x| *x))));
+                    assert_equivalent(&builtin, &lyon);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_chunks() {
+        let circle: Vec<Vec2d> = (0..500).map(|i| {
+            let angle = i as Scalar * 2.0 * 3.14159265 / 500.0;
+            [20.0 + 18.0 * angle.cos(), 20.0 + 18.0 * angle.sin()]
+        }).collect();
+        let mut chunks = Vec::new();
+        assert!(fill_tri_list(identity(), Some(&circle[..]).into_iter(), FillRule::NonZero,
+            |v| chunks.push(v.len())));
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|&n| n <= 720 && n % 6 == 0));
+        // Non-finite points are left to the built-in tessellators.
+        let nan: Scalar = Float::nan();
+        let nan = [[0.0, 0.0], [1.0, 0.0], [nan, 1.0]];
+        assert!(!fill_tri_list(identity(), Some(&nan[..]).into_iter(), FillRule::NonZero,
+            |_| panic!()));
+    }
+}
@@ -0,0 +1,221 @@
+//! Matrix operations and the vertices of common shapes.
+//!
+//! The matrix operations use the `Matrix2d` type of this library,
+//! so code using them does not depend on `vecmath` directly.
+
+use std::vec::Vec;
+use std::num::Float;
+use radians::Radians;
+use internal::{ Radius, Rectangle, Scalar, Vec2d };
+use vecmath::Matrix2d;
+
+pub use color::lerp as lerp_color;
+
+pub use vecmath::{
+    compose,
+    decompose,
+    identity,
+    invert,
+    multiply,
+    orient,
+    rotate_radians,
+    scale,
+    shear,
+    translate,
+};
+
+/// Transforms a position by a matrix, including translation.
+#[inline(always)]
+pub fn transform_pos(m: Matrix2d, pos: Vec2d) -> Vec2d {
+    [
+        m[0][0] * pos[0] + m[0][1] * pos[1] + m[0][2],
+        m[1][0] * pos[0] + m[1][1] * pos[1] + m[1][2]
+    ]
+}
+
+/// Transforms a vector by a matrix, ignoring translation.
+#[inline(always)]
+pub fn transform_vec(m: Matrix2d, v: Vec2d) -> Vec2d {
+    [
+        m[0][0] * v[0] + m[0][1] * v[1],
+        m[1][0] * v[0] + m[1][1] * v[1]
+    ]
+}
+
+/// Interpolates linearly between two rectangles, component by component.
+pub fn lerp_rect(a: Rectangle, b: Rectangle, t: Scalar) -> Rectangle {
+    [
+        a[0] + (b[0] - a[0]) * t,
+        a[1] + (b[1] - a[1]) * t,
+        a[2] + (b[2] - a[2]) * t,
+        a[3] + (b[3] - a[3]) * t
+    ]
+}
+
+/// Interpolates between two transforms, for animations.
+///
+/// Each matrix is split with `decompose` into translation,
+/// rotation and scale, which are interpolated separately and combined
+/// with `compose`. The rotation takes the shortest way around,
+/// so a rotation stays a rotation, unlike interpolating the matrices
+/// component by component. Shear is lost, see `decompose`,
+/// and a flip passes through a scale of zero.
+pub fn interpolate_transform(a: Matrix2d, b: Matrix2d, t: Scalar) -> Matrix2d {
+    let (ta, ra, sa) = decompose(a);
+    let (tb, rb, sb) = decompose(b);
+    let full: Scalar = Radians::_360();
+    let half: Scalar = Radians::_180();
+    // The difference in angle between -180 and 180 degrees.
+    let d = rb - ra;
+    let d = d - full * ((d + half) / full).floor();
+    let lerp = |a: Vec2d, b: Vec2d| [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t];
+    compose(lerp(ta, tb), ra + d * t, lerp(sa, sb))
+}
+
+/// Returns the corners of a regular polygon.
+///
+/// The first corner is at the `rotation` angle in radians from the x axis.
+/// Fewer than 3 sides are clamped to 3.
+pub fn regular_polygon(
+    center: Vec2d,
+    radius: Radius,
+    sides: usize,
+    rotation: Scalar
+) -> Vec<Vec2d> {
+    let sides = if sides < 3 { 3 } else { sides };
+    let full: Scalar = Radians::_360();
+    (0..sides).map(|i| {
+        let angle = rotation + full * i as Scalar / sides as Scalar;
+        [center[0] + radius * angle.cos(), center[1] + radius * angle.sin()]
+    }).collect()
+}
+
+/// Returns the points of a circular arc, from the `start` to the `end`
+/// angle in radians, including both ends.
+///
+/// Uses the same points as sectors and rings drawn with
+/// `resolution` segments in a full circle, see `triangulation::arc_segments`.
+pub fn arc_points(
+    center: Vec2d,
+    radius: Radius,
+    start: Scalar,
+    end: Scalar,
+    resolution: usize
+) -> Vec<Vec2d> {
+    use triangulation::{ arc_segments, unit_circle };
+
+    let n = arc_segments(resolution, start, end);
+    (0..n + 1).map(|i| {
+        let p = unit_circle(start + (end - start) * i as Scalar / n as Scalar);
+        [center[0] + radius * p[0], center[1] + radius * p[1]]
+    }).collect()
+}
+
+/// Returns the corners of a star.
+///
+/// The corners alternate between the outer and inner radius,
+/// starting with an outer point at the `rotation` angle in radians.
+/// Fewer than 2 points are clamped to 2.
+pub fn star(
+    center: Vec2d,
+    outer_radius: Radius,
+    inner_radius: Radius,
+    points: usize,
+    rotation: Scalar
+) -> Vec<Vec2d> {
+    let points = if points < 2 { 2 } else { points };
+    let n = 2 * points;
+    let full: Scalar = Radians::_360();
+    (0..n).map(|i| {
+        let angle = rotation + full * i as Scalar / n as Scalar;
+        let radius = if i % 2 == 0 { outer_radius } else { inner_radius };
+        [center[0] + radius * angle.cos(), center[1] + radius * angle.sin()]
+    }).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ arc_points, regular_polygon, star };
+    use super::{ multiply, rotate_radians, transform_pos, transform_vec, translate };
+    use std::num::Float;
+    use vecmath::Scalar;
+
+    #[test]
+    fn test_arc_points() {
+        use radians::Radians;
+
+        let pi: Scalar = Radians::_180();
+        let points = arc_points([1.0, 2.0], 2.0, 0.0, pi, 128);
+        assert_eq!(points.len(), 65);
+        let (first, last) = (points[0], points[64]);
+        assert!((first[0] - 3.0).abs() < 0.0001 && (first[1] - 2.0).abs() < 0.0001);
+        assert!((last[0] + 1.0).abs() < 0.0001 && (last[1] - 2.0).abs() < 0.0001);
+        assert_eq!(arc_points([0.0, 0.0], 1.0, 0.0, 0.0, 128).len(), 2);
+    }
+
+    #[test]
+    fn test_regular_polygon() {
+        let hexagon = regular_polygon([1.0, 2.0], 2.0, 6, 0.0);
+        let expected = [
+            [3.0, 2.0], [2.0, 2.0 + (3.0 as Scalar).sqrt()], [0.0, 2.0 + (3.0 as Scalar).sqrt()],
+            [-1.0, 2.0], [0.0, 2.0 - (3.0 as Scalar).sqrt()], [2.0, 2.0 - (3.0 as Scalar).sqrt()]
+        ];
+        assert_eq!(hexagon.len(), 6);
+        for (a, b) in hexagon.iter().zip(expected.iter()) {
+            assert!((a[0] - b[0]).abs() < 0.00001);
+            assert!((a[1] - b[1]).abs() < 0.00001);
+        }
+        assert_eq!(regular_polygon([0.0, 0.0], 1.0, 1, 0.0).len(), 3);
+    }
+
+    #[test]
+    fn test_star() {
+        let s = star([0.0, 0.0], 2.0, 1.0, 5, 0.0);
+        assert_eq!(s.len(), 10);
+        for (i, p) in s.iter().enumerate() {
+            let r = (p[0] * p[0] + p[1] * p[1]).sqrt();
+            let expected = if i % 2 == 0 { 2.0 } else { 1.0 };
+            assert!((r - expected).abs() < 0.00001);
+        }
+        assert_eq!(star([0.0, 0.0], 2.0, 1.0, 0, 0.0).len(), 4);
+    }
+
+    #[test]
+    fn test_interpolate_transform() {
+        use super::{ decompose, interpolate_transform, lerp_rect };
+
+        let (a, b) = (rotate_radians(0.3), rotate_radians(2.5));
+        for i in 0..11 {
+            let t = i as Scalar / 10.0;
+            let m = interpolate_transform(a, b, t);
+            // A pure rotation by the interpolated angle.
+            assert!((m[0][0] - m[1][1]).abs() < 0.00001);
+            assert!((m[0][1] + m[1][0]).abs() < 0.00001);
+            assert!((m[0][0] * m[0][0] + m[1][0] * m[1][0] - 1.0).abs() < 0.00001);
+            assert_eq!([m[0][2], m[1][2]], [0.0, 0.0]);
+            let (_, angle, _) = decompose(m);
+            assert!((angle - (0.3 + 2.2 * t)).abs() < 0.00001);
+        }
+        // The shortest way from 3 to -3 radians passes 180 degrees.
+        let m = interpolate_transform(rotate_radians(3.0), rotate_radians(-3.0), 0.5);
+        assert!((m[0][0] + 1.0).abs() < 0.00001);
+        assert!(m[1][0].abs() < 0.00001);
+        let m = interpolate_transform(translate([0.0, 0.0]), translate([10.0, 20.0]), 0.25);
+        assert!((m[0][2] - 2.5).abs() < 0.00001 && (m[1][2] - 5.0).abs() < 0.00001);
+        assert_eq!(lerp_rect([0.0; 4], [2.0, 4.0, 6.0, 8.0], 0.5), [1.0, 2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn test_transform() {
+        let pi = ::std::f64::consts::PI as Scalar;
+        let m = multiply(translate([10.0, 20.0]), rotate_radians(0.5 * pi));
+        let p = transform_pos(m, [1.0, 0.0]);
+        assert!((p[0] - 10.0).abs() < 0.00001);
+        assert!((p[1] - 21.0).abs() < 0.00001);
+        let v = transform_vec(m, [1.0, 0.0]);
+        assert!(v[0].abs() < 0.00001);
+        assert!((v[1] - 1.0).abs() < 0.00001);
+        assert_eq!(transform_vec(translate([10.0, 20.0]), [3.0, 4.0]), [3.0, 4.0]);
+        assert_eq!(transform_pos(translate([10.0, 20.0]), [3.0, 4.0]), [13.0, 24.0]);
+    }
+}
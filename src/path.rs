@@ -0,0 +1,1067 @@
+//! Build compound shapes from lines and curves
+
+use std::string::String;
+use std::vec::Vec;
+use std::num::Float;
+use internal;
+use radians::Radians;
+use triangulation;
+use triangulation::{ FillRule, TessellationBuffers };
+use vecmath::{ Scalar, Vec2d };
+use Graphics;
+use Context;
+use Line;
+use line::Join;
+#[cfg(feature = "lyon")]
+use lyon;
+
+/// The number of segments in a full circle for round joins and caps,
+/// the same as the round ends of `Line`.
+const STROKE_RESOLUTION: usize = 128;
+
+/// A sequence of connected points
+#[derive(Clone)]
+pub struct SubPath {
+    /// The points of the sub-path.
+    pub points: Vec<Vec2d>,
+    /// Whether the sub-path is closed.
+    pub closed: bool,
+}
+
+/// An error when parsing SVG path data
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParseError {
+    /// The byte offset of the offending token.
+    pub offset: usize,
+    /// The offending token, empty at end of data.
+    pub token: String,
+}
+
+/// A path consisting of sub-paths
+///
+/// With the `lyon` feature, `fill`, `stroke` and `stroke_dashed`
+/// are tessellated by `lyon_tessellation`, and by the built-in
+/// tessellators when lyon fails.
+#[derive(Clone)]
+pub struct Path {
+    /// The sub-paths.
+    pub subpaths: Vec<SubPath>,
+    /// The number of line segments used for each curve.
+    pub resolution: usize,
+    /// The rule deciding which areas are filled.
+    pub fill_rule: FillRule,
+    /// How the segments meet when stroked.
+    pub join: Join,
+}
+
+/// A path built from commands, then filled or stroked.
+///
+/// This is the same type as `Path`, which records sub-paths of points
+/// and flattens curves into `resolution` line segments as they are added.
+pub type PathBuilder = Path;
+
+impl Path {
+    /// Creates a new empty path.
+    pub fn new() -> Path {
+        Path {
+            subpaths: Vec::new(),
+            resolution: 16,
+            fill_rule: FillRule::NonZero,
+            join: Join::Miter,
+        }
+    }
+
+    /// Starts a new sub-path at a point.
+    pub fn move_to(mut self, x: Scalar, y: Scalar) -> Path {
+        self.subpaths.push(SubPath {
+            points: vec![[x, y]],
+            closed: false,
+        });
+        self
+    }
+
+    /// Adds a line from the current point.
+    ///
+    /// Starts a new sub-path if there is no current point.
+    pub fn line_to(mut self, x: Scalar, y: Scalar) -> Path {
+        match self.current() {
+            None => return self.move_to(x, y),
+            Some(_) => {}
+        }
+        self.push(x, y);
+        self
+    }
+
+    /// Adds a quadratic bezier curve from the current point.
+    pub fn quad_to(
+        mut self,
+        cx: Scalar, cy: Scalar,
+        x: Scalar, y: Scalar
+    ) -> Path {
+        let p0 = match self.current() {
+            None => return self.move_to(x, y),
+            Some(p) => p
+        };
+        let n = self.resolution;
+        for i in 1..n + 1 {
+            let t = i as Scalar / n as Scalar;
+            let u = 1.0 - t;
+            self.push(
+                u * u * p0[0] + 2.0 * u * t * cx + t * t * x,
+                u * u * p0[1] + 2.0 * u * t * cy + t * t * y
+            );
+        }
+        self
+    }
+
+    /// Adds a cubic bezier curve from the current point.
+    pub fn cubic_to(
+        mut self,
+        c1x: Scalar, c1y: Scalar,
+        c2x: Scalar, c2y: Scalar,
+        x: Scalar, y: Scalar
+    ) -> Path {
+        let p0 = match self.current() {
+            None => return self.move_to(x, y),
+            Some(p) => p
+        };
+        let n = self.resolution;
+        for i in 1..n + 1 {
+            let t = i as Scalar / n as Scalar;
+            let u = 1.0 - t;
+            let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+            self.push(
+                a * p0[0] + b * c1x + c * c2x + d * x,
+                a * p0[1] + b * c1y + c * c2y + d * y
+            );
+        }
+        self
+    }
+
+    /// Adds a cubic bezier curve from the current point.
+    ///
+    /// Same as `cubic_to`.
+    pub fn curve_to(
+        self,
+        c1x: Scalar, c1y: Scalar,
+        c2x: Scalar, c2y: Scalar,
+        x: Scalar, y: Scalar
+    ) -> Path {
+        self.cubic_to(c1x, c1y, c2x, c2y, x, y)
+    }
+
+    /// Closes the current sub-path.
+    ///
+    /// The next command starts a new sub-path at the
+    /// start of the closed one, unless it is a `move_to`.
+    pub fn close(mut self) -> Path {
+        match self.subpaths.last_mut() {
+            None => {}
+            Some(subpath) => subpath.closed = true
+        }
+        self
+    }
+
+    /// Adds an elliptical arc from the current point, as in SVG.
+    ///
+    /// The arc is converted to cubic bezier curves of at most 90 degrees.
+    /// The rotation of the x axis is in degrees.
+    pub fn arc_to(
+        self,
+        rx: Scalar, ry: Scalar,
+        x_axis_rotation: Scalar,
+        large_arc: bool,
+        sweep: bool,
+        x: Scalar, y: Scalar
+    ) -> Path {
+        let p0 = match self.current() {
+            None => return self.move_to(x, y),
+            Some(p) => p
+        };
+        if p0 == [x, y] { return self; }
+        let (mut rx, mut ry) = (rx.abs(), ry.abs());
+        if rx == 0.0 || ry == 0.0 { return self.line_to(x, y); }
+
+        // Convert from endpoint to center parameterization.
+        // Source: http://www.w3.org/TR/SVG/implnote.html#ArcImplementationNotes
+        let pi: Scalar = Radians::_180();
+        let phi = x_axis_rotation * pi / 180.0;
+        let (cos_phi, sin_phi) = (phi.cos(), phi.sin());
+        let (dx2, dy2) = (0.5 * (p0[0] - x), 0.5 * (p0[1] - y));
+        let x1 = cos_phi * dx2 + sin_phi * dy2;
+        let y1 = -sin_phi * dx2 + cos_phi * dy2;
+        // Scale up radii that are too small to reach the end point.
+        let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+        if lambda > 1.0 {
+            let s = lambda.sqrt();
+            rx *= s;
+            ry *= s;
+        }
+        let num = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+        let den = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+        let coef = if num <= 0.0 || den == 0.0 { 0.0 } else { (num / den).sqrt() };
+        let coef = if large_arc == sweep { -coef } else { coef };
+        let cx1 = coef * rx * y1 / ry;
+        let cy1 = -coef * ry * x1 / rx;
+        let cx = cos_phi * cx1 - sin_phi * cy1 + 0.5 * (p0[0] + x);
+        let cy = sin_phi * cx1 + cos_phi * cy1 + 0.5 * (p0[1] + y);
+        let angle = |ux: Scalar, uy: Scalar, vx: Scalar, vy: Scalar| {
+            (ux * vy - uy * vx).atan2(ux * vx + uy * vy)
+        };
+        let (ux, uy) = ((x1 - cx1) / rx, (y1 - cy1) / ry);
+        let (vx, vy) = ((-x1 - cx1) / rx, (-y1 - cy1) / ry);
+        let start = angle(1.0, 0.0, ux, uy);
+        let mut delta = angle(ux, uy, vx, vy);
+        if !sweep && delta > 0.0 { delta -= 2.0 * pi; }
+        if sweep && delta < 0.0 { delta += 2.0 * pi; }
+
+        // Split into segments of at most 90 degrees.
+        let n = (delta.abs() / (0.5 * pi)).ceil();
+        let n = if n < 1.0 { 1 } else { n as usize };
+        let step = delta / n as Scalar;
+        let t = 4.0 / 3.0 * (0.25 * step).tan();
+        let map = |ex: Scalar, ey: Scalar| -> Vec2d {
+            [cx + rx * cos_phi * ex - ry * sin_phi * ey,
+             cy + rx * sin_phi * ex + ry * cos_phi * ey]
+        };
+        let mut path = self;
+        for i in 0..n {
+            let a1 = start + i as Scalar * step;
+            let a2 = a1 + step;
+            let (cos1, sin1) = (a1.cos(), a1.sin());
+            let (cos2, sin2) = (a2.cos(), a2.sin());
+            let c1 = map(cos1 - t * sin1, sin1 + t * cos1);
+            let c2 = map(cos2 + t * sin2, sin2 - t * cos2);
+            let end = if i == n - 1 { [x, y] } else { map(cos2, sin2) };
+            path = path.cubic_to(c1[0], c1[1], c2[0], c2[1], end[0], end[1]);
+        }
+        path
+    }
+
+    /// Parses SVG path data, such as the `d` attribute of a path element.
+    ///
+    /// Supports all path commands, including implicit repetition.
+    /// Numbers can be separated by whitespace and commas.
+    pub fn parse_svg(d: &str) -> Result<Path, ParseError> {
+        let mut parser = Parser { text: d, pos: 0 };
+        let mut path = Path::new();
+        let mut command: Option<u8> = None;
+        // The current point and sub-path start point.
+        let mut current = [0.0, 0.0];
+        let mut start = [0.0, 0.0];
+        // The last control point of a cubic or quadratic curve.
+        let mut last_cubic: Option<Vec2d> = None;
+        let mut last_quad: Option<Vec2d> = None;
+        loop {
+            parser.skip_separators();
+            let offset = parser.pos;
+            let c = match parser.peek() {
+                None => break,
+                Some(c) => c
+            };
+            let cmd = if (c as char).is_alphabetic() {
+                parser.pos += 1;
+                c
+            } else {
+                match command {
+                    // Moves are repeated as lines.
+                    Some(b'M') if parser.at_number() => b'L',
+                    Some(b'm') if parser.at_number() => b'l',
+                    Some(b'Z') | Some(b'z') | None => return Err(parser.error(offset)),
+                    Some(cmd) if parser.at_number() => cmd,
+                    Some(_) => return Err(parser.error(offset)),
+                }
+            };
+            if command.is_none() && cmd != b'M' && cmd != b'm' {
+                return Err(parser.error(offset));
+            }
+            let rel = (cmd as char).is_lowercase();
+            let (ox, oy) = if rel { (current[0], current[1]) } else { (0.0, 0.0) };
+            let (mut next_cubic, mut next_quad) = (None, None);
+            match cmd {
+                b'M' | b'm' => {
+                    let x = ox + try!(parser.number());
+                    let y = oy + try!(parser.number());
+                    path = path.move_to(x, y);
+                    current = [x, y];
+                    start = current;
+                }
+                b'L' | b'l' => {
+                    let x = ox + try!(parser.number());
+                    let y = oy + try!(parser.number());
+                    path = path.line_to(x, y);
+                    current = [x, y];
+                }
+                b'H' | b'h' => {
+                    let x = ox + try!(parser.number());
+                    path = path.line_to(x, current[1]);
+                    current = [x, current[1]];
+                }
+                b'V' | b'v' => {
+                    let y = oy + try!(parser.number());
+                    path = path.line_to(current[0], y);
+                    current = [current[0], y];
+                }
+                b'C' | b'c' | b'S' | b's' => {
+                    let c1 = if cmd == b'C' || cmd == b'c' {
+                        let x = ox + try!(parser.number());
+                        let y = oy + try!(parser.number());
+                        [x, y]
+                    } else {
+                        match last_cubic {
+                            None => current,
+                            Some(p) => [2.0 * current[0] - p[0], 2.0 * current[1] - p[1]]
+                        }
+                    };
+                    let c2x = ox + try!(parser.number());
+                    let c2y = oy + try!(parser.number());
+                    let x = ox + try!(parser.number());
+                    let y = oy + try!(parser.number());
+                    path = path.cubic_to(c1[0], c1[1], c2x, c2y, x, y);
+                    next_cubic = Some([c2x, c2y]);
+                    current = [x, y];
+                }
+                b'Q' | b'q' | b'T' | b't' => {
+                    let c1 = if cmd == b'Q' || cmd == b'q' {
+                        let x = ox + try!(parser.number());
+                        let y = oy + try!(parser.number());
+                        [x, y]
+                    } else {
+                        match last_quad {
+                            None => current,
+                            Some(p) => [2.0 * current[0] - p[0], 2.0 * current[1] - p[1]]
+                        }
+                    };
+                    let x = ox + try!(parser.number());
+                    let y = oy + try!(parser.number());
+                    path = path.quad_to(c1[0], c1[1], x, y);
+                    next_quad = Some(c1);
+                    current = [x, y];
+                }
+                b'A' | b'a' => {
+                    let rx = try!(parser.number());
+                    let ry = try!(parser.number());
+                    let rotation = try!(parser.number());
+                    let large_arc = try!(parser.flag());
+                    let sweep = try!(parser.flag());
+                    let x = ox + try!(parser.number());
+                    let y = oy + try!(parser.number());
+                    path = path.arc_to(rx, ry, rotation, large_arc, sweep, x, y);
+                    current = [x, y];
+                }
+                b'Z' | b'z' => {
+                    path = path.close();
+                    current = start;
+                }
+                _ => return Err(parser.error(offset))
+            }
+            last_cubic = next_cubic;
+            last_quad = next_quad;
+            command = Some(cmd);
+        }
+        Ok(path)
+    }
+
+    /// Fills the path using the fill rule of the path.
+    ///
+    /// All sub-paths are treated as closed.
+    /// Under the non-zero rule, a sub-path with opposite
+    /// orientation inside another becomes a hole.
+    /// A single convex sub-path is drawn as a triangle fan,
+    /// other paths allocate for the tessellation, see `fill_with_buffers`.
+    pub fn fill<B>(
+        &self,
+        color: internal::Color,
+        c: &Context,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        self.fill_with_buffers(color, &mut TessellationBuffers::new(), c, back_end);
+    }
+
+    /// Fills the path, reusing buffers for the tessellation.
+    ///
+    /// Keep the buffers between frames when filling
+    /// many paths, to avoid allocating for every fill.
+    pub fn fill_with_buffers<B>(
+        &self,
+        color: internal::Color,
+        buffers: &mut TessellationBuffers,
+        c: &Context,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        let color = c.apply_alpha(color);
+        if c.skips(color) { return; }
+        let convex = self.subpaths.len() == 1
+            && triangulation::is_convex(&self.subpaths[0].points);
+        back_end.tri_list(
+            &c.draw_state,
+            &color,
+            |f| {
+                #[cfg(feature = "lyon")]
+                {
+                    if lyon::fill_tri_list(
+                        c.transform,
+                        self.subpaths.iter().map(|subpath| &subpath.points[..]),
+                        self.fill_rule,
+                        |vertices| f.emit(vertices)
+                    ) { return; }
+                }
+                if convex {
+                    triangulation::with_polygon_tri_list(
+                        c.transform,
+                        &self.subpaths[0].points,
+                        |vertices| f.emit(vertices)
+                    )
+                } else {
+                    triangulation::stream_polygons_tri_list_buffered(
+                        buffers,
+                        c.transform,
+                        self.subpaths.iter().map(|subpath| &subpath.points[..]),
+                        self.fill_rule,
+                        |vertices| f.emit(vertices)
+                    )
+                }
+            }
+        );
+    }
+
+    /// Strokes the path with a line, in one call to the back-end.
+    ///
+    /// The segments meet with the join of the path,
+    /// and open sub-paths end with the shape of the line.
+    /// See `triangulation::with_polyline_tri_list`.
+    /// The feathered edge of the line is not drawn.
+    pub fn stroke<B>(
+        &self,
+        line: &Line,
+        c: &Context,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        let color = c.apply_alpha(line.color);
+        if c.skips(color) { return; }
+        back_end.tri_list(
+            &c.draw_state,
+            &color,
+            |f| {
+                #[cfg(feature = "lyon")]
+                {
+                    if lyon::stroke_tri_list(
+                        STROKE_RESOLUTION,
+                        c.transform,
+                        self.subpaths.iter().map(|subpath| (&subpath.points[..], subpath.closed)),
+                        line.radius,
+                        line.shape,
+                        self.join,
+                        |vertices| f.emit(vertices)
+                    ) { return; }
+                }
+                for subpath in self.subpaths.iter() {
+                    triangulation::with_polyline_tri_list(
+                        STROKE_RESOLUTION,
+                        c.transform,
+                        &subpath.points,
+                        subpath.closed,
+                        line.radius,
+                        line.shape,
+                        self.join,
+                        |vertices| f.emit(vertices)
+                    )
+                }
+            }
+        );
+    }
+
+    /// Strokes the path with dashes, in one call to the back-end.
+    ///
+    /// The pattern alternates the lengths of dashes and gaps,
+    /// and is repeated twice if it has an odd number of lengths, as in SVG.
+    /// It starts `offset` units in and restarts at every sub-path.
+    /// Dashes are joined at the corners they span, with the join of
+    /// the path, and end with the shape of the line.
+    /// Patterns with negative or non-finite lengths, or no length at all,
+    /// stroke solid lines, see `stroke`.
+    pub fn stroke_dashed<B>(
+        &self,
+        line: &Line,
+        pattern: &[Scalar],
+        offset: Scalar,
+        c: &Context,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        let total = pattern.iter().fold(0.0, |sum, &x| sum + x);
+        if !(total > 0.0 && total.is_finite()) || pattern.iter().any(|&x| !(x >= 0.0)) {
+            return self.stroke(line, c, back_end);
+        }
+        let color = c.apply_alpha(line.color);
+        if c.skips(color) { return; }
+        let mut dash = Vec::new();
+        back_end.tri_list(
+            &c.draw_state,
+            &color,
+            |f| {
+                #[cfg(feature = "lyon")]
+                {
+                    let mut dashes = Vec::new();
+                    for subpath in self.subpaths.iter() {
+                        each_dash(&subpath.points, subpath.closed, pattern, offset, &mut dash,
+                            |points| dashes.push(points.to_vec()));
+                    }
+                    if lyon::stroke_tri_list(
+                        STROKE_RESOLUTION,
+                        c.transform,
+                        dashes.iter().map(|points| (&points[..], false)),
+                        line.radius,
+                        line.shape,
+                        self.join,
+                        |vertices| f.emit(vertices)
+                    ) { return; }
+                }
+                for subpath in self.subpaths.iter() {
+                    each_dash(&subpath.points, subpath.closed, pattern, offset, &mut dash, |points| {
+                        triangulation::with_polyline_tri_list(
+                            STROKE_RESOLUTION,
+                            c.transform,
+                            points,
+                            false,
+                            line.radius,
+                            line.shape,
+                            self.join,
+                            |vertices| f.emit(vertices)
+                        )
+                    });
+                }
+            }
+        );
+    }
+
+    /// Returns the length of the path,
+    /// including the closing segments of closed sub-paths.
+    ///
+    /// Curves are measured by the line segments they are flattened into,
+    /// which are the segments drawn, so the length of a curve
+    /// approaches the exact length with a higher `resolution`.
+    pub fn length(&self) -> Scalar {
+        let mut length = 0.0;
+        self.each_segment(|a, b| {
+            let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+            length += (dx * dx + dy * dy).sqrt();
+            false
+        });
+        length
+    }
+
+    /// Returns the point and the unit tangent at a distance along the path.
+    ///
+    /// The distance is measured along the drawn segments, see `length`,
+    /// through the sub-paths in order, without the gaps between them.
+    /// Returns `None` for negative distances and distances past the end,
+    /// so animations can tell when they are done.
+    pub fn point_at_distance(&self, distance: Scalar) -> Option<(Vec2d, Vec2d)> {
+        if !(distance >= 0.0) { return None; }
+        let mut travelled = 0.0;
+        let mut res = None;
+        self.each_segment(|a, b| {
+            let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+            let len = (dx * dx + dy * dy).sqrt();
+            if len == 0.0 { return false; }
+            if distance <= travelled + len {
+                let t = (distance - travelled) / len;
+                res = Some(([a[0] + t * dx, a[1] + t * dy], [dx / len, dy / len]));
+                return true;
+            }
+            travelled += len;
+            false
+        });
+        res
+    }
+
+    /// Calls a closure with the end points of every line segment,
+    /// including the closing segments, until it returns true.
+    fn each_segment<F>(&self, mut f: F)
+        where F: FnMut(Vec2d, Vec2d) -> bool
+    {
+        for subpath in self.subpaths.iter() {
+            let points = &subpath.points[..];
+            let n = points.len();
+            if n < 2 { continue; }
+            for i in 0..n - 1 {
+                if f(points[i], points[i + 1]) { return; }
+            }
+            if subpath.closed {
+                if f(points[n - 1], points[0]) { return; }
+            }
+        }
+    }
+
+    /// Returns the current point.
+    fn current(&self) -> Option<Vec2d> {
+        match self.subpaths.last() {
+            None => None,
+            Some(subpath) if subpath.closed => Some(subpath.points[0]),
+            Some(subpath) => subpath.points.last().map(|p| *p),
+        }
+    }
+
+    /// Adds a point to the current sub-path.
+    fn push(&mut self, x: Scalar, y: Scalar) {
+        let start = match self.subpaths.last() {
+            None => None,
+            Some(subpath) if subpath.closed => Some(subpath.points[0]),
+            Some(_) => None,
+        };
+        match start {
+            Some(p) => {
+                self.subpaths.push(SubPath {
+                    points: vec![p],
+                    closed: false,
+                });
+            }
+            None => {}
+        }
+        self.subpaths.last_mut().unwrap().points.push([x, y]);
+    }
+}
+
+/// Calls a closure with the points of every dash along a polyline,
+/// using `dash` as a buffer.
+///
+/// The pattern must have a positive and finite total length,
+/// see `Path::stroke_dashed`.
+fn each_dash<F>(
+    points: &[Vec2d],
+    closed: bool,
+    pattern: &[Scalar],
+    offset: Scalar,
+    dash: &mut Vec<Vec2d>,
+    mut f: F
+)
+    where F: FnMut(&[Vec2d])
+{
+    let n = points.len();
+    if n == 0 { return; }
+    // Skip the offset, with odd patterns repeated twice.
+    let period = pattern.iter().fold(0.0, |sum, &x| sum + x)
+        * if pattern.len() % 2 == 0 { 1.0 } else { 2.0 };
+    let mut phase = offset % period;
+    if phase < 0.0 { phase += period; }
+    let mut k = 0;
+    let mut on = true;
+    // A zero dash at the offset is kept, to be drawn as a dot.
+    while phase > pattern[k] || (phase == pattern[k] && phase > 0.0) {
+        phase -= pattern[k];
+        k = (k + 1) % pattern.len();
+        on = !on;
+    }
+    let mut left = pattern[k] - phase;
+
+    dash.clear();
+    if on { dash.push(points[0]); }
+    let segments = if closed { n } else { n - 1 };
+    for i in 0..segments {
+        let (a, b) = (points[i], points[(i + 1) % n]);
+        let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+        let len = (dx * dx + dy * dy).sqrt();
+        let mut travelled = 0.0;
+        while len - travelled > left {
+            travelled += left;
+            let t = travelled / len;
+            dash.push([a[0] + t * dx, a[1] + t * dy]);
+            if on {
+                f(&dash[..]);
+                dash.clear();
+            }
+            on = !on;
+            k = (k + 1) % pattern.len();
+            left = pattern[k];
+        }
+        left -= len - travelled;
+        if on { dash.push(b); }
+    }
+    if on && dash.len() >= 2 { f(&dash[..]); }
+}
+
+/// Returns points along a polyline, every `spacing` units of arc length.
+///
+/// Starts at the first point and ends at the last point,
+/// which is included even if the remaining distance is shorter.
+/// A spacing larger than the total length gives just the endpoints.
+/// Returns the points unchanged if the spacing is not positive.
+pub fn resample(points: &[Vec2d], spacing: Scalar) -> Vec<Vec2d> {
+    if points.len() == 0 || !(spacing > 0.0) {
+        return points.iter().map(|p| *p).collect();
+    }
+    let mut res = vec![points[0]];
+    let mut travelled = 0.0;
+    let mut next = spacing;
+    for i in 1..points.len() {
+        let (a, b) = (points[i - 1], points[i]);
+        let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+        let len = (dx * dx + dy * dy).sqrt();
+        while next <= travelled + len {
+            let t = (next - travelled) / len;
+            res.push([a[0] + t * dx, a[1] + t * dy]);
+            next += spacing;
+        }
+        travelled += len;
+    }
+    // Add the last point unless the last sample landed on it.
+    if travelled - (next - spacing) > spacing * 0.000001 {
+        res.push(points[points.len() - 1]);
+    }
+    res
+}
+
+/// Reads tokens from SVG path data.
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.pos).map(|c| *c)
+    }
+
+    fn skip_separators(&mut self) {
+        loop {
+            match self.peek() {
+                Some(b' ') | Some(b'\t') | Some(b'\n') | Some(b'\r') | Some(b',') => {
+                    self.pos += 1;
+                }
+                _ => break
+            }
+        }
+    }
+
+    fn at_number(&mut self) -> bool {
+        self.skip_separators();
+        match self.peek() {
+            Some(c) => c == b'-' || c == b'+' || c == b'.' || (b'0' <= c && c <= b'9'),
+            None => false
+        }
+    }
+
+    fn error(&self, offset: usize) -> ParseError {
+        ParseError {
+            offset: offset,
+            token: self.text[offset..].chars()
+                .take_while(|&c| !c.is_whitespace() && c != ',')
+                .collect(),
+        }
+    }
+
+    fn number(&mut self) -> Result<Scalar, ParseError> {
+        self.skip_separators();
+        let text = self.text;
+        let bytes = text.as_bytes();
+        let is_digit = |i: usize| i < bytes.len() && b'0' <= bytes[i] && bytes[i] <= b'9';
+        let start = self.pos;
+        let mut i = start;
+        if i < bytes.len() && (bytes[i] == b'-' || bytes[i] == b'+') { i += 1; }
+        let mut digits = 0;
+        while is_digit(i) { i += 1; digits += 1; }
+        if i < bytes.len() && bytes[i] == b'.' {
+            i += 1;
+            while is_digit(i) { i += 1; digits += 1; }
+        }
+        if digits == 0 { return Err(self.error(start)); }
+        if i < bytes.len() && (bytes[i] == b'e' || bytes[i] == b'E') {
+            let mut j = i + 1;
+            if j < bytes.len() && (bytes[j] == b'-' || bytes[j] == b'+') { j += 1; }
+            if is_digit(j) {
+                while is_digit(j) { j += 1; }
+                i = j;
+            }
+        }
+        match text[start..i].parse() {
+            Ok(val) => {
+                self.pos = i;
+                Ok(val)
+            }
+            Err(_) => Err(self.error(start))
+        }
+    }
+
+    fn flag(&mut self) -> Result<bool, ParseError> {
+        self.skip_separators();
+        let res = match self.peek() {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(self.error(self.pos))
+        };
+        self.pos += 1;
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Path;
+    use std::num::Float;
+    use triangulation;
+    use vecmath::{ identity, inside_triangle, Scalar, Vec2d };
+
+    #[test]
+    fn test_path() {
+        let path = Path::new()
+            .move_to(0.0, 0.0)
+            .line_to(10.0, 0.0)
+            .quad_to(15.0, 5.0, 10.0, 10.0)
+            .cubic_to(8.0, 12.0, 2.0, 12.0, 0.0, 10.0)
+            .close()
+            .line_to(-5.0, 5.0);
+        assert_eq!(path.subpaths.len(), 2);
+        assert_eq!(path.subpaths[0].points.len(), 2 + 2 * path.resolution);
+        assert!(path.subpaths[0].closed);
+        assert_eq!(path.subpaths[0].points[1 + path.resolution], [10.0, 10.0]);
+        assert_eq!(*path.subpaths[0].points.last().unwrap(), [0.0, 10.0]);
+        assert_eq!(path.subpaths[1].points, vec![[0.0, 0.0], [-5.0, 5.0]]);
+    }
+
+    #[test]
+    fn test_point_at_distance() {
+        // A circle of radius 10 from four cubic curves, counter-clockwise
+        // from the positive x axis with the y axis pointing down.
+        let k = 10.0 * 0.5522847498;
+        let circle = Path::new()
+            .move_to(10.0, 0.0)
+            .cubic_to(10.0, -k, k, -10.0, 0.0, -10.0)
+            .cubic_to(-k, -10.0, -10.0, -k, -10.0, 0.0)
+            .cubic_to(-10.0, k, -k, 10.0, 0.0, 10.0)
+            .cubic_to(k, 10.0, 10.0, k, 10.0, 0.0)
+            .close();
+        let length = circle.length();
+        assert!((length - 20.0 * 3.14159265).abs() < 0.1);
+        let expected = [[10.0, 0.0], [0.0, -10.0], [-10.0, 0.0], [0.0, 10.0], [10.0, 0.0]];
+        for (i, e) in expected.iter().enumerate() {
+            let (p, _) = circle.point_at_distance(length * i as Scalar / 4.0).unwrap();
+            assert!((p[0] - e[0]).abs() < 0.0001 && (p[1] - e[1]).abs() < 0.0001);
+        }
+        let (_, tangent) = circle.point_at_distance(0.0).unwrap();
+        assert!(tangent[0].abs() < 0.2 && (tangent[1] + 1.0).abs() < 0.01);
+        assert!(circle.point_at_distance(-1.0).is_none());
+        assert!(circle.point_at_distance(length + 1.0).is_none());
+
+        // Gaps between sub-paths are not counted.
+        let lines = Path::new().move_to(0.0, 0.0).line_to(10.0, 0.0)
+            .move_to(0.0, 20.0).line_to(0.0, 30.0);
+        assert_eq!(lines.length(), 20.0);
+        assert_eq!(lines.point_at_distance(15.0), Some(([0.0, 25.0], [0.0, 1.0])));
+    }
+
+    // Counts the triangles of the built-in tessellators.
+    #[cfg(not(feature = "lyon"))]
+    #[test]
+    fn test_path_builder() {
+        use super::PathBuilder;
+        use test_backend::RecordingGraphics;
+        use triangulation::TessellationBuffers;
+        use { Context, Line };
+
+        let path = PathBuilder::new()
+            .line_to(0.0, 0.0)
+            .line_to(10.0, 0.0)
+            .line_to(0.0, 10.0)
+            .close();
+        let c = Context::abs(100.0, 100.0);
+        let mut g = RecordingGraphics::new();
+        path.fill([1.0; 4], &c, &mut g);
+        // A convex sub-path is drawn as a fan.
+        assert_eq!(g.tri_lists.len(), 1);
+        assert_eq!(g.vertex_count(), 3);
+        // Filling again with the same buffers gives the same triangles.
+        let mut buffers = TessellationBuffers::new();
+        let path = path.move_to(50.0, 50.0).line_to(60.0, 50.0).line_to(50.0, 60.0).close();
+        let mut g = RecordingGraphics::new();
+        path.fill_with_buffers([1.0; 4], &mut buffers, &c, &mut g);
+        let capacity = buffers.capacity();
+        assert!(capacity > 0);
+        path.fill_with_buffers([1.0; 4], &mut buffers, &c, &mut g);
+        assert_eq!(buffers.capacity(), capacity);
+        assert_eq!(g.tri_lists[0].vertices, g.tri_lists[1].vertices);
+
+        let mut g = RecordingGraphics::new();
+        path.stroke(&Line::new([1.0; 4], 1.0), &c, &mut g);
+        // Both triangles in one call, with two triangles per edge,
+        // including the closing edge, and two per mitered corner.
+        assert_eq!(g.tri_lists.len(), 1);
+        assert_eq!(g.vertex_count(), 2 * 36);
+    }
+
+    #[test]
+    fn test_dashes() {
+        use super::each_dash;
+
+        let dashes = |points: &[Vec2d], closed: bool, pattern: &[Scalar], offset: Scalar| {
+            let mut res = Vec::new();
+            each_dash(points, closed, pattern, offset, &mut Vec::new(), |dash| {
+                res.push(dash.to_vec())
+            });
+            res
+        };
+        let square = [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]];
+        // Dashes continue around corners.
+        assert_eq!(dashes(&square, true, &[15.0, 5.0], 0.0), vec![
+            vec![[0.0, 0.0], [10.0, 0.0], [10.0, 5.0]],
+            vec![[10.0, 10.0], [0.0, 10.0], [0.0, 5.0]],
+        ]);
+        assert_eq!(dashes(&square, false, &[15.0, 5.0], 0.0).len(), 2);
+        assert_eq!(dashes(&square, true, &[15.0, 5.0], 10.0)[1],
+            vec![[10.0, 0.0], [10.0, 10.0], [5.0, 10.0]]);
+        // Odd patterns alternate, and negative offsets wrap around.
+        assert_eq!(dashes(&[[0.0, 0.0], [10.0, 0.0]], false, &[3.0], -1.0), vec![
+            vec![[1.0, 0.0], [4.0, 0.0]],
+            vec![[7.0, 0.0], [10.0, 0.0]],
+        ]);
+        // Zero dashes are dots.
+        assert_eq!(dashes(&[[0.0, 0.0], [10.0, 0.0]], false, &[0.0, 5.0], 0.0),
+            vec![vec![[0.0, 0.0]; 2], vec![[5.0, 0.0]; 2]]);
+
+    }
+
+    // Counts the triangles of the built-in tessellators.
+    #[cfg(not(feature = "lyon"))]
+    #[test]
+    fn test_stroke_dashed() {
+        use test_backend::RecordingGraphics;
+        use { Context, Line };
+
+        let path = Path::new()
+            .move_to(0.0, 0.0).line_to(10.0, 0.0).line_to(10.0, 10.0).line_to(0.0, 10.0)
+            .close();
+        let c = Context::abs(100.0, 100.0);
+        let mut g = RecordingGraphics::new();
+        path.stroke_dashed(&Line::new([1.0; 4], 1.0), &[15.0, 5.0], 0.0, &c, &mut g);
+        // Two dashes with a mitered corner each.
+        assert_eq!(g.tri_lists.len(), 1);
+        assert_eq!(g.vertex_count(), 2 * 6 * 3);
+        // Invalid patterns draw solid lines.
+        let mut g = RecordingGraphics::new();
+        path.stroke_dashed(&Line::new([1.0; 4], 1.0), &[-1.0, 5.0], 0.0, &c, &mut g);
+        assert_eq!(g.vertex_count(), 16 * 3);
+    }
+
+    #[test]
+    fn test_resample() {
+        use super::resample;
+
+        let points = resample(&[[0.0, 0.0], [10.0, 0.0]], 2.0);
+        assert_eq!(points, (0..6).map(|i| [i as Scalar * 2.0, 0.0]).collect::<Vec<_>>());
+        // Samples continue across corners.
+        let points = resample(&[[0.0, 0.0], [3.0, 0.0], [3.0, 3.0]], 2.0);
+        assert_eq!(points, vec![[0.0, 0.0], [2.0, 0.0], [3.0, 1.0], [3.0, 3.0]]);
+        assert_eq!(resample(&[[0.0, 0.0], [1.0, 0.0]], 5.0), vec![[0.0, 0.0], [1.0, 0.0]]);
+        assert_eq!(resample(&[[1.0, 1.0]], 5.0), vec![[1.0, 1.0]]);
+        assert_eq!(resample(&[], 5.0).len(), 0);
+    }
+
+    #[test]
+    fn test_rectangle_with_hole() {
+        // Clockwise outer contour.
+        let path = Path::new()
+            .move_to(0.0, 0.0)
+            .line_to(0.0, 10.0)
+            .line_to(10.0, 10.0)
+            .line_to(10.0, 0.0)
+            .close()
+        // Counter-clockwise inner contour.
+            .move_to(3.0, 3.0)
+            .line_to(7.0, 3.0)
+            .line_to(7.0, 7.0)
+            .line_to(3.0, 7.0)
+            .close();
+        let polygons: Vec<&[[Scalar; 2]]> = path.subpaths.iter()
+            .map(|s| &s.points[..]).collect();
+        let mut triangles = Vec::new();
+        triangulation::with_polygons_tri_list(
+            identity(),
+            &polygons[..],
+            path.fill_rule,
+            |v| for t in v.chunks(6) {
+                triangles.push([
+                    [t[0] as Scalar, t[1] as Scalar],
+                    [t[2] as Scalar, t[3] as Scalar],
+                    [t[4] as Scalar, t[5] as Scalar]
+                ]);
+            }
+        );
+        let area: Scalar = triangles.iter().map(|t| {
+            0.5 * ((t[1][0] - t[0][0]) * (t[2][1] - t[0][1])
+                - (t[2][0] - t[0][0]) * (t[1][1] - t[0][1]))
+        }).fold(0.0, |a, b| a + b);
+        assert_eq!(area, 100.0 - 16.0);
+        let covered = |p: [Scalar; 2]| triangles.iter().any(|&t| inside_triangle(t, p));
+        assert!(!covered([5.0, 5.0]));
+        assert!(covered([1.0, 5.0]));
+        assert!(covered([5.0, 8.5]));
+    }
+
+    fn bounds(path: &Path) -> [Scalar; 4] {
+        let (min, max): (Scalar, Scalar) = (Float::neg_infinity(), Float::infinity());
+        let mut res = [max, max, min, min];
+        for subpath in path.subpaths.iter() {
+            for p in subpath.points.iter() {
+                if p[0] < res[0] { res[0] = p[0]; }
+                if p[1] < res[1] { res[1] = p[1]; }
+                if p[0] > res[2] { res[2] = p[0]; }
+                if p[1] > res[3] { res[3] = p[1]; }
+            }
+        }
+        res
+    }
+
+    fn assert_bounds(path: &Path, expected: [Scalar; 4]) {
+        let b = bounds(path);
+        for i in 0..4 {
+            assert!((b[i] - expected[i]).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn test_parse_svg_lines() {
+        // Check mark icon.
+        let path = Path::parse_svg("M9 16.17L4.83 12l-1.42 1.41L9 19 21 7l-1.41-1.41z").unwrap();
+        assert_eq!(path.subpaths.len(), 1);
+        assert_eq!(path.subpaths[0].points.len(), 6);
+        assert!(path.subpaths[0].closed);
+        assert_bounds(&path, [3.41, 5.59, 21.0, 19.0]);
+
+        // Home icon.
+        let path = Path::parse_svg("M10 20v-6h4v6h5v-8h3L12 3 2 12h3v8z").unwrap();
+        assert_eq!(path.subpaths[0].points.len(), 11);
+        assert_bounds(&path, [2.0, 3.0, 22.0, 20.0]);
+    }
+
+    #[test]
+    fn test_parse_svg_curves() {
+        let path = Path::parse_svg("M0,0C0 10 10 10 10 0S20-10 20 0Q25 5 30 0T40 0").unwrap();
+        assert_eq!(path.subpaths[0].points.len(), 1 + 4 * path.resolution);
+        assert_bounds(&path, [0.0, -7.5, 40.0, 7.5]);
+
+        // Circle icon made from two arcs.
+        let path = Path::parse_svg("M12 2a10 10 0 1 0 0 20a10 10 0 1 0 0-20z").unwrap();
+        assert_eq!(path.subpaths[0].points.len(), 1 + 4 * path.resolution);
+        assert_bounds(&path, [2.0, 2.0, 22.0, 22.0]);
+        assert_eq!(path.subpaths[0].points[2 * path.resolution], [12.0, 22.0]);
+    }
+
+    #[test]
+    fn test_parse_svg_errors() {
+        use super::ParseError;
+
+        assert_eq!(Path::parse_svg("M10 10 L20 x").err(),
+            Some(ParseError { offset: 11, token: "x".to_string() }));
+        assert_eq!(Path::parse_svg("L10,10").err(),
+            Some(ParseError { offset: 0, token: "L10".to_string() }));
+        assert_eq!(Path::parse_svg("M10 10 Z 5").err(),
+            Some(ParseError { offset: 9, token: "5".to_string() }));
+    }
+}
@@ -0,0 +1,250 @@
+//! Draw patterns of colored shapes, without a texture.
+//!
+//! The patterns are clipped to a rectangle geometrically,
+//! so they do not depend on scissor support of the back-end.
+
+use std::vec::Vec;
+use std::num::Float;
+use internal;
+use internal::{ Scalar, Vec2d };
+use triangulation::{ self, tx, ty };
+use Context;
+use Graphics;
+
+/// A checkerboard of square cells with two colors
+#[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Checkerboard {
+    /// The color of the cell in the upper left corner
+    #[cfg_attr(feature = "serde", serde(serialize_with = "::serde_color::serialize",
+        deserialize_with = "::serde_color::deserialize"))]
+    pub color_a: internal::Color,
+    /// The color of the other cells
+    #[cfg_attr(feature = "serde", serde(serialize_with = "::serde_color::serialize",
+        deserialize_with = "::serde_color::deserialize"))]
+    pub color_b: internal::Color,
+    /// The width and height of each cell
+    pub cell_size: Scalar,
+}
+
+impl Checkerboard {
+    /// Creates a new checkerboard
+    pub fn new(
+        color_a: internal::Color,
+        color_b: internal::Color,
+        cell_size: Scalar
+    ) -> Checkerboard {
+        Checkerboard {
+            color_a: color_a,
+            color_b: color_b,
+            cell_size: cell_size,
+        }
+    }
+
+    /// Draws the checkerboard inside a rectangle.
+    ///
+    /// See `checkerboard`.
+    pub fn draw<B>(
+        &self,
+        rect: internal::Rectangle,
+        c: &Context,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        checkerboard(rect, self.cell_size, self.color_a, self.color_b, c, back_end);
+    }
+}
+
+/// Draws a checkerboard of square cells inside a rectangle.
+///
+/// The cell in the upper left corner has color `a`.
+/// Cells at the right and bottom edges are cut at the rectangle.
+/// Each color is drawn with one call to `tri_list`.
+pub fn checkerboard<B>(
+    rect: internal::Rectangle,
+    cell_size: Scalar,
+    a: internal::Color,
+    b: internal::Color,
+    c: &Context,
+    back_end: &mut B
+)
+    where B: Graphics
+{
+    if cell_size <= 0.0 || rect[2] <= 0.0 || rect[3] <= 0.0 { return; }
+
+    let cols = (rect[2] / cell_size).ceil() as u32;
+    let rows = (rect[3] / cell_size).ceil() as u32;
+    for &(parity, color) in [(0, a), (1, b)].iter() {
+        let color = c.apply_alpha(color);
+        if c.skips(color) { continue; }
+        back_end.tri_list(&c.draw_state, &color, |f| {
+            for row in 0..rows {
+                for col in 0..cols {
+                    if (row + col) % 2 != parity { continue; }
+                    let (x, y) = (col as Scalar * cell_size, row as Scalar * cell_size);
+                    let cell = [
+                        rect[0] + x,
+                        rect[1] + y,
+                        cell_size.min(rect[2] - x),
+                        cell_size.min(rect[3] - y)
+                    ];
+                    let mut vertices = triangulation::rect_tri_list_xy(c.transform, cell);
+                    triangulation::ensure_ccw(&mut vertices);
+                    f.emit(&vertices);
+                }
+            }
+        });
+    }
+}
+
+/// Draws stripes of alternating colors inside a rectangle.
+///
+/// The stripes run along the `angle` in radians from the x axis,
+/// and have a width of `stripe_width` measured across them.
+/// The stripe through the upper left corner of the rectangle has color `a`.
+/// Each color is drawn with one call to `tri_list`.
+pub fn stripes<B>(
+    rect: internal::Rectangle,
+    stripe_width: Scalar,
+    angle: Scalar,
+    a: internal::Color,
+    b: internal::Color,
+    c: &Context,
+    back_end: &mut B
+)
+    where B: Graphics
+{
+    if stripe_width <= 0.0 || rect[2] <= 0.0 || rect[3] <= 0.0 { return; }
+
+    let (x, y, w, h) = (rect[0], rect[1], rect[2], rect[3]);
+    let corners = [[x, y], [x + w, y], [x + w, y + h], [x, y + h]];
+    // The distance across the stripes, relative to the upper left corner.
+    let normal = [-angle.sin(), angle.cos()];
+    let across = |p: Vec2d| (p[0] - x) * normal[0] + (p[1] - y) * normal[1];
+    let (min, max) = corners.iter().fold((0.0, 0.0), |(min, max): (Scalar, Scalar), &p| {
+        let s = across(p);
+        (min.min(s), max.max(s))
+    });
+    let first = (min / stripe_width).floor() as i64;
+    let last = (max / stripe_width).ceil() as i64;
+    for &(parity, color) in [(0, a), (1, b)].iter() {
+        let color = c.apply_alpha(color);
+        if c.skips(color) { continue; }
+        back_end.tri_list(&c.draw_state, &color, |f| {
+            for k in first..last {
+                if ((k % 2) + 2) % 2 != parity { continue; }
+                let (s0, s1) = (k as Scalar * stripe_width, (k + 1) as Scalar * stripe_width);
+                let band = clip(&clip(&corners, &across, s0, true), &across, s1, false);
+                let mut vertices = Vec::with_capacity(band.len() * 6);
+                for i in 1..band.len().saturating_sub(1) {
+                    for p in [band[0], band[i], band[i + 1]].iter() {
+                        vertices.push(tx(c.transform, p[0], p[1]));
+                        vertices.push(ty(c.transform, p[0], p[1]));
+                    }
+                }
+                if vertices.is_empty() { continue; }
+                triangulation::ensure_ccw(&mut vertices);
+                f.emit(&vertices);
+            }
+        });
+    }
+}
+
+/// Clips a convex polygon to the side of a line where
+/// `across(p)` is above or below a value.
+fn clip<F>(polygon: &[Vec2d], across: &F, value: Scalar, above: bool) -> Vec<Vec2d>
+    where F: Fn(Vec2d) -> Scalar
+{
+    let inside = |s: Scalar| if above { s >= value } else { s <= value };
+    let n = polygon.len();
+    let mut res = Vec::with_capacity(n + 1);
+    for i in 0..n {
+        let (p, q) = (polygon[i], polygon[(i + 1) % n]);
+        let (sp, sq) = (across(p), across(q));
+        if inside(sp) { res.push(p); }
+        if inside(sp) != inside(sq) {
+            let t = (value - sp) / (sq - sp);
+            res.push([p[0] + (q[0] - p[0]) * t, p[1] + (q[1] - p[1]) * t]);
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use std::num::Float;
+    use super::{ checkerboard, stripes, Checkerboard };
+    use test_backend::RecordingGraphics;
+    use Context;
+
+    fn area(vertices: &[f32]) -> f32 {
+        vertices.chunks(6).fold(0.0, |sum, t| {
+            sum + 0.5 * ((t[2] - t[0]) * (t[5] - t[1]) - (t[4] - t[0]) * (t[3] - t[1])).abs()
+        })
+    }
+
+    #[test]
+    fn test_checkerboard() {
+        let (a, b) = ([1.0; 4], [0.5, 0.5, 0.5, 1.0]);
+        let mut g = RecordingGraphics::new();
+        checkerboard([0.0, 0.0, 40.0, 40.0], 10.0, a, b, &Context::new(), &mut g);
+        assert_eq!(g.tri_lists.len(), 2);
+        assert_eq!(g.tri_lists[0].color, a);
+        assert_eq!(g.tri_lists[1].color, b);
+        // 8 quads of 2 triangles for each color.
+        assert_eq!(g.tri_lists[0].vertices.len(), 8 * 12);
+        assert_eq!(g.tri_lists[1].vertices.len(), 8 * 12);
+
+        // Partial cells are cut at the edges.
+        let mut g = RecordingGraphics::new();
+        checkerboard([0.0, 0.0, 35.0, 15.0], 10.0, a, b, &Context::new(), &mut g);
+        let total = area(&g.tri_lists[0].vertices) + area(&g.tri_lists[1].vertices);
+        assert!((total - 35.0 * 15.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_checkerboard_primitive() {
+        let (a, b) = ([1.0; 4], [0.5, 0.5, 0.5, 1.0]);
+        let mut g = RecordingGraphics::new();
+        Checkerboard::new(a, b, 8.0).draw([0.0, 0.0, 16.0, 16.0], &Context::new(), &mut g);
+        assert_eq!(g.tri_lists.len(), 2);
+        // The diagonal cells have color a, the others color b.
+        let centers = |vertices: &[f32]| -> Vec<[f32; 2]> {
+            vertices.chunks(12).map(|q| {
+                let (x, y) = q.chunks(2).fold((0.0, 0.0), |(x, y), v| (x + v[0], y + v[1]));
+                [x / 6.0, y / 6.0]
+            }).collect()
+        };
+        let ca = centers(&g.tri_lists[0].vertices);
+        let cb = centers(&g.tri_lists[1].vertices);
+        assert_eq!(ca.len(), 2);
+        assert_eq!(cb.len(), 2);
+        for p in ca.iter() {
+            assert!((p[0] < 8.0) == (p[1] < 8.0));
+        }
+        for p in cb.iter() {
+            assert!((p[0] < 8.0) != (p[1] < 8.0));
+        }
+    }
+
+    #[test]
+    fn test_stripes() {
+        let (a, b) = ([1.0; 4], [0.0, 0.0, 0.0, 1.0]);
+        let mut g = RecordingGraphics::new();
+        stripes([0.0, 0.0, 30.0, 20.0], 5.0, 0.7, a, b, &Context::new(), &mut g);
+        assert_eq!(g.tri_lists[0].color, a);
+        assert_eq!(g.tri_lists[1].color, b);
+        let total = area(&g.tri_lists[0].vertices) + area(&g.tri_lists[1].vertices);
+        assert!((total - 30.0 * 20.0).abs() < 0.001);
+        for v in g.tri_lists[0].vertices.chunks(2) {
+            assert!(v[0] >= -0.001 && v[0] <= 30.001 && v[1] >= -0.001 && v[1] <= 20.001);
+        }
+
+        // Horizontal stripes have the same area for each color.
+        let mut g = RecordingGraphics::new();
+        stripes([0.0, 0.0, 30.0, 20.0], 5.0, 0.0, a, b, &Context::new(), &mut g);
+        assert!((area(&g.tri_lists[0].vertices) - 300.0).abs() < 0.001);
+        assert!((area(&g.tri_lists[1].vertices) - 300.0).abs() < 0.001);
+    }
+}
@@ -1,16 +1,31 @@
 //! Draw polygon
 
+use color;
 use internal;
 use triangulation;
+use math;
+use DrawState;
 use Graphics;
 use Context;
 use Color;
 
+pub use triangulation::{ FillRule, TessellationBuffers };
+
 /// A polygon
-#[derive(Copy, Clone)]
+///
+/// Create it with `new` or `Default` and the builder methods.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Polygon {
     /// The color of the polygon
+    #[cfg_attr(feature = "serde", serde(serialize_with = "::serde_color::serialize",
+        deserialize_with = "::serde_color::deserialize"))]
     pub color: internal::Color,
+    /// The rule deciding which areas are filled
+    pub fill_rule: FillRule,
+    /// The width of the feathered edge, if anti-aliased
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub antialias: Option<internal::Width>,
 }
 
 impl Polygon {
@@ -18,10 +33,25 @@ impl Polygon {
     pub fn new(color: internal::Color) -> Polygon {
         Polygon {
             color: color,
+            fill_rule: FillRule::NonZero,
+            antialias: None,
         }
     }
 
+    /// Adds a feathered edge of some width, in device pixels,
+    /// outside the polygon.
+    ///
+    /// See `triangulation::with_polygon_feather_tri_list_c`.
+    pub fn antialias(mut self, width: internal::Width) -> Polygon {
+        self.antialias = Some(width);
+        self
+    }
+
     /// Draws polygon
+    ///
+    /// The polygon may be concave or intersect itself.
+    /// Convex polygons are drawn as a triangle fan without allocating,
+    /// others with the fill rule, see `draw_with_buffers`.
     pub fn draw<B>(
         &self,
         polygon: internal::Polygon,
@@ -30,15 +60,113 @@ impl Polygon {
     )
         where B: Graphics
     {
+        self.draw_with_buffers(polygon, &mut TessellationBuffers::new(), c, back_end);
+    }
+
+    /// Draws the polygon with a draw state and transform,
+    /// for callers without a context.
+    ///
+    /// See `Context::from_state`.
+    pub fn draw_with_state<B>(
+        &self,
+        polygon: internal::Polygon,
+        draw_state: &DrawState,
+        transform: internal::Matrix2d,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        self.draw(polygon, &Context::from_state(draw_state, transform), back_end);
+    }
+
+    /// Draws polygon, reusing buffers for the tessellation.
+    ///
+    /// Keep the buffers between frames when drawing
+    /// many concave polygons, to avoid allocating for every draw.
+    /// Convex polygons do not use the buffers.
+    pub fn draw_with_buffers<B>(
+        &self,
+        polygon: internal::Polygon,
+        buffers: &mut TessellationBuffers,
+        c: &Context,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        let color = c.apply_alpha(self.color);
+        if c.skips(color) { return; }
+        let convex = triangulation::is_convex(polygon);
         back_end.tri_list(
             &c.draw_state,
-            &self.color,
-            |f|
-        triangulation::with_polygon_tri_list(
-            c.transform,
-            polygon,
-            |vertices| f(vertices)
-        ));
+            &color,
+            |f| if convex {
+                triangulation::with_polygon_tri_list(
+                    c.transform,
+                    polygon,
+                    |vertices| f.emit(vertices)
+                )
+            } else {
+                triangulation::with_polygons_tri_list_buffered(
+                    buffers,
+                    c.transform,
+                    &[polygon],
+                    self.fill_rule,
+                    |vertices| f.emit(vertices)
+                )
+            }
+        );
+
+        if let Some(width) = self.antialias {
+            if !back_end.has_vertex_colors() { return; }
+            let width = c.local_width(width);
+            back_end.tri_list_c(
+                &c.draw_state,
+                |f|
+            triangulation::with_polygon_feather_tri_list_c(
+                c.transform,
+                polygon,
+                width,
+                color,
+                |vertices, colors| f.emit(vertices, colors)
+            ));
+        }
+    }
+
+    /// Draws a regular polygon.
+    ///
+    /// See `math::regular_polygon`.
+    pub fn draw_regular<B>(
+        &self,
+        center: internal::Vec2d,
+        radius: internal::Radius,
+        sides: usize,
+        rotation: internal::Scalar,
+        c: &Context,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        let polygon = math::regular_polygon(center, radius, sides, rotation);
+        self.draw(&polygon[..], c, back_end);
+    }
+
+    /// Draws a star.
+    ///
+    /// See `math::star`.
+    pub fn draw_star<B>(
+        &self,
+        center: internal::Vec2d,
+        outer_radius: internal::Radius,
+        inner_radius: internal::Radius,
+        points: usize,
+        rotation: internal::Scalar,
+        c: &Context,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        let polygon = math::star(center, outer_radius, inner_radius, points, rotation);
+        self.draw(&polygon[..], c, back_end);
     }
 
     /// Draws tweened polygon with linear interpolation
@@ -51,26 +179,36 @@ impl Polygon {
     )
         where B: Graphics
     {
-        if self.color[3] == 0.0 { return; }
+        let color = c.apply_alpha(self.color);
+        if c.skips(color) { return; }
         back_end.tri_list(
             &c.draw_state,
-            &self.color,
+            &color,
             |f|
         triangulation::with_lerp_polygons_tri_list(
             c.transform,
             polygons,
             tween_factor,
-            |vertices| f(vertices)
+            |vertices| f.emit(vertices)
         ));
     }
 }
 
+impl Default for Polygon {
+    /// A white polygon with the non-zero fill rule.
+    fn default() -> Polygon {
+        Polygon::new(color::WHITE)
+    }
+}
+
 quack! {
     p: Polygon[]
     get:
         fn () -> Color [] { Color(p.color) }
+        fn () -> FillRule [] { p.fill_rule }
     set:
         fn (val: Color) [] { p.color = val.0 }
+        fn (val: FillRule) [] { p.fill_rule = val }
     action:
 }
 
@@ -78,11 +216,63 @@ quack! {
 mod test {
     use quack::Set;
     use super::Polygon;
+    use super::{ FillRule, TessellationBuffers };
     use Color;
 
     #[test]
     fn test_polygon() {
         let _polygon = Polygon::new([1.0; 4])
-            .set(Color([0.0; 4]));
+            .set(Color([0.0; 4]))
+            .set(FillRule::EvenOdd);
+    }
+
+    #[test]
+    fn test_convex_fan() {
+        use test_backend::RecordingGraphics;
+        use Context;
+
+        let c = Context::abs(100.0, 100.0);
+        let mut g = RecordingGraphics::new();
+        let hexagon = ::math::regular_polygon([50.0, 50.0], 20.0, 6, 0.0);
+        Polygon::new([1.0; 4]).draw(&hexagon, &c, &mut g);
+        // A fan of four triangles, instead of the slabs of the tessellator.
+        assert_eq!(g.vertex_count(), 12);
+        // The fan does not touch the buffers.
+        let mut buffers = TessellationBuffers::new();
+        Polygon::new([1.0; 4]).draw_with_buffers(&hexagon, &mut buffers, &c, &mut g);
+        assert_eq!(buffers.capacity(), 0);
+    }
+
+    #[test]
+    fn test_reused_buffers() {
+        use path::Path;
+        use test_backend::RecordingGraphics;
+        use Context;
+
+        let c = Context::abs(100.0, 100.0);
+        let mut g = RecordingGraphics::new();
+        let arrow = [[0.0, 0.0], [20.0, 0.0], [10.0, 5.0], [20.0, 20.0], [0.0, 20.0]];
+        let path = Path::new()
+            .move_to(0.0, 0.0).line_to(20.0, 0.0).line_to(10.0, 5.0).close()
+            .move_to(30.0, 30.0).line_to(50.0, 30.0).line_to(40.0, 50.0).close();
+        let mut buffers = TessellationBuffers::new();
+        Polygon::new([1.0; 4]).draw_with_buffers(&arrow, &mut buffers, &c, &mut g);
+        path.fill_with_buffers([1.0; 4], &mut buffers, &c, &mut g);
+        let capacity = buffers.capacity();
+        assert!(capacity > 0);
+        // Later draws fit in the buffers grown by the first ones.
+        for _ in 0..10 {
+            Polygon::new([1.0; 4]).draw_with_buffers(&arrow, &mut buffers, &c, &mut g);
+            path.fill_with_buffers([1.0; 4], &mut buffers, &c, &mut g);
+        }
+        assert_eq!(buffers.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_default() {
+        let p = Polygon::default();
+        assert_eq!(p.color, [1.0; 4]);
+        assert_eq!(p.fill_rule, FillRule::NonZero);
+        assert_eq!(p.antialias, None);
     }
 }
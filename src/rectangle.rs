@@ -1,10 +1,16 @@
 //! Draw rectangle
 
+use std::vec::Vec;
 use internal;
 use triangulation;
+use triangulation::Resolution;
+use color;
+use color::Gradient;
 use Context;
+use DrawState;
 use Graphics;
 use Color;
+use radians::Radians;
 
 pub use vecmath::margin_rectangle as margin;
 
@@ -33,7 +39,8 @@ pub fn square(
 }
 
 /// The shape of the rectangle
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Shape {
     /// Square corners
     Square,
@@ -41,12 +48,19 @@ pub enum Shape {
     Round(internal::Radius),
     /// Bevel corners
     Bevel(internal::Radius),
+    /// Round corners with a radius each, clockwise from the top-left corner
+    ///
+    /// A radius of zero gives a sharp corner.
+    RoundCorners([internal::Radius; 4]),
 }
 
 /// The border of the rectangle
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Border {
     /// The color of the border
+    #[cfg_attr(feature = "serde", serde(serialize_with = "::serde_color::serialize",
+        deserialize_with = "::serde_color::deserialize"))]
     pub color: internal::Color,
     /// The radius of the border
     pub radius: internal::Radius,
@@ -56,15 +70,53 @@ pub struct Border {
 #[derive(Copy)]
 pub struct MaybeBorder(pub Option<Border>);
 
+/// The drop shadow of the rectangle
+///
+/// The shadow is approximated by layers of expanded copies
+/// of the rectangle, with decreasing alpha outwards.
+/// Layer `k` of `layers` is expanded by `blur * k / layers`
+/// and has the alpha of the color multiplied by
+/// `(1 - k / layers)^falloff / layers`.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Shadow {
+    /// The color of the shadow
+    #[cfg_attr(feature = "serde", serde(serialize_with = "::serde_color::serialize",
+        deserialize_with = "::serde_color::deserialize"))]
+    pub color: internal::Color,
+    /// The offset of the shadow from the rectangle
+    pub offset: internal::Vec2d,
+    /// The distance the shadow extends outside the rectangle
+    pub blur: internal::Scalar,
+    /// The number of layers
+    pub layers: usize,
+    /// The exponent of the alpha falloff, higher is softer
+    pub falloff: internal::ColorComponent,
+}
+
 /// A filled rectangle
-#[derive(Copy, Clone)]
+///
+/// Create it with a constructor or `Default` and the builder methods.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Rectangle {
     /// The rectangle color
+    #[cfg_attr(feature = "serde", serde(serialize_with = "::serde_color::serialize",
+        deserialize_with = "::serde_color::deserialize"))]
     pub color: internal::Color,
     /// The roundness of the rectangle
     pub shape: Shape,
     /// The border
     pub border: Option<Border>,
+    /// The drop shadow
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub shadow: Option<Shadow>,
+    /// The width of the feathered edge in device pixels, if anti-aliased
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub antialias: Option<internal::Width>,
+    /// The number of segments in a full circle, for round corners
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub resolution: Resolution,
 }
 
 impl Rectangle {
@@ -74,6 +126,9 @@ impl Rectangle {
             color: color,
             shape: Shape::Square,
             border: None,
+            shadow: None,
+            antialias: None,
+            resolution: Resolution::Fixed(128),
         }
     }
 
@@ -85,7 +140,10 @@ impl Rectangle {
         Rectangle {
             color: color,
             shape: Shape::Round(round_radius),
-            border: None
+            border: None,
+            shadow: None,
+            antialias: None,
+            resolution: Resolution::Fixed(128),
         }
     }
 
@@ -100,7 +158,10 @@ impl Rectangle {
             border: Some(Border {
                     color: color,
                     radius: radius
-                })
+                }),
+            shadow: None,
+            antialias: None,
+            resolution: Resolution::Fixed(128),
         }
     }
 
@@ -116,10 +177,106 @@ impl Rectangle {
             border: Some(Border {
                     color: color,
                     radius: border_radius
-                })
+                }),
+            shadow: None,
+            antialias: None,
+            resolution: Resolution::Fixed(128),
         }
     }
 
+    /// Rounds each corner with its own radius, clockwise
+    /// from the top-left corner.
+    ///
+    /// A radius of zero keeps the corner sharp.
+    /// See `Shape::RoundCorners`.
+    pub fn round_corners(mut self, radii: [internal::Radius; 4]) -> Rectangle {
+        self.shape = Shape::RoundCorners(radii);
+        self
+    }
+
+    /// Adds a drop shadow with 8 layers and a falloff of 2.
+    ///
+    /// The shadow follows the shape of the rectangle
+    /// and is drawn before the fill. See `Shadow`.
+    pub fn shadow(
+        mut self,
+        offset: internal::Vec2d,
+        blur: internal::Scalar,
+        color: internal::Color
+    ) -> Rectangle {
+        self.shadow = Some(Shadow {
+            color: color,
+            offset: offset,
+            blur: blur,
+            layers: 8,
+            falloff: 2.0,
+        });
+        self
+    }
+
+    /// Adds a feathered edge of some width, in device pixels,
+    /// outside the rectangle, or outside the border if there is one.
+    ///
+    /// See `triangulation::with_polygon_feather_tri_list_c`.
+    pub fn antialias(mut self, width: internal::Width) -> Rectangle {
+        self.antialias = Some(width);
+        self
+    }
+
+    /// Sets the number of segments in a full circle, for round corners.
+    ///
+    /// Each corner uses a quarter of the segments.
+    pub fn resolution(mut self, resolution: Resolution) -> Rectangle {
+        self.resolution = resolution;
+        self
+    }
+
+    /// Returns the number of points in each round corner of some radius.
+    pub fn corner_resolution(&self, radius: internal::Radius, c: &Context) -> usize {
+        let n = self.resolution.segments(radius / c.local_width(1.0)) / 4;
+        if n < 2 { 2 } else { n }
+    }
+
+    /// Returns true if a point in local coordinates is inside the rectangle,
+    /// or on its edge.
+    ///
+    /// The shape of the corners and the border are included.
+    /// See `Context::inverse_transform_pos`.
+    pub fn contains(&self, rectangle: internal::Rectangle, point: internal::Vec2d) -> bool {
+        use std::num::Float;
+        use vecmath::margin_rectangle;
+
+        let border = match self.border { Some(b) => b.radius, None => 0.0 };
+        let r = margin_rectangle(rectangle, -border);
+        let (x, y, w, h) = (r[0], r[1], r[2], r[3]);
+        let (px, py) = (point[0], point[1]);
+        if px < x || px > x + w || py < y || py > y + h { return false; }
+
+        // The corner of the quadrant containing the point.
+        let (left, top) = (px < x + 0.5 * w, py < y + 0.5 * h);
+        let k = match (left, top) {
+            (true, true) => 0,
+            (false, true) => 1,
+            (false, false) => 2,
+            (true, false) => 3,
+        };
+        let (radius, bevel) = match self.shape {
+            Shape::Square => return true,
+            Shape::Round(radius) => (radius, false),
+            Shape::Bevel(radius) => (radius, true),
+            Shape::RoundCorners(radii) => (radii[k], false),
+        };
+        if radius <= 0.0 { return true; }
+        let max = 0.5 * w.min(h);
+        let radius = radius + border;
+        let radius = if radius > max { max } else { radius };
+        // Distances past the center of the corner, towards the corner.
+        let dx = if left { x + radius - px } else { px - (x + w - radius) };
+        let dy = if top { y + radius - py } else { py - (y + h - radius) };
+        if dx <= 0.0 || dy <= 0.0 { return true; }
+        if bevel { dx + dy <= radius } else { dx * dx + dy * dy <= radius * radius }
+    }
+
     /// Draws the rectangle
     pub fn draw<B>(
         &self,
@@ -129,52 +286,235 @@ impl Rectangle {
     )
         where B: Graphics
     {
-        if self.color[3] != 0.0 {
+        if let Some(shadow) = self.shadow {
+            self.draw_shadow(&shadow, rectangle, c, back_end);
+        }
+
+        let fill = c.apply_alpha(self.color);
+        if !c.skips(fill) {
             match self.shape {
                 Shape::Square => {
                     back_end.tri_list(
                         &c.draw_state,
-                        &self.color,
-                        |f| f(&triangulation::rect_tri_list_xy(c.transform, rectangle)),
+                        &fill,
+                        |f| {
+                            let mut vertices = triangulation::rect_tri_list_xy(
+                                c.transform, rectangle);
+                            triangulation::ensure_ccw(&mut vertices);
+                            f.emit(&vertices)
+                        },
                     );
                 }
                 Shape::Round(round_radius) => {
                     back_end.tri_list(
                         &c.draw_state,
-                        &self.color,
+                        &fill,
                         |f|
                     triangulation::with_round_rectangle_tri_list(
-                        32,
+                        self.corner_resolution(round_radius, c),
                         c.transform,
                         rectangle,
                         round_radius,
-                        |vertices| f(vertices)
+                        |vertices| f.emit(vertices)
                     ));
                 }
                 Shape::Bevel(bevel_radius) => {
                     back_end.tri_list(
                         &c.draw_state,
-                        &self.color,
+                        &fill,
                         |f|
                     triangulation::with_round_rectangle_tri_list(
                         2,
                         c.transform,
                         rectangle,
                         bevel_radius,
-                        |vertices| f(vertices)
+                        |vertices| f.emit(vertices)
+                    ));
+                }
+                Shape::RoundCorners(radii) => {
+                    back_end.tri_list(
+                        &c.draw_state,
+                        &fill,
+                        |f|
+                    triangulation::with_round_corners_rectangle_tri_list(
+                        self.corner_resolution(max_radius(radii), c),
+                        c.transform,
+                        rectangle,
+                        radii,
+                        |vertices| f.emit(vertices)
                     ));
                 }
             }
         }
 
+        self.draw_border(rectangle, c, back_end);
+
+        if let Some(width) = self.antialias {
+            if !back_end.has_vertex_colors() { return; }
+            let width = c.local_width(width);
+            let (expand, color) = match self.border {
+                Some(Border { color, radius }) => (radius, color),
+                None => (0.0, self.color)
+            };
+            let corner = match self.shape {
+                Shape::Round(radius) => self.corner_resolution(radius + expand, c),
+                Shape::RoundCorners(radii) =>
+                    self.corner_resolution(max_radius(radii) + expand, c),
+                _ => 2
+            };
+            let outline = outline(self.shape, rectangle, expand, corner);
+            let color = c.apply_alpha(color);
+            if c.skips(color) { return; }
+            back_end.tri_list_c(
+                &c.draw_state,
+                |f|
+            triangulation::with_polygon_feather_tri_list_c(
+                c.transform,
+                &outline[..],
+                width,
+                color,
+                |vertices, colors| f.emit(vertices, colors)
+            ));
+        }
+    }
+
+    /// Draws the rectangle with a draw state and transform,
+    /// for callers without a context.
+    ///
+    /// See `Context::from_state`.
+    pub fn draw_with_state<B>(
+        &self,
+        rectangle: internal::Rectangle,
+        draw_state: &DrawState,
+        transform: internal::Matrix2d,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        self.draw(rectangle, &Context::from_state(draw_state, transform), back_end);
+    }
+
+    /// Draws many rectangles with a color each, using the same draw state.
+    ///
+    /// The rectangles are filled with square corners.
+    /// They are streamed through as few calls to the back-end as possible,
+    /// see `graphics::tri_list_colored`. This is much faster than
+    /// calling `draw` for every rectangle, for example in bar charts.
+    pub fn draw_iter<I, B>(iter: I, c: &Context, back_end: &mut B)
+        where
+            I: IntoIterator<Item = (internal::Rectangle, internal::Color)>,
+            B: Graphics
+    {
+        use graphics::tri_list_colored;
+
+        let iter = iter.into_iter()
+            .map(|(rect, color)| (rect, c.apply_alpha(color)))
+            .filter(|&(_, color)| !c.skips(color));
+        tri_list_colored(back_end, &c.draw_state, iter, |rect, f| {
+            let mut vertices = triangulation::rect_tri_list_xy(c.transform, rect);
+            triangulation::ensure_ccw(&mut vertices);
+            f(&vertices)
+        });
+    }
+
+    /// Draws the rectangle filled with a gradient along the x axis,
+    /// instead of the color.
+    ///
+    /// The fill is always square, the border follows the shape.
+    /// See `triangulation::with_gradient_rect_tri_list_c`.
+    pub fn draw_gradient<B>(
+        &self,
+        gradient: &Gradient,
+        rectangle: internal::Rectangle,
+        c: &Context,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        if !gradient.stops.iter().all(|&(_, color)| c.skips(c.apply_alpha(color))) {
+            let gradient = gradient.fade(c.alpha);
+            back_end.tri_list_c(
+                &c.draw_state,
+                |f|
+            triangulation::with_gradient_rect_tri_list_c(
+                c.transform,
+                rectangle,
+                &gradient,
+                |vertices, colors| f.emit(vertices, colors)
+            ));
+        }
+
+        self.draw_border(rectangle, c, back_end);
+    }
+
+    /// Draws the layers of a shadow, from the outermost layer inwards.
+    fn draw_shadow<B>(
+        &self,
+        shadow: &Shadow,
+        rectangle: internal::Rectangle,
+        c: &Context,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        use std::num::Float;
+        use vecmath::margin_rectangle;
+
+        let n = shadow.layers;
+        let rect = [
+            rectangle[0] + shadow.offset[0],
+            rectangle[1] + shadow.offset[1],
+            rectangle[2],
+            rectangle[3]
+        ];
+        for k in (0..n).rev() {
+            let t = k as internal::Scalar / n as internal::Scalar;
+            let expand = shadow.blur * t;
+            let alpha = (1.0 - t as internal::ColorComponent).powf(shadow.falloff)
+                / n as internal::ColorComponent;
+            let shape = match self.shape {
+                Shape::Square if expand > 0.0 => Shape::Round(expand),
+                Shape::Square => Shape::Square,
+                Shape::Round(radius) => Shape::Round(radius + expand),
+                Shape::Bevel(radius) => Shape::Bevel(radius + expand),
+                Shape::RoundCorners(radii) => Shape::RoundCorners([
+                    radii[0] + expand, radii[1] + expand,
+                    radii[2] + expand, radii[3] + expand
+                ]),
+            };
+            let color = shadow.color;
+            Rectangle {
+                color: [color[0], color[1], color[2], color[3] * alpha],
+                shape: shape,
+                border: None,
+                shadow: None,
+                antialias: None,
+                resolution: self.resolution,
+            }.draw(margin_rectangle(rect, -expand), c, back_end);
+        }
+    }
+
+    /// Draws only the border of the rectangle, without the fill.
+    ///
+    /// The corners of a square border are mitered.
+    /// Does nothing when the rectangle has no border.
+    pub fn draw_border<B>(
+        &self,
+        rectangle: internal::Rectangle,
+        c: &Context,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
         if let Some(Border { color, radius: border_radius }) = self.border {
-            if color[3] == 0.0 { return; }
+            let color = c.apply_alpha(color);
+            if c.skips(color) { return; }
             match self.shape {
                 Shape::Square => {
                     back_end.tri_list(
                         &c.draw_state,
                         &color,
-                        |f| f(
+                        |f| f.emit(
                             &triangulation::rect_border_tri_list_xy(
                                 c.transform, rectangle, border_radius),
                         )
@@ -186,12 +526,12 @@ impl Rectangle {
                         &color,
                         |f|
                     triangulation::with_round_rectangle_border_tri_list(
-                        128,
+                        4 * self.corner_resolution(round_radius + border_radius, c),
                         c.transform,
                         rectangle,
                         round_radius,
                         border_radius,
-                        |vertices| f(vertices)
+                        |vertices| f.emit(vertices)
                     ));
                 }
                 Shape::Bevel(bevel_radius) => {
@@ -205,7 +545,21 @@ impl Rectangle {
                         rectangle,
                         bevel_radius,
                         border_radius,
-                        |vertices| f(vertices)
+                        |vertices| f.emit(vertices)
+                    ));
+                }
+                Shape::RoundCorners(radii) => {
+                    back_end.tri_list(
+                        &c.draw_state,
+                        &color,
+                        |f|
+                    triangulation::with_round_corners_rectangle_border_tri_list(
+                        4 * self.corner_resolution(max_radius(radii) + border_radius, c),
+                        c.transform,
+                        rectangle,
+                        radii,
+                        border_radius,
+                        |vertices| f.emit(vertices)
                     ));
                 }
             }
@@ -213,6 +567,60 @@ impl Rectangle {
     }
 }
 
+impl Default for Rectangle {
+    /// A white rectangle with square corners and no border.
+    fn default() -> Rectangle {
+        Rectangle::new(color::WHITE)
+    }
+}
+
+/// Returns the largest of the corner radii.
+fn max_radius(radii: [internal::Radius; 4]) -> internal::Radius {
+    radii.iter().fold(0.0, |a, &b| if b > a { b } else { a })
+}
+
+/// Returns the outline of a shape as a polygon,
+/// expanded outwards by some distance.
+///
+/// Round corners have `resolution_corner` points each.
+fn outline(
+    shape: Shape,
+    rectangle: internal::Rectangle,
+    expand: internal::Scalar,
+    resolution_corner: usize
+) -> Vec<internal::Vec2d> {
+    use std::num::Float;
+    use vecmath::margin_rectangle;
+
+    let r = margin_rectangle(rectangle, -expand);
+    let (x, y, w, h) = (r[0], r[1], r[2], r[3]);
+    let (radius, resolution) = match shape {
+        Shape::Square => return vec![[x, y], [x + w, y], [x + w, y + h], [x, y + h]],
+        Shape::RoundCorners(radii) => return (0..4 * resolution_corner)
+            .map(|j| triangulation::round_corners_rectangle_point(
+                resolution_corner, rectangle, radii, j, expand))
+            .collect(),
+        Shape::Round(radius) => (radius + expand, resolution_corner),
+        Shape::Bevel(radius) => (radius + expand, 2),
+    };
+    let corners = [
+        [x + w - radius, y + h - radius],
+        [x + radius, y + h - radius],
+        [x + radius, y + radius],
+        [x + w - radius, y + radius]
+    ];
+    let quarter: internal::Scalar = Radians::_90();
+    let mut points = Vec::with_capacity(4 * resolution);
+    for (k, corner) in corners.iter().enumerate() {
+        for i in 0..resolution {
+            let angle = (k as internal::Scalar
+                + i as internal::Scalar / (resolution - 1) as internal::Scalar) * quarter;
+            points.push([corner[0] + angle.cos() * radius, corner[1] + angle.sin() * radius]);
+        }
+    }
+    points
+}
+
 quack! {
     r: Rectangle[]
     get:
@@ -224,11 +632,13 @@ quack! {
         fn (val: Shape) [] { r.shape = val }
         fn (val: Border) [] { r.border = Some(val) }
         fn (val: MaybeBorder) [] { r.border = val.0 }
+        fn (val: Shadow) [] { r.shadow = Some(val) }
     action:
 }
 
 #[cfg(test)]
 mod test {
+    use std::num::Float;
     use super::Rectangle;
     use super::Shape;
     use super::Border;
@@ -242,4 +652,181 @@ mod test {
             .set(Shape::Round(10.0))
             .set(Border { color: [0.0; 4], radius: 4.0 });
     }
+
+    #[test]
+    fn test_default() {
+        use triangulation::Resolution;
+
+        assert_eq!(Rectangle::default(), Rectangle::new([1.0; 4]));
+        let literal = Rectangle {
+            color: [1.0, 0.0, 0.0, 1.0],
+            shape: Shape::Round(4.0),
+            border: Some(Border { color: [0.0; 4], radius: 1.0 }),
+            shadow: None,
+            antialias: Some(1.0),
+            resolution: Resolution::Fixed(64),
+        };
+        let built = Rectangle::round_border([0.0; 4], 4.0, 1.0)
+            .set(Color([1.0, 0.0, 0.0, 1.0]))
+            .antialias(1.0)
+            .resolution(Resolution::Fixed(64));
+        assert_eq!(built, literal);
+    }
+
+    #[test]
+    fn test_draw_gradient() {
+        use Context;
+        use color::Gradient;
+        use test_backend::RecordingGraphics;
+
+        let red = [1.0, 0.0, 0.0, 1.0];
+        let green = [0.0, 1.0, 0.0, 1.0];
+        let blue = [0.0, 0.0, 1.0, 1.0];
+        let gradient = Gradient::new(vec![(0.0, red), (1.0, blue), (0.25, green)]);
+        let mut g = RecordingGraphics::new();
+        Rectangle::new([1.0; 4]).draw_gradient(&gradient, [0.0, 0.0, 100.0, 10.0],
+            &Context::new(), &mut g);
+        assert_eq!(g.tri_lists.len(), 0);
+        let t = &g.tri_lists_c[0];
+        // Two strips of two triangles.
+        assert_eq!(t.vertices.len(), 2 * 2 * 6);
+        for (v, color) in t.vertices.chunks(2).zip(t.colors.chunks(4)) {
+            let expected = if v[0] == 0.0 { red }
+                else if v[0] == 25.0 { green }
+                else if v[0] == 100.0 { blue }
+                else { panic!("Unexpected edge at {}", v[0]) };
+            assert_eq!(color, &expected[..]);
+        }
+    }
+
+    #[test]
+    fn test_contains() {
+        let rect = [0.0, 0.0, 20.0, 10.0];
+        let square = Rectangle::new([1.0; 4]);
+        assert!(square.contains(rect, [0.0, 0.0]));
+        assert!(square.contains(rect, [20.0, 5.0]));
+        assert!(!square.contains(rect, [20.5, 5.0]));
+        let round = Rectangle::round([1.0; 4], 4.0);
+        assert!(!round.contains(rect, [0.5, 0.5]));
+        assert!(round.contains(rect, [4.0, 0.0]));
+        assert!(round.contains(rect, [19.0, 5.0]));
+        let corners = Rectangle::new([1.0; 4]).round_corners([4.0, 0.0, 0.0, 0.0]);
+        assert!(!corners.contains(rect, [0.5, 0.5]));
+        assert!(corners.contains(rect, [19.9, 0.1]));
+        assert!(Rectangle::border([1.0; 4], 1.0).contains(rect, [20.5, 5.0]));
+    }
+
+    #[test]
+    fn test_round_corners() {
+        use Context;
+        use test_backend::RecordingGraphics;
+
+        let mut g = RecordingGraphics::new();
+        Rectangle::new([1.0; 4]).round_corners([10.0, 0.0, 0.0, 0.0])
+            .draw([0.0, 0.0, 100.0, 50.0], &Context::new(), &mut g);
+        Rectangle::round_border([1.0; 4], 0.0, 1.0).round_corners([10.0, 0.0, 0.0, 0.0])
+            .draw([0.0, 0.0, 100.0, 50.0], &Context::new(), &mut g);
+        let sharp = [[100.0, 0.0], [100.0, 50.0], [0.0, 50.0]];
+        let mut arc = 0;
+        for v in g.tri_lists[0].vertices.chunks(2) {
+            if sharp.iter().any(|p| v[0] == p[0] && v[1] == p[1]) { continue; }
+            // Everything else is on the arc of the top-left corner.
+            let (dx, dy) = (v[0] - 10.0, v[1] - 10.0);
+            assert!(dx <= 0.0 && dy <= 0.0);
+            assert!((dx * dx + dy * dy - 100.0).abs() < 0.01);
+            arc += 1;
+        }
+        assert!(arc > 2);
+        // The border is mitered at the sharp corners.
+        let bottom_right = g.tri_lists.iter().skip(1)
+            .any(|t| t.vertices.chunks(2).any(|v| v[0] == 101.0 && v[1] == 51.0));
+        assert!(bottom_right);
+    }
+
+    #[test]
+    fn test_shadow() {
+        use Context;
+        use test_backend::RecordingGraphics;
+
+        let mut g = RecordingGraphics::new();
+        Rectangle::round([1.0; 4], 4.0)
+            .shadow([2.0, 2.0], 8.0, [0.0, 0.0, 0.0, 0.5])
+            .draw([0.0, 0.0, 40.0, 20.0], &Context::new(), &mut g);
+        // The shadow layers come before the fill.
+        assert_eq!(g.tri_lists.len(), 8 + 1);
+        assert_eq!(g.tri_lists[8].color, [1.0; 4]);
+        let extent = |vertices: &[f32]| vertices.chunks(2)
+            .fold(0.0, |m, v| if v[0] > m { v[0] } else { m });
+        for i in 1..8 {
+            let (outer, inner) = (&g.tri_lists[i - 1], &g.tri_lists[i]);
+            assert!(outer.color[3] < inner.color[3]);
+            assert!(extent(&outer.vertices) > extent(&inner.vertices));
+        }
+    }
+
+    #[test]
+    fn test_square_vertices() {
+        use Context;
+        use test_backend::RecordingGraphics;
+        use triangulation::rect_tri_list_xy;
+
+        let c = Context::abs(100.0, 100.0);
+        let rect = [10.0, 20.0, 30.0, 40.0];
+        let mut g = RecordingGraphics::new();
+        Rectangle::new([1.0; 4]).draw(rect, &c, &mut g);
+        assert_eq!(g.tri_lists.len(), 1);
+        assert_eq!(g.vertex_count(), 6);
+        // The same vertices, up to the winding.
+        let expected = rect_tri_list_xy(c.transform, rect);
+        for v in g.tri_lists[0].vertices.chunks(2) {
+            assert!(expected.chunks(2).any(|e| e == v));
+        }
+    }
+
+    #[test]
+    fn test_draw_iter() {
+        use Context;
+        use test_backend::RecordingGraphics;
+        use vecmath::Scalar;
+
+        let c = Context::new();
+        let rects = (0..100).map(|i| {
+            let color = if i % 2 == 0 { [1.0; 4] } else { [0.0, 0.0, 0.0, 1.0] };
+            ([i as Scalar, 0.0, 1.0, 1.0], color)
+        });
+        let mut g = RecordingGraphics::new();
+        Rectangle::draw_iter(rects, &c, &mut g);
+        assert_eq!(g.tri_lists.len(), 0);
+        assert_eq!(g.tri_lists_c.len(), 1);
+        let t = &g.tri_lists_c[0];
+        assert_eq!(t.vertices.len(), 100 * 12);
+        assert_eq!(t.colors.len(), 100 * 24);
+        assert_eq!(&t.colors[24..28], &[0.0, 0.0, 0.0, 1.0][..]);
+
+        let mut direct = RecordingGraphics::new();
+        Rectangle::new([1.0; 4]).draw([0.0, 0.0, 1.0, 1.0], &c, &mut direct);
+        assert_eq!(&t.vertices[..12], &direct.tri_lists[0].vertices[..]);
+    }
+
+    #[test]
+    fn test_draw_border() {
+        use Context;
+        use test_backend::RecordingGraphics;
+
+        let c = Context::new();
+        let rect = Rectangle::new([1.0; 4]).set(Border { color: [0.0, 0.0, 1.0, 1.0], radius: 0.1 });
+        let mut g = RecordingGraphics::new();
+        rect.draw_border([-0.5, -0.5, 1.0, 1.0], &c, &mut g);
+        assert_eq!(g.tri_lists.len(), 1);
+        assert_eq!(g.tri_lists[0].color, [0.0, 0.0, 1.0, 1.0]);
+        assert_eq!(g.vertex_count(), 24);
+        // No vertex lies strictly inside the inner edge of the border.
+        for v in g.tri_lists[0].vertices.chunks(2) {
+            assert!(v[0].abs() >= 0.4 - 0.0001 || v[1].abs() >= 0.4 - 0.0001);
+        }
+
+        let mut g = RecordingGraphics::new();
+        Rectangle::new([1.0; 4]).draw_border([0.0, 0.0, 1.0, 1.0], &c, &mut g);
+        assert_eq!(g.tri_lists.len(), 0);
+    }
 }
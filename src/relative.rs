@@ -183,16 +183,33 @@ impl<T: Get<SrcRect>
 }
 
 /// Implemented by contexts that can transform.
+///
+/// Also implemented by `Matrix2d`, which returns the combined matrix,
+/// so a one-off transform does not require a new context.
 pub trait RelativeTransform: Sized {
     /// Appends transform to the current one.
+    ///
+    /// Computes `current * transform`, so the transform is applied
+    /// first, in local coordinates.
     fn append_transform(&self, transform: Matrix2d) -> Self;
 
     /// Prepends transform to the current one.
+    ///
+    /// Computes `transform * current`, so the transform is applied
+    /// last, in normalized device coordinates.
+    /// To apply a transform in view coordinates,
+    /// see `Context::prepend_view_transform`.
     fn prepend_transform(&self, transform: Matrix2d) -> Self;
 
     /// Translate x an y in local coordinates.
     fn trans(&self, x: Scalar, y: Scalar) -> Self;
 
+    /// Translates by a position in local coordinates.
+    #[inline(always)]
+    fn trans_pos(&self, pos: Vec2d) -> Self {
+        self.trans(pos[0], pos[1])
+    }
+
     /// Rotates degrees in local coordinates.
     #[inline(always)]
     fn rot_deg(&self, angle: Scalar) -> Self {
@@ -203,6 +220,24 @@ pub trait RelativeTransform: Sized {
     /// Rotate radians in local coordinates.
     fn rot_rad(&self, angle: Scalar) -> Self;
 
+    /// Rotates degrees about a pivot in local coordinates.
+    #[inline(always)]
+    fn rot_deg_about(&self, pivot: Vec2d, angle: Scalar) -> Self {
+        let pi: Scalar = Radians::_180();
+        self.rot_rad_about(pivot, angle * pi / 180.0)
+    }
+
+    /// Rotates radians about a pivot in local coordinates.
+    ///
+    /// Translates to the pivot, rotates and translates back,
+    /// so the pivot stays in place. To spin a sprite in place,
+    /// use the center of its rectangle, `[x + w / 2, y + h / 2]`,
+    /// or see `Image::rot_rad_center`.
+    #[inline(always)]
+    fn rot_rad_about(&self, pivot: Vec2d, angle: Scalar) -> Self {
+        self.trans(pivot[0], pivot[1]).rot_rad(angle).trans(-pivot[0], -pivot[1])
+    }
+
     /// Orients x axis to look at point locally.
     ///
     /// Leaves x axis unchanged if the point to
@@ -212,6 +247,12 @@ pub trait RelativeTransform: Sized {
     /// Scales in local coordinates.
     fn scale(&self, sx: Scalar, sy: Scalar) -> Self;
 
+    /// Scales by a vector in local coordinates.
+    #[inline(always)]
+    fn scale_pos(&self, s: Vec2d) -> Self {
+        self.scale(s[0], s[1])
+    }
+
     /// Scales in both directions in local coordinates.
     #[inline(always)]
     fn zoom(&self, s: Scalar) -> Self {
@@ -293,6 +334,57 @@ impl<T: Clone> RelativeTransform for T
     }
 }
 
+/// Implemented by contexts that can set the transform in view coordinates.
+///
+/// `RelativeTransform` composes with the current transform,
+/// so the result depends on earlier transforms.
+/// The methods of this trait replace the current transform
+/// with the view transform followed by a single transformation,
+/// which is useful for elements pinned to the view, like a HUD.
+pub trait AbsoluteTransform: Sized {
+    /// Translates x and y in view coordinates.
+    fn trans_abs(&self, x: Scalar, y: Scalar) -> Self;
+
+    /// Rotates degrees in view coordinates.
+    #[inline(always)]
+    fn rot_deg_abs(&self, angle: Scalar) -> Self {
+        let pi: Scalar = Radians::_180();
+        self.rot_rad_abs(angle * pi / 180.0)
+    }
+
+    /// Rotates radians in view coordinates.
+    fn rot_rad_abs(&self, angle: Scalar) -> Self;
+
+    /// Scales in view coordinates.
+    fn scale_abs(&self, sx: Scalar, sy: Scalar) -> Self;
+}
+
+impl<T: Clone> AbsoluteTransform for T
+    where
+        (ViewTransform, Self): Pair<Data = ViewTransform, Object = Self>
+            + GetFrom + SetAt,
+        (Transform, Self): Pair<Data = Transform, Object = Self>
+            + GetFrom + SetAt,
+{
+    #[inline(always)]
+    fn trans_abs(&self, x: Scalar, y: Scalar) -> Self {
+        let ViewTransform(mat) = self.get();
+        self.clone().set(Transform(multiply(mat, translate([x, y]))))
+    }
+
+    #[inline(always)]
+    fn rot_rad_abs(&self, angle: Scalar) -> Self {
+        let ViewTransform(mat) = self.get();
+        self.clone().set(Transform(multiply(mat, rotate_radians(angle))))
+    }
+
+    #[inline(always)]
+    fn scale_abs(&self, sx: Scalar, sy: Scalar) -> Self {
+        let ViewTransform(mat) = self.get();
+        self.clone().set(Transform(multiply(mat, scale(sx, sy))))
+    }
+}
+
 /// Should be implemented by contexts that
 /// draws something relative to view.
 pub trait RelativeViewTransform {
@@ -301,6 +393,10 @@ pub trait RelativeViewTransform {
     /// This is usually [0.0, 0.0] in the upper left corner
     /// with the x axis pointing to the right
     /// and the y axis pointing down.
+    ///
+    /// Everything else, like the draw state, is kept.
+    /// This is useful for drawing a user interface
+    /// after world content drawn with a camera transform.
     #[inline(always)]
     fn view(&self) -> Self;
 
@@ -309,9 +405,12 @@ pub trait RelativeViewTransform {
     /// This is usually [0.0, 0.0] in the center
     /// with the x axis pointing to the right
     /// and the y axis pointing up.
+    /// Use `view` to return to the view coordinate system.
     fn reset(&self) -> Self;
 
     /// Stores the current transform as new view.
+    ///
+    /// A later call to `view` returns to this transform.
     fn store_view(&self) -> Self;
 
     /// Computes the current view size.
@@ -346,7 +445,8 @@ impl<T: Clone> RelativeViewTransform for T
     fn get_view_size(&self) -> (Scalar, Scalar) {
         let ViewTransform(mat) = self.get();
         let scale = get_scale(mat);
-        (2.0 / scale[0], 2.0 / scale[1])
+        let size = |s: Scalar| if s == 0.0 { 0.0 } else { 2.0 / s };
+        (size(scale[0]), size(scale[1]))
     }
 }
 
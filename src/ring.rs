@@ -0,0 +1,201 @@
+//! Draw ring
+
+use internal;
+use triangulation;
+use triangulation::Resolution;
+use std::num::Float;
+use radians::Radians;
+use Graphics;
+use Context;
+use Color;
+
+/// A filled ring between an inner and an outer radius
+#[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Ring {
+    /// The ring color
+    #[cfg_attr(feature = "serde", serde(serialize_with = "::serde_color::serialize",
+        deserialize_with = "::serde_color::deserialize"))]
+    pub color: internal::Color,
+    /// The inner radius
+    pub inner_radius: internal::Radius,
+    /// The outer radius
+    pub outer_radius: internal::Radius,
+    /// The number of segments in a full circle
+    pub resolution: Resolution,
+    /// The start angle in radians
+    pub start: internal::Scalar,
+    /// The end angle in radians
+    pub end: internal::Scalar,
+}
+
+impl Ring {
+    /// Creates a new ring
+    pub fn new(
+        color: internal::Color,
+        inner_radius: internal::Radius,
+        outer_radius: internal::Radius
+    ) -> Ring {
+        Ring {
+            color: color,
+            inner_radius: inner_radius,
+            outer_radius: outer_radius,
+            resolution: Resolution::Fixed(128),
+            start: 0.0,
+            end: Radians::_360(),
+        }
+    }
+
+    /// Creates a new arc segment of a ring.
+    ///
+    /// The start and end angles are in radians.
+    pub fn arc(
+        color: internal::Color,
+        inner_radius: internal::Radius,
+        outer_radius: internal::Radius,
+        start: internal::Scalar,
+        end: internal::Scalar
+    ) -> Ring {
+        Ring {
+            start: start,
+            end: end,
+            ..Ring::new(color, inner_radius, outer_radius)
+        }
+    }
+
+    /// Sets the number of segments in the full ring.
+    ///
+    /// `Resolution::Adaptive` uses the outer radius on the screen,
+    /// so small rings use fewer triangles and large ones do not show facets.
+    /// Arcs keep the segment density of the full ring.
+    pub fn resolution(mut self, resolution: Resolution) -> Ring {
+        self.resolution = resolution;
+        self
+    }
+
+    // Use the same segment density for arcs as for the full ring.
+    fn segments(&self, radius: internal::Radius, c: &Context) -> usize {
+        let resolution = self.resolution.segments(radius / c.local_width(1.0));
+        triangulation::arc_segments(resolution, self.start, self.end)
+    }
+
+    /// Draws the ring around a center.
+    ///
+    /// Draws nothing when the inner radius is not less than the outer radius.
+    pub fn draw<B>(
+        &self,
+        center: internal::Vec2d,
+        c: &Context,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        let color = c.apply_alpha(self.color);
+        if c.skips(color) { return; }
+        if self.inner_radius >= self.outer_radius { return; }
+
+        let n = self.segments(self.outer_radius, c);
+        back_end.tri_strip(
+            &c.draw_state,
+            &color,
+            |f|
+        triangulation::with_ring_tri_strip(
+            n,
+            c.transform,
+            center,
+            self.inner_radius,
+            self.outer_radius,
+            self.start,
+            self.end,
+            |vertices| f.emit_strip(vertices)
+        ));
+    }
+
+    /// Draws the ring between the ellipses of two rectangles.
+    ///
+    /// The inner rectangle must be inside the outer one,
+    /// but the ellipses do not need to share the center.
+    /// An inner rectangle of zero size draws a filled ellipse.
+    /// The radii of the ring are ignored.
+    pub fn draw_rects<B>(
+        &self,
+        outer: internal::Rectangle,
+        inner: internal::Rectangle,
+        c: &Context,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        let color = c.apply_alpha(self.color);
+        if c.skips(color) { return; }
+
+        let n = self.segments(outer[2].abs().max(outer[3].abs()) / 2.0, c);
+        back_end.tri_strip(
+            &c.draw_state,
+            &color,
+            |f|
+        triangulation::with_ellipse_ring_tri_strip(
+            n,
+            c.transform,
+            outer,
+            inner,
+            self.start,
+            self.end,
+            |vertices| f.emit_strip(vertices)
+        ));
+    }
+}
+
+quack! {
+    r: Ring[]
+    get:
+        fn () -> Color [] { Color(r.color) }
+    set:
+        fn (val: Color) [] { r.color = val.0 }
+    action:
+}
+
+#[cfg(test)]
+mod test {
+    use super::Ring;
+    use triangulation;
+    use vecmath::identity;
+    use radians::Radians;
+    use Color;
+    use quack::Set;
+
+    #[test]
+    fn test_ring() {
+        let _ring = Ring::new([1.0; 4], 5.0, 10.0)
+            .set(Color([0.0; 4]));
+    }
+
+    #[test]
+    fn test_ring_vertex_count() {
+        let resolution = 16;
+        let mut vertices = 0;
+        triangulation::with_ring_tri_strip(
+            resolution,
+            identity(),
+            [0.0, 0.0],
+            5.0,
+            10.0,
+            0.0,
+            Radians::_360(),
+            |v| vertices += v.len() / 2
+        );
+        assert_eq!(vertices, 2 * (resolution + 1));
+    }
+
+    #[test]
+    fn test_adaptive_resolution() {
+        use triangulation::Resolution;
+        use Context;
+
+        let c = Context::abs(100.0, 100.0);
+        let ring = Ring::new([1.0; 4], 5.0, 10.0).resolution(Resolution::Adaptive(0.25));
+        assert!(ring.segments(10.0, &c) < ring.segments(100.0, &c));
+        let arc = Ring { start: 0.0, end: Radians::_180(), ..ring };
+        assert!(arc.segments(100.0, &c) < ring.segments(100.0, &c));
+    }
+}
@@ -0,0 +1,152 @@
+//! Draw sector
+
+use internal;
+use triangulation;
+use triangulation::Resolution;
+use std::num::Float;
+use radians::Radians;
+use Graphics;
+use Context;
+use Color;
+
+/// A filled sector of an ellipse, like a slice of a pie chart
+#[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Sector {
+    /// The sector color
+    #[cfg_attr(feature = "serde", serde(serialize_with = "::serde_color::serialize",
+        deserialize_with = "::serde_color::deserialize"))]
+    pub color: internal::Color,
+    /// The start angle in radians
+    pub start: internal::Scalar,
+    /// The end angle in radians
+    pub end: internal::Scalar,
+    /// The number of segments in a full circle
+    pub resolution: Resolution,
+}
+
+impl Sector {
+    /// Creates a new sector.
+    ///
+    /// The angles are in radians.
+    pub fn new(
+        color: internal::Color,
+        start: internal::Scalar,
+        end: internal::Scalar
+    ) -> Sector {
+        Sector {
+            color: color,
+            start: start,
+            end: end,
+            resolution: Resolution::Fixed(128),
+        }
+    }
+
+    /// Sets the number of segments in the full ellipse.
+    ///
+    /// `Resolution::Adaptive` uses the larger radius of the ellipse
+    /// on the screen. The sector keeps the segment density of the full ellipse.
+    pub fn resolution(mut self, resolution: Resolution) -> Sector {
+        self.resolution = resolution;
+        self
+    }
+
+    /// Returns the start and end angle covered by the sector.
+    ///
+    /// An end angle less than the start angle wraps around
+    /// through the full circle, and spans of a full circle
+    /// or more are clamped to one full circle.
+    pub fn angles(&self) -> (internal::Scalar, internal::Scalar) {
+        let full: internal::Scalar = Radians::_360();
+        let mut span = self.end - self.start;
+        if span < 0.0 { span += full; }
+        if span > full { span = full; }
+        (self.start, self.start + span)
+    }
+
+    /// Draws the sector inside the bounding rectangle of the ellipse.
+    pub fn draw<B>(
+        &self,
+        rectangle: internal::Rectangle,
+        c: &Context,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        let color = c.apply_alpha(self.color);
+        if c.skips(color) { return; }
+
+        let (start, end) = self.angles();
+        let radius = rectangle[2].abs().max(rectangle[3].abs()) / 2.0;
+        let resolution = self.resolution.segments(radius / c.local_width(1.0));
+        let n = triangulation::arc_segments(resolution, start, end);
+        back_end.tri_list(
+            &c.draw_state,
+            &color,
+            |f|
+        triangulation::with_sector_tri_list(
+            n,
+            c.transform,
+            rectangle,
+            start,
+            end,
+            |vertices| f.emit(vertices)
+        ));
+    }
+}
+
+quack! {
+    s: Sector[]
+    get:
+        fn () -> Color [] { Color(s.color) }
+    set:
+        fn (val: Color) [] { s.color = val.0 }
+    action:
+}
+
+#[cfg(test)]
+mod test {
+    use super::Sector;
+    use internal::Scalar;
+    use std::num::Float;
+    use radians::Radians;
+    use test_backend::RecordingGraphics;
+    use Context;
+
+    #[test]
+    fn test_sector() {
+        let pi: Scalar = Radians::_180();
+        let c = Context::new();
+        let mut g = RecordingGraphics::new();
+        // Wraps around from 3/2 pi to 1/2 pi, a half circle.
+        Sector::new([1.0; 4], 1.5 * pi, 0.5 * pi)
+            .draw([-1.0, -1.0, 2.0, 2.0], &c, &mut g);
+        let v = &g.tri_lists[0].vertices;
+        assert_eq!(v.len() / 6, 64);
+        // Every triangle starts in the center.
+        assert_eq!((v[0], v[1]), (0.0, 0.0));
+        let angle = |x: f32, y: f32| (y as Scalar).atan2(x as Scalar);
+        assert!((angle(v[2], v[3]) + 0.5 * pi).abs() < 1e-5);
+        let n = v.len();
+        assert!((angle(v[n - 2], v[n - 1]) - 0.5 * pi).abs() < 1e-5);
+
+        // A full disk.
+        let mut g = RecordingGraphics::new();
+        Sector::new([1.0; 4], 0.0, 3.0 * pi)
+            .draw([-1.0, -1.0, 2.0, 2.0], &c, &mut g);
+        assert_eq!(g.vertex_count() / 3, 128);
+    }
+
+    #[test]
+    fn test_adaptive_resolution() {
+        use triangulation::Resolution;
+
+        let pi: Scalar = Radians::_180();
+        let c = Context::abs(100.0, 100.0);
+        let sector = Sector::new([1.0; 4], 0.0, pi).resolution(Resolution::Adaptive(0.25));
+        let (mut small, mut large) = (RecordingGraphics::new(), RecordingGraphics::new());
+        sector.draw([0.0, 0.0, 10.0, 10.0], &c, &mut small);
+        sector.draw([0.0, 0.0, 100.0, 100.0], &c, &mut large);
+        assert!(small.vertex_count() < large.vertex_count());
+    }
+}
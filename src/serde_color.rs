@@ -0,0 +1,222 @@
+//! Serialization of colors.
+//!
+//! Colors are written as `[r, g, b, a]` sequences and may be read back
+//! either from a sequence or from a hexadecimal string like `"FF0000"`.
+
+use serde::{ Serialize, Serializer, Deserializer };
+use serde::de::{ self, Visitor, SeqVisitor };
+
+use color;
+use internal;
+use Color;
+
+struct ColorVisitor;
+
+impl Visitor for ColorVisitor {
+    type Value = internal::Color;
+
+    fn visit_str<E>(&mut self, v: &str) -> Result<internal::Color, E>
+        where E: de::Error
+    {
+        match color::try_hex(v) {
+            Some(c) => Ok(c),
+            None => Err(E::invalid_value("expected a hexadecimal color string"))
+        }
+    }
+
+    fn visit_seq<V>(&mut self, mut seq: V) -> Result<internal::Color, V::Error>
+        where V: SeqVisitor
+    {
+        let mut c = [0.0; 4];
+        for i in 0..4 {
+            c[i] = match try!(seq.visit()) {
+                Some(x) => x,
+                None => return Err(de::Error::invalid_length(i))
+            };
+        }
+        try!(seq.end());
+        Ok(c)
+    }
+}
+
+/// Serializes a color as an `[r, g, b, a]` sequence.
+pub fn serialize<S>(c: &internal::Color, s: &mut S) -> Result<(), S::Error>
+    where S: Serializer
+{
+    c.serialize(s)
+}
+
+/// Deserializes a color from a sequence or a hexadecimal string.
+pub fn deserialize<D>(d: &mut D) -> Result<internal::Color, D::Error>
+    where D: Deserializer
+{
+    d.deserialize(ColorVisitor)
+}
+
+pub mod option {
+    //! Serialization of optional colors.
+
+    use serde::{ Serializer, Deserializer, Deserialize };
+    use internal;
+    use Color;
+
+    /// Serializes an optional color.
+    pub fn serialize<S>(
+        c: &Option<internal::Color>,
+        s: &mut S
+    ) -> Result<(), S::Error>
+        where S: Serializer
+    {
+        match *c {
+            Some(c) => s.serialize_some(Color(c)),
+            None => s.serialize_none()
+        }
+    }
+
+    /// Deserializes an optional color.
+    pub fn deserialize<D>(d: &mut D) -> Result<Option<internal::Color>, D::Error>
+        where D: Deserializer
+    {
+        let c: Option<Color> = try!(Deserialize::deserialize(d));
+        Ok(c.map(|Color(c)| c))
+    }
+}
+
+impl ::serde::Serialize for Color {
+    fn serialize<S>(&self, s: &mut S) -> Result<(), S::Error>
+        where S: Serializer
+    {
+        serialize(&self.0, s)
+    }
+}
+
+impl ::serde::Deserialize for Color {
+    fn deserialize<D>(d: &mut D) -> Result<Color, D::Error>
+        where D: Deserializer
+    {
+        deserialize(d).map(Color)
+    }
+}
+
+#[cfg(all(test, feature = "serde_json"))]
+mod test {
+    use serde::{ Serialize, Deserialize };
+    use serde_json;
+    use blend::BlendPreset;
+    use color::Gradient;
+    use context::{ Transform, ViewTransform };
+    use ellipse;
+    use image::Fit;
+    use line::{ self, PixelLine };
+    use rectangle;
+    use text::Outline;
+    use triangulation::{ FillRule, Resolution };
+    use spline::Spline;
+    use {
+        Capsule, Checkerboard, Color, Ellipse, Image, Line, Polygon,
+        Rect, Rectangle, Ring, Sector, SrcRect, Text
+    };
+
+    /// Checks that a value is written the same after a round trip,
+    /// and returns the JSON.
+    fn round_trip<T>(value: &T) -> String
+        where T: Serialize + Deserialize
+    {
+        let json = serde_json::to_string(value).unwrap();
+        let back: T = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&back).unwrap(), json);
+        json
+    }
+
+    #[test]
+    fn test_rectangle() {
+        let rect = Rectangle::round([1.0, 0.0, 0.0, 1.0], 4.0)
+            .shadow([2.0, 2.0], 4.0, [0.0, 0.0, 0.0, 0.5])
+            .antialias(1.0)
+            .resolution(Resolution::Adaptive(0.5));
+        round_trip(&rect);
+        let back: Rectangle = serde_json::from_str(&round_trip(&rect)).unwrap();
+        assert_eq!(back, rect);
+        round_trip(&Rectangle::border([1.0; 4], 2.0));
+        round_trip(&Rectangle::new([1.0; 4]).round_corners([1.0, 2.0, 3.0, 4.0]));
+        round_trip(&rectangle::Shape::Bevel(2.0));
+        round_trip(&rectangle::Border { color: [1.0; 4], radius: 2.0 });
+    }
+
+    #[test]
+    fn test_shapes() {
+        let e = Ellipse::border([0.0, 0.0, 1.0, 1.0], 2.0).antialias(1.0);
+        let back: Ellipse = serde_json::from_str(&round_trip(&e)).unwrap();
+        assert_eq!(back, e);
+        round_trip(&ellipse::Border { color: [1.0; 4], radius: 1.0 });
+        let l = Line::round([1.0; 4], 2.0).antialias(1.0);
+        let back: Line = serde_json::from_str(&round_trip(&l)).unwrap();
+        assert_eq!(back, l);
+        round_trip(&line::Shape::Bevel);
+        round_trip(&line::Join::Round);
+        round_trip(&PixelLine::new([1.0; 4]));
+        let p = Polygon::new([1.0; 4]).antialias(1.0);
+        let back: Polygon = serde_json::from_str(&round_trip(&p)).unwrap();
+        assert_eq!(back, p);
+        round_trip(&Ring::new([1.0; 4], 5.0, 10.0).resolution(Resolution::Fixed(32)));
+        round_trip(&Sector::new([1.0; 4], 0.0, 1.0));
+        round_trip(&Capsule::border([1.0; 4], 2.0));
+        round_trip(&Checkerboard::new([1.0; 4], [0.0, 0.0, 0.0, 1.0], 8.0));
+        round_trip(&Spline::new([1.0; 4], 2.0));
+        round_trip(&FillRule::EvenOdd);
+    }
+
+    #[test]
+    fn test_image_and_text() {
+        let img = Image::colored([0.0, 1.0, 0.0, 1.0]).flip_uv_h().trim([1.0; 4]);
+        let back: Image = serde_json::from_str(&round_trip(&img)).unwrap();
+        assert_eq!(back, img);
+        round_trip(&Image::new());
+        round_trip(&Fit::Contain);
+        let text = Text::new(14).outline([1.0; 4], 1.0);
+        let back: Text = serde_json::from_str(&round_trip(&text)).unwrap();
+        assert_eq!(back, text);
+        round_trip(&Outline { color: [1.0; 4], thickness: 2.0 });
+    }
+
+    #[test]
+    fn test_colors() {
+        let Color(c) = serde_json::from_str("\"FF0000\"").unwrap();
+        assert_eq!(c, [1.0, 0.0, 0.0, 1.0]);
+        round_trip(&Color([0.5, 0.25, 0.0, 1.0]));
+        let img: Image = serde_json::from_str(
+            "{\"color\":\"00FF00\",\"rectangle\":null,\"source_rectangle\":null}"
+        ).unwrap();
+        assert_eq!(img.color, Some([0.0, 1.0, 0.0, 1.0]));
+        assert!(serde_json::from_str::<Color>("\"nope\"").is_err());
+        assert!(serde_json::from_str::<Color>("[1.0, 0.0]").is_err());
+        round_trip(&Gradient::new(vec![(0.0, [1.0; 4]), (1.0, [0.0, 0.0, 0.0, 1.0])]));
+    }
+
+    #[test]
+    fn test_transform() {
+        let t = Transform([[1.0, 0.0, 2.0], [0.0, 1.0, 3.0]]);
+        let Transform(back) = serde_json::from_str(&round_trip(&t)).unwrap();
+        assert_eq!(back, t.0);
+        let ViewTransform(back) = serde_json::from_str(&round_trip(&ViewTransform(t.0))).unwrap();
+        assert_eq!(back, t.0);
+        let Rect(back) = serde_json::from_str(&round_trip(&Rect([1.0, 2.0, 3.0, 4.0]))).unwrap();
+        assert_eq!(back, [1.0, 2.0, 3.0, 4.0]);
+        let SrcRect(back) = serde_json::from_str(&round_trip(&SrcRect([1, 2, 3, 4]))).unwrap();
+        assert_eq!(back, [1, 2, 3, 4]);
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Preset {
+        #[serde(serialize_with = "::blend::serialize_preset",
+            deserialize_with = "::blend::deserialize_preset")]
+        preset: BlendPreset,
+    }
+
+    #[test]
+    fn test_blend_preset() {
+        assert_eq!(round_trip(&Preset { preset: BlendPreset::Add }), "{\"preset\":\"Add\"}");
+        assert_eq!(round_trip(&Preset { preset: BlendPreset::Alpha }), "{\"preset\":\"Alpha\"}");
+        assert!(serde_json::from_str::<Preset>("{\"preset\":\"Nope\"}").is_err());
+    }
+}
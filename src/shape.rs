@@ -0,0 +1,132 @@
+//! A common interface for drawing primitives with their geometry
+//!
+//! Each primitive is paired with its geometry in a tuple,
+//! like `(Rectangle, [x, y, w, h])`, which implements `Shape`.
+//! Shapes of different types can be stored as `Box<Shape<G>>`
+//! for a retained list of drawables, see `draw_visible`.
+
+use std::boxed::Box;
+use std::vec::Vec;
+use internal;
+use vecmath::margin_rectangle;
+use Context;
+use Ellipse;
+use Graphics;
+use Line;
+use Polygon;
+use Rectangle;
+
+/// A primitive with its geometry.
+pub trait Shape<G: Graphics> {
+    /// Draws the shape.
+    fn draw(&self, c: &Context, g: &mut G);
+
+    /// Returns the bounding rectangle [x, y, w, h] in local coordinates.
+    ///
+    /// Borders are included, but not drop shadows or feathered edges.
+    fn bounds(&self) -> internal::Rectangle;
+}
+
+impl<G: Graphics> Shape<G> for (Rectangle, internal::Rectangle) {
+    fn draw(&self, c: &Context, g: &mut G) {
+        self.0.draw(self.1, c, g);
+    }
+
+    fn bounds(&self) -> internal::Rectangle {
+        match self.0.border {
+            Some(border) => margin_rectangle(self.1, -border.radius),
+            None => self.1
+        }
+    }
+}
+
+impl<G: Graphics> Shape<G> for (Ellipse, internal::Rectangle) {
+    fn draw(&self, c: &Context, g: &mut G) {
+        self.0.draw(self.1, c, g);
+    }
+
+    fn bounds(&self) -> internal::Rectangle {
+        match self.0.border {
+            Some(border) => margin_rectangle(self.1, -border.radius),
+            None => self.1
+        }
+    }
+}
+
+impl<G: Graphics> Shape<G> for (Line, internal::Line) {
+    fn draw(&self, c: &Context, g: &mut G) {
+        self.0.draw(self.1, c, g);
+    }
+
+    fn bounds(&self) -> internal::Rectangle {
+        let l = self.1;
+        let points = [[l[0], l[1]], [l[2], l[3]]];
+        margin_rectangle(bounding_rectangle(&points), -self.0.radius)
+    }
+}
+
+impl<G: Graphics> Shape<G> for (Polygon, Vec<internal::Vec2d>) {
+    fn draw(&self, c: &Context, g: &mut G) {
+        self.0.draw(&self.1[..], c, g);
+    }
+
+    fn bounds(&self) -> internal::Rectangle {
+        bounding_rectangle(&self.1[..])
+    }
+}
+
+/// Draws the shapes in order, skipping those outside the view.
+///
+/// See `Context::culls`.
+pub fn draw_visible<G>(shapes: &[Box<Shape<G>>], c: &Context, g: &mut G)
+    where G: Graphics
+{
+    for shape in shapes.iter() {
+        if c.culls(shape.bounds()) { continue; }
+        shape.draw(c, g);
+    }
+}
+
+/// Returns the smallest rectangle containing the points.
+///
+/// Returns an empty rectangle at the origin when there are no points.
+fn bounding_rectangle(points: &[internal::Vec2d]) -> internal::Rectangle {
+    use std::num::Float;
+
+    if points.len() == 0 { return [0.0; 4]; }
+    let (mut min, mut max) = (points[0], points[0]);
+    for p in points.iter() {
+        for i in 0..2 {
+            min[i] = min[i].min(p[i]);
+            max[i] = max[i].max(p[i]);
+        }
+    }
+    [min[0], min[1], max[0] - min[0], max[1] - min[1]]
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ draw_visible, Shape };
+    use test_backend::RecordingGraphics;
+    use { Context, Ellipse, Line, Polygon, Rectangle };
+
+    #[test]
+    fn test_retained_list() {
+        let c = Context::abs(100.0, 100.0);
+        let shapes: Vec<Box<Shape<RecordingGraphics>>> = vec![
+            Box::new((Rectangle::new([1.0; 4]), [10.0, 10.0, 20.0, 20.0])),
+            Box::new((Ellipse::new([1.0; 4]), [200.0, 10.0, 20.0, 20.0])),
+            Box::new((Line::new([1.0; 4], 2.0), [-10.0, 50.0, 10.0, 50.0])),
+            Box::new((Polygon::new([1.0; 4]),
+                vec![[0.0, -30.0], [10.0, -30.0], [5.0, -20.0]])),
+        ];
+        assert_eq!(shapes[0].bounds(), [10.0, 10.0, 20.0, 20.0]);
+        assert_eq!(shapes[2].bounds(), [-12.0, 48.0, 24.0, 4.0]);
+        assert_eq!(shapes[3].bounds(), [0.0, -30.0, 10.0, 10.0]);
+
+        let mut g = RecordingGraphics::new();
+        draw_visible(&shapes[..], &c, &mut g);
+        // The ellipse and the polygon are outside the view.
+        assert_eq!(g.tri_lists.len(), 2);
+    }
+}
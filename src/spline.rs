@@ -0,0 +1,163 @@
+//! Draw spline
+
+use std::vec::Vec;
+use internal;
+use line::{ Join, Shape };
+use triangulation;
+use Graphics;
+use Context;
+use Color;
+
+/// A smooth curve passing through points
+///
+/// The curve is a cardinal spline, which is a Catmull-Rom spline
+/// when the tension is zero.
+#[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Spline {
+    /// The spline color
+    #[cfg_attr(feature = "serde", serde(serialize_with = "::serde_color::serialize",
+        deserialize_with = "::serde_color::deserialize"))]
+    pub color: internal::Color,
+    /// The line radius
+    pub radius: internal::Radius,
+    /// The tension, where 0 is Catmull-Rom and 1 gives straight lines
+    pub tension: internal::Scalar,
+    /// The number of line segments between two points
+    pub subdivisions: usize,
+}
+
+impl Spline {
+    /// Creates a new Catmull-Rom spline
+    pub fn new(color: internal::Color, radius: internal::Radius) -> Spline {
+        Spline {
+            color: color,
+            radius: radius,
+            tension: 0.0,
+            subdivisions: 16,
+        }
+    }
+
+    /// Draws the spline through the points.
+    ///
+    /// The curve is drawn as one polyline with round joins and ends,
+    /// see `triangulation::with_polyline_tri_list`.
+    /// Draws nothing for fewer than 2 points.
+    pub fn draw<B>(
+        &self,
+        points: &[internal::Vec2d],
+        c: &Context,
+        back_end: &mut B
+    )
+        where B: Graphics
+    {
+        let color = c.apply_alpha(self.color);
+        if points.len() < 2 || c.skips(color) { return; }
+
+        let curve = cardinal_spline(points, self.tension, self.subdivisions);
+        back_end.tri_list(
+            &c.draw_state,
+            &color,
+            |f|
+        triangulation::with_polyline_tri_list(
+            // The same density as the round ends of `Line`.
+            128,
+            c.transform,
+            &curve,
+            false,
+            self.radius,
+            Shape::Round,
+            Join::Round,
+            |vertices| f.emit(vertices)
+        ));
+    }
+}
+
+/// Computes the points of a cardinal spline through the points.
+///
+/// Each segment between two points is divided into `subdivisions` parts.
+/// The end points are repeated to compute the tangents at the ends,
+/// so 2 points give a straight line.
+pub fn cardinal_spline(
+    points: &[internal::Vec2d],
+    tension: internal::Scalar,
+    subdivisions: usize
+) -> Vec<internal::Vec2d> {
+    use internal::Scalar;
+
+    let n = points.len();
+    if n < 2 { return points.to_vec(); }
+
+    let subdivisions = if subdivisions == 0 { 1 } else { subdivisions };
+    let s = 0.5 * (1.0 - tension);
+    let mut res = Vec::with_capacity((n - 1) * subdivisions + 1);
+    res.push(points[0]);
+    for i in 0..n - 1 {
+        let p0 = points[if i == 0 { 0 } else { i - 1 }];
+        let (p1, p2) = (points[i], points[i + 1]);
+        let p3 = points[if i + 2 < n { i + 2 } else { n - 1 }];
+        let m1 = [s * (p2[0] - p0[0]), s * (p2[1] - p0[1])];
+        let m2 = [s * (p3[0] - p1[0]), s * (p3[1] - p1[1])];
+        for j in 1..subdivisions + 1 {
+            // Cubic Hermite basis.
+            let t = j as Scalar / subdivisions as Scalar;
+            let (t2, t3) = (t * t, t * t * t);
+            let h00 = 2.0 * t3 - 3.0 * t2 + 1.0;
+            let h10 = t3 - 2.0 * t2 + t;
+            let h01 = -2.0 * t3 + 3.0 * t2;
+            let h11 = t3 - t2;
+            res.push([
+                h00 * p1[0] + h10 * m1[0] + h01 * p2[0] + h11 * m2[0],
+                h00 * p1[1] + h10 * m1[1] + h01 * p2[1] + h11 * m2[1]
+            ]);
+        }
+    }
+    res
+}
+
+quack! {
+    s: Spline[]
+    get:
+        fn () -> Color [] { Color(s.color) }
+    set:
+        fn (val: Color) [] { s.color = val.0 }
+    action:
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ cardinal_spline, Spline };
+    use std::num::Float;
+    use test_backend::RecordingGraphics;
+    use Context;
+
+    #[test]
+    fn test_collinear() {
+        let points = [[0.0, 0.0], [1.0, 2.0], [3.0, 6.0], [4.0, 8.0]];
+        let curve = cardinal_spline(&points, 0.0, 8);
+        assert_eq!(curve.len(), 3 * 8 + 1);
+        assert_eq!(curve[8], [1.0, 2.0]);
+        assert_eq!(curve[24], [4.0, 8.0]);
+        for p in curve.iter() {
+            assert!((p[1] - 2.0 * p[0]).abs() < 0.00001);
+        }
+    }
+
+    #[test]
+    fn test_spline() {
+        let c = Context::new();
+        let spline = Spline { subdivisions: 4, ..Spline::new([1.0; 4], 0.5) };
+        let mut g = RecordingGraphics::new();
+        spline.draw(&[[0.0, 0.0]], &c, &mut g);
+        assert_eq!(g.tri_lists.len(), 0);
+        spline.draw(&[[0.0, 0.0], [1.0, 0.0]], &c, &mut g);
+        // One polyline of 4 segments with round ends.
+        assert_eq!(g.tri_lists.len(), 1);
+        assert_eq!(g.vertex_count(), (4 * 2 + 2 * 64) * 3);
+        // Joins fill the outside of corners.
+        let mut g = RecordingGraphics::new();
+        spline.draw(&[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0]], &c, &mut g);
+        assert_eq!(g.tri_lists.len(), 1);
+        assert!(g.vertex_count() > (8 * 2 + 2 * 64) * 3);
+    }
+}
@@ -0,0 +1,84 @@
+//! Clipping with the stencil buffer
+//!
+//! Clipping to an arbitrary shape takes two passes:
+//!
+//! 1. Clear the stencil buffer with `Graphics::clear_stencil(0)`
+//!    and draw the mask shape with `c.stencil(Stencil::Clip(1))`.
+//!    This writes the value to the stencil buffer without drawing colors.
+//! 2. Draw the content with `c.stencil(Stencil::Inside(1))`
+//!    to draw only inside the mask, or `Stencil::Outside(1)`
+//!    to draw only outside the mask.
+//!
+//! Back-ends without a stencil buffer ignore the stencil settings.
+
+use draw_state::DrawState;
+use draw_state::block::{
+    self,
+    Comparison,
+    StencilOp,
+    StencilSide,
+};
+
+/// How a draw call uses the stencil buffer.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Stencil {
+    /// Writes the value to the stencil buffer without drawing colors
+    Clip(u8),
+    /// Draws where the stencil buffer equals the value
+    Inside(u8),
+    /// Draws where the stencil buffer does not equal the value
+    Outside(u8),
+}
+
+impl Stencil {
+    /// Returns the draw state with the stencil settings applied.
+    pub fn apply(self, mut draw_state: DrawState) -> DrawState {
+        let side = |fun, value, op_pass| StencilSide {
+            fun: fun,
+            value: value,
+            mask_read: 255,
+            mask_write: 255,
+            op_fail: StencilOp::Keep,
+            op_depth_fail: StencilOp::Keep,
+            op_pass: op_pass,
+        };
+        let side = match self {
+            Stencil::Clip(value) => {
+                draw_state.color_mask = block::MASK_NONE;
+                side(Comparison::Always, value, StencilOp::Replace)
+            }
+            Stencil::Inside(value) =>
+                side(Comparison::Equal, value, StencilOp::Keep),
+            Stencil::Outside(value) =>
+                side(Comparison::NotEqual, value, StencilOp::Keep),
+        };
+        draw_state.stencil = Some(block::Stencil { front: side, back: side });
+        draw_state
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Stencil;
+    use draw_state::block::{ Comparison, StencilOp };
+    use Context;
+
+    #[test]
+    fn test_stencil() {
+        let c = Context::new();
+        assert!(c.draw_state.stencil.is_none());
+
+        let mask = c.stencil(Stencil::Clip(1)).draw_state.stencil.unwrap();
+        assert_eq!(mask.front.fun, Comparison::Always);
+        assert_eq!(mask.front.op_pass, StencilOp::Replace);
+        assert_eq!(mask.front.value, 1);
+
+        let inside = c.stencil(Stencil::Inside(1)).draw_state.stencil.unwrap();
+        assert_eq!(inside.front.fun, Comparison::Equal);
+        assert_eq!(inside.back.op_pass, StencilOp::Keep);
+
+        let outside = c.stencil(Stencil::Outside(2)).draw_state.stencil.unwrap();
+        assert_eq!(outside.front.fun, Comparison::NotEqual);
+        assert_eq!(outside.front.value, 2);
+    }
+}
@@ -0,0 +1,169 @@
+//! SVG export back-end
+//!
+//! Enabled with the `svg` feature.
+
+use std::io::{ self, Write };
+use std::marker::PhantomData;
+use draw_state::DrawState;
+use internal;
+use { AttributeEmitter, Emitter, Graphics };
+use ImageSize;
+
+/// A back-end that records drawing as an SVG document.
+///
+/// Every `tri_list` call becomes one `<path>` element with the
+/// triangles merged into its path data.
+/// Textured draws have no texture data available,
+/// so they are emitted as placeholders in the tint color.
+pub struct SvgGraphics<T> {
+    /// The width of the document in pixels.
+    pub width: u32,
+    /// The height of the document in pixels.
+    pub height: u32,
+    /// The SVG elements recorded so far.
+    pub elements: Vec<String>,
+    texture: PhantomData<T>,
+}
+
+impl<T> SvgGraphics<T> {
+    /// Creates a new SVG back-end with a document size in pixels.
+    pub fn new(width: u32, height: u32) -> SvgGraphics<T> {
+        SvgGraphics {
+            width: width,
+            height: height,
+            elements: Vec::new(),
+            texture: PhantomData,
+        }
+    }
+
+    /// Returns the complete SVG document.
+    pub fn document(&self) -> String {
+        let mut res = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" \
+            width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n",
+            self.width, self.height, self.width, self.height
+        );
+        for element in self.elements.iter() {
+            res.push_str(&element[..]);
+            res.push('\n');
+        }
+        res.push_str("</svg>\n");
+        res
+    }
+
+    /// Consumes the back-end and returns the complete SVG document.
+    pub fn into_string(self) -> String {
+        self.document()
+    }
+
+    /// Writes the complete SVG document.
+    pub fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(self.document().as_bytes())
+    }
+
+    fn push_triangles(&mut self, color: &internal::Color, vertices: &[f32]) {
+        let (w, h) = (self.width as f32, self.height as f32);
+        let mut data = String::new();
+        for tri in vertices.chunks(6) {
+            if tri.len() < 6 { break; }
+            data.push_str(&format!(
+                "M{} {} L{} {} L{} {} Z ",
+                (tri[0] + 1.0) * 0.5 * w, (1.0 - tri[1]) * 0.5 * h,
+                (tri[2] + 1.0) * 0.5 * w, (1.0 - tri[3]) * 0.5 * h,
+                (tri[4] + 1.0) * 0.5 * w, (1.0 - tri[5]) * 0.5 * h
+            )[..]);
+        }
+        if data.len() == 0 { return; }
+
+        self.elements.push(format!(
+            "<path d=\"{}\" {}/>",
+            data.trim_right(),
+            fill(color)
+        ));
+    }
+}
+
+/// Formats fill attributes from a color.
+fn fill(color: &internal::Color) -> String {
+    fn component(f: f32) -> u8 {
+        let f = if f < 0.0 { 0.0 } else if f > 1.0 { 1.0 } else { f };
+        (f * 255.0 + 0.5) as u8
+    }
+    format!(
+        "fill=\"rgb({},{},{})\" fill-opacity=\"{}\"",
+        component(color[0]),
+        component(color[1]),
+        component(color[2]),
+        color[3]
+    )
+}
+
+impl<T: ImageSize> Graphics for SvgGraphics<T> {
+    type Texture = T;
+
+    fn clear(&mut self, color: [f32; 4]) {
+        // Everything drawn so far is hidden by the background.
+        self.elements.clear();
+        let background = format!(
+            "<rect width=\"{}\" height=\"{}\" {}/>",
+            self.width, self.height, fill(&color)
+        );
+        self.elements.push(background);
+    }
+
+    fn tri_list<F>(&mut self, _draw_state: &DrawState, color: &[f32; 4], mut f: F)
+        where F: FnMut(&mut Emitter)
+    {
+        let mut vertices = Vec::new();
+        f(&mut Emitter::new(&mut vertices));
+        self.push_triangles(color, &vertices);
+    }
+
+    fn tri_list_uv<F>(
+        &mut self,
+        _draw_state: &DrawState,
+        color: &[f32; 4],
+        _texture: &T,
+        mut f: F
+    ) where F: FnMut(&mut AttributeEmitter)
+    {
+        let (mut vertices, mut uvs) = (Vec::new(), Vec::new());
+        f(&mut AttributeEmitter::new(&mut vertices, &mut uvs));
+        self.push_triangles(color, &vertices);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SvgGraphics;
+    use { Context, Ellipse, Rectangle };
+    use ImageSize;
+
+    struct Texture;
+
+    impl ImageSize for Texture {
+        fn get_size(&self) -> (u32, u32) { (1, 1) }
+    }
+
+    #[test]
+    fn test_svg() {
+        let c = Context::abs(100.0, 100.0);
+        let mut g: SvgGraphics<Texture> = SvgGraphics::new(100, 100);
+        ::clear([1.0; 4], &mut g);
+        Rectangle::new([1.0, 0.0, 0.0, 1.0])
+            .draw([10.0, 10.0, 30.0, 30.0], &c, &mut g);
+        Ellipse::new([0.0, 0.0, 1.0, 0.5])
+            .draw([50.0, 50.0, 40.0, 40.0], &c, &mut g);
+        assert_eq!(g.elements.len(), 3);
+        assert!(g.elements[0].starts_with("<rect"));
+        assert!(g.elements[0].contains("rgb(255,255,255)"));
+        assert!(g.elements[1].starts_with("<path"));
+        assert!(g.elements[1].contains("rgb(255,0,0)"));
+        assert!(g.elements[2].contains("rgb(0,0,255)"));
+        assert!(g.elements[2].contains("fill-opacity=\"0.5\""));
+
+        let doc = g.into_string();
+        assert!(doc.starts_with("<svg"));
+        assert!(doc.ends_with("</svg>\n"));
+    }
+}
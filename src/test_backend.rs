@@ -0,0 +1,399 @@
+//! A back-end that records draw calls, for testing.
+//!
+//! Enabled in the tests of this crate and with the `testing` feature.
+
+use std::vec::Vec;
+use draw_state::DrawState;
+use { AttributeEmitter, Emitter, Graphics };
+use ImageSize;
+
+/// A texture with a fixed size of 256x256 pixels.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Texture;
+
+impl ImageSize for Texture {
+    fn get_size(&self) -> (u32, u32) { (256, 256) }
+}
+
+/// A recorded call to `tri_list`.
+#[derive(Clone)]
+pub struct TriList {
+    /// The draw state
+    pub draw_state: DrawState,
+    /// The color
+    pub color: [f32; 4],
+    /// The vertices of all chunks, in order
+    pub vertices: Vec<f32>,
+}
+
+/// A recorded call to `tri_list_uv`.
+#[derive(Clone)]
+pub struct TriListUv {
+    /// The draw state
+    pub draw_state: DrawState,
+    /// The color
+    pub color: [f32; 4],
+    /// The vertices of all chunks, in order
+    pub vertices: Vec<f32>,
+    /// The texture coordinates of all chunks, in order
+    pub uvs: Vec<f32>,
+}
+
+/// A recorded call to `tri_list_c`.
+#[derive(Clone)]
+pub struct TriListC {
+    /// The draw state
+    pub draw_state: DrawState,
+    /// The vertices of all chunks, in order
+    pub vertices: Vec<f32>,
+    /// The colors of all chunks, four components per vertex
+    pub colors: Vec<f32>,
+}
+
+/// Records every call to the back-end.
+///
+/// Triangle strips are recorded as triangle lists,
+/// using the default conversion of `Graphics`.
+#[derive(Clone)]
+pub struct RecordingGraphics {
+    /// The colors of calls to `clear`
+    pub clears: Vec<[f32; 4]>,
+    /// The values of calls to `clear_stencil`
+    pub stencil_clears: Vec<u8>,
+    /// The calls to `tri_list`
+    pub tri_lists: Vec<TriList>,
+    /// The calls to `tri_list_uv`
+    pub tri_lists_uv: Vec<TriListUv>,
+    /// The calls to `tri_list_c`
+    pub tri_lists_c: Vec<TriListC>,
+}
+
+impl RecordingGraphics {
+    /// Creates a new recording back-end.
+    pub fn new() -> RecordingGraphics {
+        RecordingGraphics {
+            clears: Vec::new(),
+            stencil_clears: Vec::new(),
+            tri_lists: Vec::new(),
+            tri_lists_uv: Vec::new(),
+            tri_lists_c: Vec::new(),
+        }
+    }
+
+    /// Returns the number of vertices recorded by `tri_list`
+    /// and `tri_list_uv`.
+    pub fn vertex_count(&self) -> usize {
+        let a = self.tri_lists.iter()
+            .fold(0, |n, t| n + t.vertices.len() / 2);
+        let b = self.tri_lists_uv.iter()
+            .fold(0, |n, t| n + t.vertices.len() / 2);
+        a + b
+    }
+}
+
+impl Graphics for RecordingGraphics {
+    type Texture = Texture;
+
+    fn clear(&mut self, color: [f32; 4]) {
+        self.clears.push(color);
+    }
+
+    fn clear_stencil(&mut self, value: u8) {
+        self.stencil_clears.push(value);
+    }
+
+    fn tri_list<F>(&mut self, draw_state: &DrawState, color: &[f32; 4], mut f: F)
+        where F: FnMut(&mut Emitter)
+    {
+        let mut vertices = Vec::new();
+        f(&mut Emitter::new(&mut vertices));
+        self.tri_lists.push(TriList {
+            draw_state: draw_state.clone(),
+            color: *color,
+            vertices: vertices,
+        });
+    }
+
+    fn tri_list_uv<F>(
+        &mut self,
+        draw_state: &DrawState,
+        color: &[f32; 4],
+        _texture: &Texture,
+        mut f: F
+    ) where F: FnMut(&mut AttributeEmitter)
+    {
+        let mut vertices = Vec::new();
+        let mut uvs = Vec::new();
+        f(&mut AttributeEmitter::new(&mut vertices, &mut uvs));
+        self.tri_lists_uv.push(TriListUv {
+            draw_state: draw_state.clone(),
+            color: *color,
+            vertices: vertices,
+            uvs: uvs,
+        });
+    }
+
+    fn has_vertex_colors(&self) -> bool { true }
+
+    fn tri_list_c<F>(&mut self, draw_state: &DrawState, mut f: F)
+        where F: FnMut(&mut AttributeEmitter)
+    {
+        let mut vertices = Vec::new();
+        let mut colors = Vec::new();
+        f(&mut AttributeEmitter::new(&mut vertices, &mut colors));
+        self.tri_lists_c.push(TriListC {
+            draw_state: draw_state.clone(),
+            vertices: vertices,
+            colors: colors,
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ RecordingGraphics, Texture };
+    use { Capsule, Context, Ellipse, Image, Line, Polygon, Rectangle, Ring, Sector };
+
+    #[test]
+    fn test_rectangle() {
+        let c = Context::abs(100.0, 100.0);
+        let mut g = RecordingGraphics::new();
+        ::clear([1.0; 4], &mut g);
+        Rectangle::new([1.0, 0.0, 0.0, 1.0])
+            .draw([0.0, 0.0, 50.0, 50.0], &c, &mut g);
+        assert_eq!(g.clears, vec![[1.0; 4]]);
+        assert_eq!(g.tri_lists.len(), 1);
+        assert_eq!(g.tri_lists[0].color, [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(g.tri_lists[0].vertices.len(), 6 * 2);
+        assert_eq!(g.vertex_count(), 6);
+    }
+
+    #[test]
+    fn test_image() {
+        let c = Context::abs(100.0, 100.0);
+        let mut g = RecordingGraphics::new();
+        Image::new().draw(&Texture, &c, &mut g);
+        assert_eq!(g.tri_lists_uv.len(), 1);
+        assert_eq!(g.tri_lists_uv[0].uvs.len(), 6 * 2);
+        assert_eq!(g.tri_lists_uv[0].color, [1.0; 4]);
+    }
+
+    #[test]
+    fn test_transparent() {
+        use draw_state::BlendPreset;
+
+        let c = Context::abs(100.0, 100.0);
+        let rect = [0.0, 0.0, 10.0, 10.0];
+        let draws = |alpha: f32, c: &Context| {
+            let color = [1.0, 0.0, 0.0, alpha];
+            let mut g = RecordingGraphics::new();
+            Rectangle::new(color).draw(rect, c, &mut g);
+            Ellipse::new(color).draw(rect, c, &mut g);
+            Line::new(color, 1.0).draw(rect, c, &mut g);
+            Polygon::new(color).draw(&[[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]], c, &mut g);
+            Ring::new(color, 1.0, 2.0).draw([0.0, 0.0], c, &mut g);
+            Image::colored(color).draw(&Texture, c, &mut g);
+            g.tri_lists.len() + g.tri_lists_uv.len()
+        };
+        assert_eq!(draws(0.0, &c), 0);
+        assert_eq!(draws(0.5, &c), 6);
+        // Zero alpha may still change the render target without alpha blending.
+        let add = Context { draw_state: c.draw_state.blend(BlendPreset::Add), ..c };
+        assert_eq!(draws(0.0, &add), 6);
+
+        // Fill and border are skipped independently.
+        let opaque = [1.0; 4];
+        let transparent = [0.0; 4];
+        let mut g = RecordingGraphics::new();
+        Rectangle::border(transparent, 1.0).draw(rect, &c, &mut g);
+        Ellipse::border(transparent, 1.0).draw(rect, &c, &mut g);
+        assert_eq!(g.tri_lists.len(), 0);
+        Rectangle { color: opaque, ..Rectangle::border(transparent, 1.0) }
+            .draw(rect, &c, &mut g);
+        Ellipse { color: opaque, ..Ellipse::border(transparent, 1.0) }
+            .draw(rect, &c, &mut g);
+        assert_eq!(g.tri_lists.len(), 2);
+        Rectangle::border(opaque, 1.0).draw(rect, &c, &mut g);
+        Ellipse::border(opaque, 1.0).draw(rect, &c, &mut g);
+        assert_eq!(g.tri_lists.len(), 4);
+    }
+
+    #[test]
+    fn test_winding() {
+        use RelativeTransform;
+        use line::PixelLine;
+        use path::Path;
+
+        let color = [1.0; 4];
+        let rect = [10.0, 10.0, 30.0, 20.0];
+        let abs = Context::abs(100.0, 100.0);
+        for c in [Context::new(), abs, abs.flip_h(), abs.rot_deg(30.0)].iter() {
+            let mut g = RecordingGraphics::new();
+            Rectangle::new(color).draw(rect, c, &mut g);
+            // Negative sizes reverse the corners.
+            Rectangle::new(color).draw([40.0, 30.0, -30.0, -20.0], c, &mut g);
+            Rectangle::draw_iter(vec![([40.0, 10.0, -30.0, 20.0], color)], c, &mut g);
+            Rectangle::new(color).antialias(1.0).draw([40.0, 10.0, -30.0, 20.0], c, &mut g);
+            Rectangle::round(color, 4.0).draw(rect, c, &mut g);
+            Rectangle::border(color, 2.0).draw(rect, c, &mut g);
+            Rectangle::round_border(color, 4.0, 2.0).draw(rect, c, &mut g);
+            Ellipse::new(color).draw(rect, c, &mut g);
+            Ellipse::border(color, 2.0).draw(rect, c, &mut g);
+            Line::round(color, 2.0).draw([10.0, 10.0, 40.0, 30.0], c, &mut g);
+            Line::new(color, 2.0).draw_iter(vec![([40.0, 30.0, 10.0, 10.0], color)], c, &mut g);
+            PixelLine::new(color).draw([10.0, 30.0, 40.0, 10.0], c, &mut g);
+            // Clockwise and counter-clockwise polygons.
+            Polygon::new(color).draw(&[[0.0, 0.0], [10.0, 0.0], [0.0, 10.0]], c, &mut g);
+            Polygon::new(color).draw(&[[0.0, 0.0], [0.0, 10.0], [10.0, 0.0]], c, &mut g);
+            Ring::new(color, 5.0, 10.0).draw([50.0, 50.0], c, &mut g);
+            Sector::new(color, 0.0, 2.0).draw(rect, c, &mut g);
+            Capsule::new(color).draw(rect, c, &mut g);
+            Path::new().move_to(0.0, 0.0).line_to(20.0, 0.0).line_to(10.0, 20.0)
+                .close().fill(color, c, &mut g);
+            Image::new().draw(&Texture, c, &mut g);
+            Image::new().draw_quad([[0.0, 0.0], [2.0, 0.0], [2.0, 2.0], [0.0, 2.0]],
+                2, &Texture, c, &mut g);
+
+            let lists = g.tri_lists.iter().map(|t| &t.vertices)
+                .chain(g.tri_lists_uv.iter().map(|t| &t.vertices))
+                .chain(g.tri_lists_c.iter().map(|t| &t.vertices));
+            for vertices in lists {
+                for t in vertices.chunks(6) {
+                    let area = (t[2] - t[0]) * (t[5] - t[1]) - (t[4] - t[0]) * (t[3] - t[1]);
+                    assert!(area >= -1e-6);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_finite() {
+        use std::num::Float;
+        use triangulation::Resolution;
+        use { RelativeTransform, RelativeViewTransform };
+
+        let color = [1.0; 4];
+        let rect = [10.0, 10.0, 30.0, 20.0];
+        let abs = Context::abs(100.0, 100.0);
+        let contexts = [Context::abs(0.0, 100.0), Context::abs(100.0, 0.0),
+            Context::from_viewport([0, 0, 0, 0]), abs.orient(0.0, 0.0)];
+        let mut g = RecordingGraphics::new();
+        for c in contexts.iter() {
+            Rectangle::new(color).antialias(1.0).draw(rect, c, &mut g);
+            Ellipse::new(color).resolution(Resolution::Adaptive(0.5)).draw(rect, c, &mut g);
+            Line::round(color, 2.0).draw([10.0, 10.0, 40.0, 30.0], c, &mut g);
+        }
+        for &size in [[-30.0, -20.0], [0.0, 0.0], [-30.0, 0.0]].iter() {
+            Ellipse::border(color, 2.0).antialias(1.0).resolution(Resolution::Adaptive(0.5))
+                .draw([10.0, 10.0, size[0], size[1]], &abs, &mut g);
+        }
+        assert!(g.tri_lists.len() > 0);
+        let lists = g.tri_lists.iter().map(|t| &t.vertices)
+            .chain(g.tri_lists_c.iter().map(|t| &t.vertices));
+        for vertices in lists {
+            assert!(vertices.iter().all(|v| v.is_finite()));
+        }
+        assert_eq!(Context::abs(0.0, 100.0).get_view_size(), (0.0, 100.0));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn test_non_finite() {
+        use std::num::Float;
+
+        let nan: ::internal::Scalar = Float::nan();
+        let mut g = RecordingGraphics::new();
+        Rectangle::new([1.0; 4]).draw([nan, 0.0, 10.0, 10.0], &Context::new(), &mut g);
+    }
+
+    #[test]
+    fn test_antialias() {
+        let color = [1.0, 0.5, 0.25, 1.0];
+        let c = Context::abs(100.0, 100.0);
+        let mut g = RecordingGraphics::new();
+        Ellipse::new(color).antialias(1.0).draw([10.0, 10.0, 20.0, 20.0], &c, &mut g);
+        Polygon::new(color).antialias(1.0)
+            .draw(&[[0.0, 0.0], [10.0, 0.0], [0.0, 10.0]], &c, &mut g);
+        Line::round(color, 2.0).antialias(1.0).draw([0.0, 0.0, 20.0, 0.0], &c, &mut g);
+        assert_eq!(g.tri_lists.len(), 3);
+        assert_eq!(g.tri_lists_c.len(), 3);
+        let polygon = &g.tri_lists_c[1];
+        // Two triangles per edge.
+        assert_eq!(polygon.vertices.len(), 3 * 2 * 6);
+        for t in g.tri_lists_c.iter() {
+            assert_eq!(t.colors.len(), t.vertices.len() * 2);
+            let (mut inner, mut outer) = (0, 0);
+            for v in t.colors.chunks(4) {
+                if v[3] == 0.0 {
+                    assert_eq!(&v[..3], &color[..3]);
+                    outer += 1;
+                } else {
+                    assert_eq!(v, &color[..]);
+                    inner += 1;
+                }
+            }
+            assert_eq!(inner, outer);
+        }
+    }
+
+    #[test]
+    fn test_antialias_scale() {
+        use std::num::Float;
+        use Transformed;
+
+        let color = [1.0, 0.5, 0.25, 1.0];
+        let c = Context::abs(100.0, 100.0);
+        let mut g = RecordingGraphics::new();
+        let square = [[10.0, 10.0], [20.0, 10.0], [20.0, 20.0], [10.0, 20.0]];
+        Polygon::new(color).antialias(1.0).draw(&square, &c.scale(2.0, 2.0), &mut g);
+        Rectangle::new(color).antialias(1.0)
+            .draw([10.0, 10.0, 10.0, 10.0], &c.scale(2.0, 2.0), &mut g);
+        Rectangle::new(color).antialias(1.0).draw([20.0, 20.0, 20.0, 20.0], &c, &mut g);
+        assert_eq!(g.tri_lists_c.len(), 3);
+        for t in g.tri_lists_c.iter() {
+            // The transparent vertices are one pixel outside the square,
+            // which spans 20 to 40 pixels in both directions.
+            for (v, col) in t.vertices.chunks(2).zip(t.colors.chunks(4)) {
+                let (x, y) = ((v[0] + 1.0) * 50.0, (1.0 - v[1]) * 50.0);
+                let d = if col[3] == 0.0 { 1.0 } else { 0.0 };
+                assert_eq!(&col[..3], &color[..3]);
+                assert!((x - 20.0 + d).abs() < 0.001 || (x - 40.0 - d).abs() < 0.001);
+                assert!((y - 20.0 + d).abs() < 0.001 || (y - 40.0 - d).abs() < 0.001);
+            }
+        }
+    }
+
+    #[test]
+    fn test_draw_with_state() {
+        use Transformed;
+
+        let c = Context::abs(100.0, 100.0).trans(10.0, 20.0).rot_deg(30.0);
+        let color = [1.0, 0.5, 0.25, 1.0];
+        let rect = [0.0, 0.0, 30.0, 20.0];
+        let polygon = [[0.0, 0.0], [10.0, 0.0], [5.0, 10.0]];
+        let line = [0.0, 0.0, 30.0, 40.0];
+        let (mut a, mut b) = (RecordingGraphics::new(), RecordingGraphics::new());
+        Rectangle::new(color).draw(rect, &c, &mut a);
+        Rectangle::new(color).draw_with_state(rect, &c.draw_state, c.transform, &mut b);
+        Ellipse::new(color).draw(rect, &c, &mut a);
+        Ellipse::new(color).draw_with_state(rect, &c.draw_state, c.transform, &mut b);
+        Line::new(color, 2.0).draw(line, &c, &mut a);
+        Line::new(color, 2.0).draw_with_state(line, &c.draw_state, c.transform, &mut b);
+        Polygon::new(color).draw(&polygon, &c, &mut a);
+        Polygon::new(color).draw_with_state(&polygon, &c.draw_state, c.transform, &mut b);
+        Image::new().draw(&Texture, &c, &mut a);
+        Image::new().draw_with_state(&Texture, &c.draw_state, c.transform, &mut b);
+
+        assert_eq!(a.tri_lists.len(), 4);
+        assert_eq!(a.tri_lists.len(), b.tri_lists.len());
+        for (x, y) in a.tri_lists.iter().zip(b.tri_lists.iter()) {
+            assert_eq!(x.color, y.color);
+            assert_eq!(x.vertices, y.vertices);
+        }
+        assert_eq!(a.tri_lists_uv.len(), 1);
+        assert_eq!(b.tri_lists_uv.len(), 1);
+        assert_eq!(a.tri_lists_uv[0].vertices, b.tri_lists_uv[0].vertices);
+        assert_eq!(a.tri_lists_uv[0].uvs, b.tri_lists_uv[0].uvs);
+    }
+}
@@ -1,20 +1,53 @@
 //! Draw text
 
+use std::string::{ String, ToString };
+use std::vec::Vec;
+use std::num::Float;
 use color;
 use internal;
+use radians::Radians;
 use Image;
 use Context;
+use DrawState;
 use Graphics;
 use character::CharacterCache;
+use triangulation;
 use RelativeTransform;
 
+/// Text outline
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Outline {
+    /// The outline color
+    #[cfg_attr(feature = "serde", serde(serialize_with = "::serde_color::serialize",
+        deserialize_with = "::serde_color::deserialize"))]
+    pub color: internal::Color,
+    /// The outline thickness, in the same units as the font size
+    pub thickness: internal::Width,
+}
+
 /// Renders text
-#[derive(Copy, Clone)]
+///
+/// Create it with `new`, `colored` or `Default` and the builder methods.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Text {
     /// The color
+    #[cfg_attr(feature = "serde", serde(serialize_with = "::serde_color::serialize",
+        deserialize_with = "::serde_color::deserialize"))]
     pub color: internal::Color,
     /// The font size
     pub font_size: internal::FontSize,
+    /// The outline
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub outline: Option<Outline>,
+}
+
+impl Default for Text {
+    /// Black text with font size 12 and no outline.
+    fn default() -> Text {
+        Text::new(12)
+    }
 }
 
 impl Text {
@@ -23,6 +56,7 @@ impl Text {
         Text {
             color: color::BLACK,
             font_size: font_size,
+            outline: None,
         }
     }
 
@@ -34,36 +68,468 @@ impl Text {
         Text {
             color: color,
             font_size: font_size,
+            outline: None,
         }
     }
 
+    /// Adds an outline.
+    ///
+    /// Each character is drawn offset in 8 directions
+    /// with the outline color before the text is drawn.
+    /// The thickness scales with the transform.
+    pub fn outline(
+        mut self,
+        color: internal::Color,
+        thickness: internal::Width
+    ) -> Text {
+        self.outline = Some(Outline { color: color, thickness: thickness });
+        self
+    }
+
     /// Draws text with a character cache
+    ///
+    /// Returns the error of the cache if a character can not be loaded.
     pub fn draw<C, B>(
         &self, 
         text: &str, 
         cache: &mut C, 
         c: &Context, 
         back_end: &mut B
-    )
+    ) -> Result<(), <C as CharacterCache>::Error>
+        where
+            C: CharacterCache,
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        self.draw_spans(&[(text, self.color)], cache, c, back_end)
+    }
+
+    /// Draws text with a draw state and transform,
+    /// for callers without a context.
+    ///
+    /// See `Context::from_state`.
+    pub fn draw_with_state<C, B>(
+        &self,
+        text: &str,
+        cache: &mut C,
+        draw_state: &DrawState,
+        transform: internal::Matrix2d,
+        back_end: &mut B
+    ) -> Result<(), <C as CharacterCache>::Error>
+        where
+            C: CharacterCache,
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        self.draw(text, cache, &Context::from_state(draw_state, transform), back_end)
+    }
+
+    /// Draws spans of text with a color each, laid out as one line.
+    ///
+    /// The spans use the font size of the text,
+    /// and kerning applies across span boundaries.
+    /// The outline, if any, is drawn for all spans first.
+    pub fn draw_spans<C, B>(
+        &self,
+        spans: &[(&str, internal::Color)],
+        cache: &mut C,
+        c: &Context,
+        back_end: &mut B
+    ) -> Result<(), <C as CharacterCache>::Error>
         where
             C: CharacterCache,
             B: Graphics<Texture = <C as CharacterCache>::Texture>
     {
-        let image = Image::colored(self.color);
-        let mut x = 0;
-        let mut y = 0;
-        for ch in text.chars() {
-            let character = cache.character(self.font_size, ch);
-            image.draw(&character.texture,
-                &c.trans(
-                    x as f64 + character.left(),
-                    y as f64 - character.top()
-                ),
-                back_end
+        if let Some(Outline { color, thickness }) = self.outline {
+            if !c.skips(c.apply_alpha(color)) {
+                let step: internal::Scalar = Radians::_360();
+                for i in 0..8 {
+                    let angle = step * i as internal::Scalar / 8.0;
+                    let offset = c.trans(thickness * angle.cos(), thickness * angle.sin());
+                    try!(self.draw_characters(spans, Some(color), cache, &offset, back_end));
+                }
+            }
+        }
+        self.draw_characters(spans, None, cache, c, back_end)
+    }
+
+    /// Computes the width of spans of text, as drawn by `draw_spans`.
+    pub fn width_spans<C>(
+        &self,
+        spans: &[(&str, internal::Color)],
+        cache: &mut C
+    ) -> Result<internal::Scalar, <C as CharacterCache>::Error>
+        where C: CharacterCache
+    {
+        self.layout(spans, cache, |_, _, _, _, _| Ok(()))
+    }
+
+    /// Draws the characters of the spans,
+    /// with the color of each span unless a color is given.
+    ///
+    /// Characters in a shared texture are batched,
+    /// see `CharacterCache::atlas_region`.
+    fn draw_characters<C, B>(
+        &self,
+        spans: &[(&str, internal::Color)],
+        color: Option<internal::Color>,
+        cache: &mut C,
+        c: &Context,
+        back_end: &mut B
+    ) -> Result<(), <C as CharacterCache>::Error>
+        where
+            C: CharacterCache,
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        let mut batch = GlyphBatch {
+            id: None,
+            ch: ' ',
+            color: [0.0; 4],
+            vertices: Vec::new(),
+            uvs: Vec::new(),
+        };
+        try!(self.layout(spans, cache, |cache, ch, span, x, y| {
+            let color = color.unwrap_or(spans[span].1);
+            if c.skips(c.apply_alpha(color)) { return Ok(()); }
+            let region = cache.atlas_region(self.font_size, ch);
+            let id = region.map(|(id, _)| id);
+            if id.is_none() || id != batch.id || color != batch.color {
+                try!(self.draw_batch(&mut batch, cache, c, back_end));
+                batch.id = id;
+                batch.ch = ch;
+                batch.color = color;
+            }
+            let character = try!(cache.character(self.font_size, ch));
+            let (left, top) = (x + character.left(), y - character.top());
+            match region {
+                None => {
+                    Image::colored(color).draw(&character.texture,
+                        &c.trans(left, top), back_end);
+                }
+                Some((_, source_rectangle)) => {
+                    let rect = [
+                        left,
+                        top,
+                        source_rectangle[2] as internal::Scalar,
+                        source_rectangle[3] as internal::Scalar
+                    ];
+                    let mut xy = triangulation::rect_tri_list_xy(c.transform, rect);
+                    let mut uv = triangulation::rect_tri_list_uv(
+                        &character.texture, source_rectangle);
+                    triangulation::ensure_ccw_uv(&mut xy, &mut uv);
+                    batch.vertices.extend(xy.iter().map(|x| *x));
+                    batch.uvs.extend(uv.iter().map(|x| *x));
+                }
+            }
+            Ok(())
+        }));
+        self.draw_batch(&mut batch, cache, c, back_end)
+    }
+
+    /// Draws the characters of a batch with the texture
+    /// of its first character, and empties the batch.
+    fn draw_batch<C, B>(
+        &self,
+        batch: &mut GlyphBatch,
+        cache: &mut C,
+        c: &Context,
+        back_end: &mut B
+    ) -> Result<(), <C as CharacterCache>::Error>
+        where
+            C: CharacterCache,
+            B: Graphics<Texture = <C as CharacterCache>::Texture>
+    {
+        if batch.vertices.len() == 0 { return Ok(()); }
+        let character = try!(cache.character(self.font_size, batch.ch));
+        {
+            let (vertices, uvs) = (&batch.vertices, &batch.uvs);
+            back_end.tri_list_uv(
+                &c.draw_state,
+                &c.apply_alpha(batch.color),
+                &character.texture,
+                |f| {
+                    for (v, uv) in vertices.chunks(720).zip(uvs.chunks(720)) {
+                        f.emit(v, uv);
+                    }
+                }
             );
-            x += character.width() as i32;
-            y += character.height() as i32;
         }
+        batch.vertices.clear();
+        batch.uvs.clear();
+        Ok(())
     }
+
+    /// Lays out the characters of the spans as one line,
+    /// calling `f` with the cache, each character,
+    /// the index of its span and its position.
+    ///
+    /// Returns the width of the line.
+    fn layout<C, F>(
+        &self,
+        spans: &[(&str, internal::Color)],
+        cache: &mut C,
+        mut f: F
+    ) -> Result<internal::Scalar, <C as CharacterCache>::Error>
+        where
+            C: CharacterCache,
+            F: FnMut(&mut C, char, usize, internal::Scalar, internal::Scalar)
+                -> Result<(), <C as CharacterCache>::Error>
+    {
+        let mut x = 0.0;
+        let mut y = 0.0;
+        let mut previous = None;
+        for (i, &(text, _)) in spans.iter().enumerate() {
+            for ch in text.chars() {
+                if let Some(left) = previous {
+                    x += cache.kerning(self.font_size, left, ch);
+                }
+                let (width, height) = {
+                    let character = try!(cache.character(self.font_size, ch));
+                    (character.width(), character.height())
+                };
+                try!(f(cache, ch, i, x, y));
+                x += width;
+                y += height;
+                previous = Some(ch);
+            }
+        }
+        Ok(x)
+    }
+}
+
+/// Consecutive characters in the same shared texture, with the same color.
+struct GlyphBatch {
+    /// The id of the shared texture, or `None` for an empty batch
+    id: Option<usize>,
+    /// The first character, used to look up the texture
+    ch: char,
+    /// The color of the characters
+    color: internal::Color,
+    /// The vertices of the characters
+    vertices: Vec<f32>,
+    /// The texture coordinates of the characters
+    uvs: Vec<f32>,
 }
 
+/// Wraps text into lines no wider than a maximum width.
+///
+/// Lines are broken greedily at whitespace, which is collapsed
+/// into single spaces. Words wider than the maximum width are broken
+/// between characters. Each `\n` starts a new line, so empty lines are kept.
+/// Draw the lines one below the other with `Text::draw`.
+///
+/// Returns the error of the cache if a character can not be loaded.
+pub fn wrap<C>(
+    cache: &mut C,
+    font_size: internal::FontSize,
+    text: &str,
+    max_width: internal::Scalar
+) -> Result<Vec<String>, <C as CharacterCache>::Error>
+    where C: CharacterCache
+{
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        let words = paragraph.split(|ch: char| ch.is_whitespace())
+            .filter(|word| !word.is_empty());
+        for word in words {
+            let mut candidate = line.clone();
+            if !candidate.is_empty() { candidate.push(' '); }
+            candidate.push_str(word);
+            if try!(cache.width(font_size, &candidate)) <= max_width {
+                line = candidate;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(line);
+                line = String::new();
+            }
+            if try!(cache.width(font_size, word)) <= max_width {
+                line = word.to_string();
+                continue;
+            }
+            // The word does not fit on a line of its own.
+            for ch in word.chars() {
+                let mut candidate = line.clone();
+                candidate.push(ch);
+                if !line.is_empty()
+                && try!(cache.width(font_size, &candidate)) > max_width {
+                    lines.push(line);
+                    line = ch.to_string();
+                } else {
+                    line = candidate;
+                }
+            }
+        }
+        lines.push(line);
+    }
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod test {
+    use std::num::Float;
+    use super::Text;
+    use character::{ Character, CharacterCache };
+    use test_backend::{ RecordingGraphics, Texture };
+    use Context;
+    use internal::Scalar;
+
+    struct Cache {
+        character: Character<Texture>,
+        atlas: bool,
+    }
+
+    impl CharacterCache for Cache {
+        type Texture = Texture;
+        type Error = char;
+
+        fn character(&mut self, _font_size: u32, ch: char)
+        -> Result<&Character<Texture>, char> {
+            if ch == '?' { Err(ch) } else { Ok(&self.character) }
+        }
+
+        fn atlas_region(&mut self, _font_size: u32, ch: char) -> Option<(usize, [i32; 4])> {
+            if !self.atlas { return None; }
+            let i = ch as i32 - 'a' as i32;
+            Some((if ch == 'z' { 1 } else { 0 }, [(i % 16) * 16, (i / 16) * 16, 10, 12]))
+        }
+
+        fn kerning(&mut self, _font_size: u32, left: char, right: char) -> Scalar {
+            match (left, right) {
+                ('A', 'V') => -3.0,
+                ('T', 'o') => -2.5,
+                _ => 0.0
+            }
+        }
+    }
+
+    fn cache() -> Cache {
+        Cache {
+            character: Character {
+                offset: [0.0, 0.0],
+                size: [10.0, 0.0],
+                texture: Texture,
+            },
+            atlas: false,
+        }
+    }
+
+    #[test]
+    fn test_default() {
+        let text = Text::default();
+        assert_eq!(text.color, [0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(text.font_size, 12);
+        assert_eq!(text.outline, None);
+        assert_eq!(Text::colored([1.0; 4], 16).outline, None);
+    }
+
+    #[test]
+    fn test_wrap() {
+        use super::wrap;
+
+        let mut cache = cache();
+        let text = "the quick brown";
+        let lines = wrap(&mut cache, 12, text, 100.0).unwrap();
+        assert_eq!(lines, vec!["the quick".to_string(), "brown".to_string()]);
+        // The line is broken at a space.
+        assert_eq!(format!("{} {}", lines[0], lines[1]), text);
+
+        let lines = wrap(&mut cache, 12, "a\n\nabcdefg", 30.0).unwrap();
+        assert_eq!(lines, vec!["a".to_string(), "".to_string(),
+            "abc".to_string(), "def".to_string(), "g".to_string()]);
+        assert_eq!(wrap(&mut cache, 12, "a ?", 100.0), Err('?'));
+    }
+
+    #[test]
+    fn test_atlas_batch() {
+        let mut cache = Cache { atlas: true, ..cache() };
+        let red = [1.0, 0.0, 0.0, 1.0];
+        let c = Context::abs(100.0, 100.0);
+        let mut g = RecordingGraphics::new();
+        Text::colored(red, 12).draw("hello", &mut cache, &c, &mut g).unwrap();
+        assert_eq!(g.tri_lists_uv.len(), 1);
+        assert_eq!(g.tri_lists_uv[0].vertices.len(), 5 * 12);
+        assert_eq!(g.tri_lists_uv[0].color, red);
+
+        // Batches break when the texture or the color changes.
+        let mut g = RecordingGraphics::new();
+        let blue = [0.0, 0.0, 1.0, 1.0];
+        Text::new(12).draw_spans(&[("abzab", red), ("ab", blue)], &mut cache, &c, &mut g)
+            .unwrap();
+        assert_eq!(g.tri_lists_uv.len(), 4);
+        assert_eq!(g.tri_lists_uv[3].color, blue);
+    }
+
+    #[test]
+    fn test_outline() {
+        let mut cache = cache();
+        let red = [1.0, 0.0, 0.0, 1.0];
+        let black = [0.0, 0.0, 0.0, 1.0];
+        let c = Context::abs(100.0, 100.0);
+        let mut g = RecordingGraphics::new();
+        Text::colored(red, 12).outline(black, 2.0).draw("ab", &mut cache, &c, &mut g).unwrap();
+        assert_eq!(g.tri_lists_uv.len(), 2 * 8 + 2);
+        for t in g.tri_lists_uv[..16].iter() {
+            assert_eq!(t.color, black);
+        }
+        for t in g.tri_lists_uv[16..].iter() {
+            assert_eq!(t.color, red);
+        }
+
+        let mut g = RecordingGraphics::new();
+        Text::colored(red, 12).draw("ab", &mut cache, &c, &mut g).unwrap();
+        assert_eq!(g.tri_lists_uv.len(), 2);
+    }
+
+    #[test]
+    fn test_kerning() {
+        let mut cache = cache();
+        let c = Context::new();
+        let mut g = RecordingGraphics::new();
+        Text::new(12).draw("AVTo", &mut cache, &c, &mut g).unwrap();
+        let xs: Vec<f32> = g.tri_lists_uv.iter()
+            .map(|t| t.vertices.chunks(2).fold(::std::f32::MAX, |x, v| x.min(v[0])))
+            .collect();
+        assert_eq!(xs, vec![0.0, 7.0, 17.0, 24.5]);
+        assert_eq!(cache.width(12, "AVTo"), Ok(34.5));
+        assert_eq!(cache.width(12, "VA"), Ok(20.0));
+    }
+
+    #[test]
+    fn test_spans() {
+        let red = [1.0, 0.0, 0.0, 1.0];
+        let blue = [0.0, 0.0, 1.0, 1.0];
+        let c = Context::new();
+        let mut cache = cache();
+        let mut g = RecordingGraphics::new();
+        Text::new(12).draw("AVTo", &mut cache, &c, &mut g).unwrap();
+        let mut h = RecordingGraphics::new();
+        Text::new(12).draw_spans(&[("AV", red), ("To", blue)], &mut cache, &c, &mut h)
+            .unwrap();
+        assert_eq!(h.tri_lists_uv.len(), 4);
+        for (a, b) in g.tri_lists_uv.iter().zip(h.tri_lists_uv.iter()) {
+            assert_eq!(a.vertices, b.vertices);
+        }
+        assert_eq!(h.tri_lists_uv[1].color, red);
+        assert_eq!(h.tri_lists_uv[2].color, blue);
+
+        // Kerning applies across the span boundary.
+        let mut h = RecordingGraphics::new();
+        Text::new(12).draw_spans(&[("A", red), ("VTo", blue)], &mut cache, &c, &mut h)
+            .unwrap();
+        assert_eq!(g.tri_lists_uv[1].vertices, h.tri_lists_uv[1].vertices);
+        assert_eq!(Text::new(12).width_spans(&[("A", red), ("VTo", blue)], &mut cache),
+            cache.width(12, "AVTo"));
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut cache = cache();
+        let c = Context::abs(100.0, 100.0);
+        let mut g = RecordingGraphics::new();
+        assert_eq!(Text::new(12).draw("a?b", &mut cache, &c, &mut g), Err('?'));
+        // Stops at the first error.
+        assert_eq!(g.tri_lists_uv.len(), 1);
+        assert_eq!(cache.width(12, "abc"), Ok(30.0));
+        assert_eq!(cache.width(12, "a?"), Err('?'));
+    }
+}
@@ -1,10 +1,19 @@
 //! Methods for converting shapes into triangles.
+//!
+//! All emitted triangles are counter-clockwise in normalized device
+//! coordinates, with the y axis pointing up, whatever the transform
+//! or the sign of the sizes. Transforms with a negative determinant,
+//! like flips, are compensated by `ensure_ccw` and its variants.
 
+use std::vec::Vec;
 use {
     ImageSize,
 };
+use color::Gradient;
 use interpolation::{lerp};
+use line::{ Join, Shape, MITER_LIMIT };
 use internal::{
+    Color,
     Line,
     SourceRectangle,
     Polygon,
@@ -12,6 +21,7 @@ use internal::{
     Radius,
     Rectangle,
 };
+use std::cmp::Ordering;
 use std::num::{Float};
 use vecmath::{
     multiply,
@@ -24,15 +34,176 @@ use vecmath::{
 use radians::Radians;
 
 /// Transformed x coordinate as f32.
+///
+/// The triangulation functions transform each vertex with `tx` and `ty`,
+/// or four at a time with `transform4`.
+///
+/// In debug builds, both panic on infinite or NaN results,
+/// which back-ends draw as garbage or not at all.
+/// The backtrace of the panic shows the primitive being drawn.
 #[inline(always)]
 pub fn tx(m: Matrix2d, x: Scalar, y: Scalar) -> f32 {
-    (m[0][0] * x + m[0][1] * y + m[0][2]) as f32
+    let v = (m[0][0] * x + m[0][1] * y + m[0][2]) as f32;
+    debug_assert!(v.is_finite(),
+        "Non-finite vertex x {} from point [{}, {}] and transform {:?}", v, x, y, m);
+    v
 }
 
 /// Transformed y coordinate as f32.
 #[inline(always)]
 pub fn ty(m: Matrix2d, x: Scalar, y: Scalar) -> f32 {
-    (m[1][0] * x + m[1][1] * y + m[1][2]) as f32
+    let v = (m[1][0] * x + m[1][1] * y + m[1][2]) as f32;
+    debug_assert!(v.is_finite(),
+        "Non-finite vertex y {} from point [{}, {}] and transform {:?}", v, x, y, m);
+    v
+}
+
+/// The sine of angles from 0 to 90 degrees, in 256 steps.
+const QUARTER_SINE: [Scalar; 257] = [
+    0.0, 0.006135884649154475, 0.012271538285719925, 0.01840672990580482,
+    0.024541228522912288, 0.030674803176636626, 0.03680722294135883, 0.04293825693494082,
+    0.049067674327418015, 0.055195244349689934, 0.06132073630220858, 0.06744391956366405,
+    0.07356456359966743, 0.07968243797143013, 0.0857973123444399, 0.09190895649713272,
+    0.0980171403295606, 0.10412163387205459, 0.11022220729388306, 0.11631863091190475,
+    0.1224106751992162, 0.12849811079379317, 0.13458070850712617, 0.1406582393328492,
+    0.14673047445536175, 0.15279718525844344, 0.15885814333386145, 0.16491312048996992,
+    0.17096188876030122, 0.17700422041214875, 0.18303988795514095, 0.1890686641498062,
+    0.19509032201612825, 0.2011046348420919, 0.20711137619221856, 0.21311031991609136,
+    0.2191012401568698, 0.22508391135979283, 0.2310581082806711, 0.2370236059943672,
+    0.24298017990326387, 0.24892760574572015, 0.25486565960451457, 0.2607941179152755,
+    0.26671275747489837, 0.272621355449949, 0.27851968938505306, 0.2844075372112719,
+    0.29028467725446233, 0.2961508882436238, 0.3020059493192281, 0.30784964004153487,
+    0.3136817403988915, 0.3195020308160157, 0.3253102921622629, 0.33110630575987643,
+    0.33688985339222005, 0.3426607173119944, 0.34841868024943456, 0.35416352542049034,
+    0.3598950365349881, 0.36561299780477385, 0.37131719395183754, 0.37700741021641826,
+    0.3826834323650898, 0.38834504669882625, 0.3939920400610481, 0.3996241998456468,
+    0.40524131400498986, 0.4108431710579039, 0.41642956009763715, 0.4220002707997997,
+    0.4275550934302821, 0.43309381885315196, 0.43861623853852766, 0.4441221445704292,
+    0.44961132965460654, 0.45508358712634384, 0.46053871095824, 0.4659764957679662,
+    0.47139673682599764, 0.4767992300633221, 0.4821837720791227, 0.487550160148436,
+    0.49289819222978404, 0.49822766697278187, 0.5035383837257176, 0.508830142543107,
+    0.5141027441932217, 0.5193559901655896, 0.524589682678469, 0.5298036246862946,
+    0.5349976198870972, 0.5401714727298929, 0.5453249884220465, 0.5504579729366048,
+    0.5555702330196022, 0.560661576197336, 0.5657318107836131, 0.5707807458869673,
+    0.5758081914178453, 0.5808139580957645, 0.5857978574564389, 0.5907597018588742,
+    0.5956993044924334, 0.600616479383869, 0.6055110414043255, 0.6103828062763095,
+    0.6152315905806268, 0.6200572117632891, 0.6248594881423863, 0.629638238914927,
+    0.6343932841636455, 0.6391244448637757, 0.6438315428897914, 0.6485144010221124,
+    0.6531728429537768, 0.6578066932970786, 0.6624157775901718, 0.6669999223036375,
+    0.6715589548470183, 0.6760927035753159, 0.680600997795453, 0.6850836677727004,
+    0.6895405447370668, 0.693971460889654, 0.6983762494089729, 0.7027547444572253,
+    0.7071067811865475, 0.7114321957452164, 0.7157308252838186, 0.7200025079613817,
+    0.7242470829514669, 0.7284643904482252, 0.7326542716724128, 0.7368165688773698,
+    0.7409511253549591, 0.745057785441466, 0.7491363945234593, 0.7531867990436124,
+    0.7572088465064845, 0.7612023854842618, 0.765167265622459, 0.7691033376455796,
+    0.773010453362737, 0.7768884656732324, 0.7807372285720944, 0.7845565971555752,
+    0.7883464276266062, 0.7921065773002124, 0.7958369046088835, 0.799537269107905,
+    0.8032075314806448, 0.8068475535437992, 0.8104571982525948, 0.8140363297059483,
+    0.8175848131515837, 0.8211025149911046, 0.8245893027850253, 0.8280450452577558,
+    0.8314696123025452, 0.83486287498638, 0.838224705554838, 0.8415549774368983,
+    0.844853565249707, 0.8481203448032971, 0.8513551931052652, 0.8545579883654005,
+    0.8577286100002721, 0.8608669386377673, 0.8639728561215867, 0.8670462455156926,
+    0.8700869911087113, 0.8730949784182901, 0.8760700941954066, 0.8790122264286334,
+    0.8819212643483549, 0.8847970984309378, 0.8876396204028539, 0.8904487232447579,
+    0.8932243011955153, 0.8959662497561851, 0.8986744656939538, 0.901348847046022,
+    0.9039892931234433, 0.9065957045149153, 0.9091679830905223, 0.9117060320054299,
+    0.9142097557035307, 0.9166790599210427, 0.9191138516900578, 0.9215140393420419,
+    0.9238795325112867, 0.9262102421383113, 0.9285060804732155, 0.9307669610789837,
+    0.9329927988347388, 0.9351835099389475, 0.937339011912575, 0.9394592236021899,
+    0.9415440651830208, 0.9435934581619604, 0.9456073253805213, 0.9475855910177411,
+    0.9495281805930367, 0.9514350209690083, 0.9533060403541938, 0.9551411683057707,
+    0.9569403357322089, 0.9587034748958716, 0.9604305194155658, 0.9621214042690416,
+    0.9637760657954398, 0.9653944416976894, 0.9669764710448521, 0.9685220942744173,
+    0.970031253194544, 0.9715038909862518, 0.9729399522055601, 0.9743393827855759,
+    0.9757021300385286, 0.9770281426577544, 0.9783173707196277, 0.9795697656854405,
+    0.9807852804032304, 0.9819638691095552, 0.9831054874312163, 0.984210092386929,
+    0.9852776423889412, 0.9863080972445987, 0.9873014181578584, 0.9882575677307495,
+    0.989176509964781, 0.9900582102622971, 0.99090263542778, 0.9917097536690995,
+    0.99247953459871, 0.9932119492347945, 0.9939069700023561, 0.9945645707342554,
+    0.9951847266721968, 0.9957674144676598, 0.996312612182778, 0.9968202992911657,
+    0.9972904566786902, 0.9977230666441916, 0.9981181129001492, 0.9984755805732948,
+    0.9987954562051724, 0.9990777277526454, 0.9993223845883495, 0.9995294175010931,
+    0.9996988186962042, 0.9998305817958234, 0.9999247018391445, 0.9999811752826011,
+    1.0,
+];
+
+/// The sine of `k` steps of a quarter circle in 256 steps.
+fn table_sine(k: usize) -> Scalar {
+    let k = k % 1024;
+    let r = k % 256;
+    match k / 256 {
+        0 => QUARTER_SINE[r],
+        1 => QUARTER_SINE[256 - r],
+        2 => -QUARTER_SINE[r],
+        _ => -QUARTER_SINE[256 - r],
+    }
+}
+
+/// Transforms four points to f32, as `[x0, y0, x1, y1, ...]`.
+///
+/// With the `simd` feature, the points are transformed in f32 lanes
+/// with SIMD instructions, which is within float tolerance of `tx`
+/// and `ty` for coordinates that fit in f32.
+/// Without it, this is `transform4_scalar`.
+#[cfg(not(feature = "simd"))]
+#[inline(always)]
+pub fn transform4(m: Matrix2d, xs: [Scalar; 4], ys: [Scalar; 4]) -> [f32; 8] {
+    transform4_scalar(m, xs, ys)
+}
+
+/// The SIMD version of `transform4`, see above.
+#[cfg(feature = "simd")]
+#[inline(always)]
+pub fn transform4(m: Matrix2d, xs: [Scalar; 4], ys: [Scalar; 4]) -> [f32; 8] {
+    use std::simd::f32x4;
+
+    let splat = |v: Scalar| f32x4(v as f32, v as f32, v as f32, v as f32);
+    let lanes = |v: [Scalar; 4]| f32x4(v[0] as f32, v[1] as f32, v[2] as f32, v[3] as f32);
+    let (x, y) = (lanes(xs), lanes(ys));
+    let f32x4(x0, x1, x2, x3) = splat(m[0][0]) * x + splat(m[0][1]) * y + splat(m[0][2]);
+    let f32x4(y0, y1, y2, y3) = splat(m[1][0]) * x + splat(m[1][1]) * y + splat(m[1][2]);
+    let res = [x0, y0, x1, y1, x2, y2, x3, y3];
+    debug_assert!(res.iter().all(|v| v.is_finite()),
+        "Non-finite vertices {:?} from points {:?}, {:?} and transform {:?}", res, xs, ys, m);
+    res
+}
+
+/// Transforms four points to f32 by calling `tx` and `ty` for each point.
+///
+/// This is compiled with and without the `simd` feature,
+/// so benchmarks can compare it with `transform4` in one build.
+#[inline(always)]
+pub fn transform4_scalar(m: Matrix2d, xs: [Scalar; 4], ys: [Scalar; 4]) -> [f32; 8] {
+    [
+        tx(m, xs[0], ys[0]), ty(m, xs[0], ys[0]),
+        tx(m, xs[1], ys[1]), ty(m, xs[1], ys[1]),
+        tx(m, xs[2], ys[2]), ty(m, xs[2], ys[2]),
+        tx(m, xs[3], ys[3]), ty(m, xs[3], ys[3]),
+    ]
+}
+
+/// Returns the point on the unit circle at an angle in radians,
+/// as `[cos, sin]`.
+///
+/// Interpolates linearly in a table of 1024 points around the circle,
+/// instead of computing the sine and cosine.
+/// The result is within 0.00001 of the exact point.
+/// Infinite and NaN angles give NaN coordinates.
+pub fn unit_circle(angle: Scalar) -> Vec2d {
+    let full: Scalar = Radians::_360();
+    let t = angle / full * 1024.0;
+    let t = t - (t / 1024.0).floor() * 1024.0;
+    // Infinite and NaN angles must not index the table.
+    if !(t >= 0.0 && t < 1024.0) {
+        let nan: Scalar = Float::nan();
+        return [nan, nan];
+    }
+    let k = t.floor();
+    let frac = t - k;
+    let k = k as usize;
+    let (cos_a, cos_b) = (table_sine(k + 256), table_sine(k + 257));
+    let (sin_a, sin_b) = (table_sine(k), table_sine(k + 1));
+    [cos_a + (cos_b - cos_a) * frac, sin_a + (sin_b - sin_a) * frac]
 }
 
 /// Streams tweened polygons using linear interpolation.
@@ -95,7 +266,102 @@ pub fn with_ellipse_tri_list<F>(
 
         let angle = i as Scalar / n as Scalar * Radians::_360();
         i += 1;
-        Some([cx + angle.cos() * cw, cy + angle.sin() * ch])
+        let p = unit_circle(angle);
+        Some([cx + p[0] * cw, cy + p[1] * ch])
+    }, f);
+}
+
+/// Returns the number of segments for an arc,
+/// keeping the density of a full circle with `resolution` segments.
+///
+/// Returns at least one segment.
+pub fn arc_segments(resolution: usize, start: Scalar, end: Scalar) -> usize {
+    let full: Scalar = Radians::_360();
+    let span = (end - start).abs();
+    let n = (resolution as Scalar * span / full).ceil();
+    if n < 1.0 { 1 } else { n as usize }
+}
+
+/// The fewest segments in a full circle for `Resolution::Adaptive`.
+pub const MIN_SEGMENTS: usize = 8;
+
+/// The most segments in a full circle for `Resolution::Adaptive`.
+pub const MAX_SEGMENTS: usize = 1024;
+
+/// The number of segments used for curves.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Resolution {
+    /// A fixed number of segments in a full circle.
+    Fixed(usize),
+    /// Enough segments to keep the distance between the chords
+    /// and the curve under a tolerance in device pixels.
+    Adaptive(Scalar),
+}
+
+impl Default for Resolution {
+    fn default() -> Resolution { Resolution::Fixed(128) }
+}
+
+impl Resolution {
+    /// Returns the number of segments in a full circle
+    /// with a radius in device pixels.
+    ///
+    /// Adaptive resolutions are clamped to `MIN_SEGMENTS`
+    /// and `MAX_SEGMENTS`. Use `Context::local_width(1.0)`
+    /// to convert a radius from local coordinates.
+    pub fn segments(&self, radius: Scalar) -> usize {
+        match *self {
+            Resolution::Fixed(n) => n,
+            Resolution::Adaptive(tolerance) => {
+                let pi: Scalar = Radians::_180();
+                // The distance from a chord to the circle is
+                // `radius * (1 - cos(angle / 2))`.
+                let ratio = tolerance / radius.abs();
+                let n = if !(ratio > 0.0) { MAX_SEGMENTS as Scalar }
+                    else if ratio >= 2.0 { 1.0 }
+                    else { (pi / (1.0 - ratio).acos()).ceil() };
+                let n = n.min(MAX_SEGMENTS as Scalar) as usize;
+                if n < MIN_SEGMENTS { MIN_SEGMENTS } else { n }
+            }
+        }
+    }
+}
+
+/// Streams a sector of an ellipse as a triangle fan from the center.
+///
+/// The sector covers the angles from `start` to `end` in radians.
+/// A span of a full circle or more gives the whole ellipse.
+#[inline(always)]
+pub fn with_sector_tri_list<F>(
+    resolution: usize,
+    m: Matrix2d,
+    rect: Rectangle,
+    start: Scalar,
+    end: Scalar,
+    f: F
+)
+    where
+        F: FnMut(&[f32])
+{
+
+    let (x, y, w, h) = (rect[0], rect[1], rect[2], rect[3]);
+    let (cw, ch) = (0.5 * w, 0.5 * h);
+    let (cx, cy) = (x + cw, y + ch);
+    let n = resolution;
+    let mut i: usize = 0;
+    let mut center = true;
+    stream_polygon_tri_list(m, || {
+        if center {
+            center = false;
+            return Some([cx, cy]);
+        }
+        if i > n { return None; }
+
+        let angle = start + (end - start) * i as Scalar / n as Scalar;
+        i += 1;
+        let p = unit_circle(angle);
+        Some([cx + p[0] * cw, cy + p[1] * ch])
     }, f);
 }
 
@@ -125,36 +391,414 @@ pub fn with_round_border_line_tri_list<F>(
 
         let j = i;
         i += 1;
-        // Detect the half circle from index.
-        // There is one half circle at each end of the line.
-        // Together they form a full circle if
-        // the length of the line is zero.
-        match j {
-            j if j >= resolution_cap => {
-                // Compute the angle to match start and end
-                // point of half circle.
-                // This requires an angle offset since
-                // the other end of line is the first half circle.
-                let angle = (j - resolution_cap) as Scalar
-                    / (resolution_cap - 1) as Scalar * Radians::_180()
-                    + Radians::_180();
-                // Rotate 90 degrees since the line is horizontal.
-                let angle = angle + Radians::_90();
-                Some([w + angle.cos() * radius, angle.sin() * radius])
-            },
-            j => {
-                // Compute the angle to match start and end
-                // point of half circle.
-                let angle = j as Scalar
-                    / (resolution_cap - 1) as Scalar * Radians::_180();
-                // Rotate 90 degrees since the line is horizontal.
-                let angle = angle + Radians::_90();
-                Some([angle.cos() * radius, angle.sin() * radius])
-            },
-        }
+        Some(round_border_line_point(resolution_cap, w, radius, j))
     }, f);
 }
 
+/// Returns the point `j` on the outline of a round border line,
+/// in coordinates where the line of length `w` starts at the origin
+/// and points along the x axis.
+#[inline(always)]
+fn round_border_line_point(
+    resolution_cap: usize,
+    w: Scalar,
+    radius: Radius,
+    j: usize
+) -> Vec2d {
+    // Detect the half circle from index.
+    // There is one half circle at each end of the line.
+    // Together they form a full circle if
+    // the length of the line is zero.
+    match j {
+        j if j >= resolution_cap => {
+            // Compute the angle to match start and end
+            // point of half circle.
+            // This requires an angle offset since
+            // the other end of line is the first half circle.
+            let angle = (j - resolution_cap) as Scalar
+                / (resolution_cap - 1) as Scalar * Radians::_180()
+                + Radians::_180();
+            // Rotate 90 degrees since the line is horizontal.
+            let angle = angle + Radians::_90();
+            let p = unit_circle(angle);
+            [w + p[0] * radius, p[1] * radius]
+        },
+        j => {
+            // Compute the angle to match start and end
+            // point of half circle.
+            let angle = j as Scalar
+                / (resolution_cap - 1) as Scalar * Radians::_180();
+            // Rotate 90 degrees since the line is horizontal.
+            let angle = angle + Radians::_90();
+            let p = unit_circle(angle);
+            [p[0] * radius, p[1] * radius]
+        },
+    }
+}
+
+/// Streams a polyline with a radius as one tri list.
+///
+/// The segments meet with a `join`, and the ends of an open polyline
+/// get a `cap` like the ends of a line, see `line::Shape`.
+/// A closed polyline also joins the last point to the first.
+/// Repeated points are skipped, and a single point draws
+/// a dot with round or bevel caps.
+/// Round joins and caps use the density of a full circle
+/// with `resolution` segments, see `arc_segments`.
+///
+/// The segments overlap at the inside of corners,
+/// so translucent colors look darker there.
+pub fn with_polyline_tri_list<F>(
+    resolution: usize,
+    m: Matrix2d,
+    points: Polygon,
+    closed: bool,
+    radius: Radius,
+    cap: Shape,
+    join: Join,
+    mut f: F
+)
+    where
+        F: FnMut(&[f32])
+{
+    let mut vertices: [f32; 720] = [0.0; 720];
+    let mut i = 0;
+    {
+        let mut triangle = |a: Vec2d, b: Vec2d, c: Vec2d| {
+            // Buffer is full.
+            if i + 6 > vertices.len() {
+                ensure_ccw(&mut vertices[..i]);
+                f(&vertices[..i]);
+                i = 0;
+            }
+            for (k, p) in [a, b, c].iter().enumerate() {
+                vertices[i + 2 * k] = tx(m, p[0], p[1]);
+                vertices[i + 2 * k + 1] = ty(m, p[0], p[1]);
+            }
+            i += 6;
+        };
+
+        let mut distinct = points.iter().enumerate()
+            .filter(|&(j, p)| j == 0 || *p != points[j - 1])
+            .map(|(_, &p)| p);
+        let first = match distinct.next() { None => return, Some(p) => p };
+        let mut prev = first;
+        let mut first_dir = None;
+        let mut prev_dir = None;
+        for p in distinct {
+            let d = unit_direction(prev, p);
+            polyline_segment(prev, p, d, radius, &mut triangle);
+            match prev_dir {
+                None => first_dir = Some(d),
+                Some(d0) => polyline_join(resolution, prev, d0, d, radius, join, &mut triangle),
+            }
+            prev_dir = Some(d);
+            prev = p;
+        }
+        match (first_dir, prev_dir) {
+            (Some(d1), Some(d0)) if closed => {
+                let mut d0 = d0;
+                if prev != first {
+                    let d = unit_direction(prev, first);
+                    polyline_segment(prev, first, d, radius, &mut triangle);
+                    polyline_join(resolution, prev, d0, d, radius, join, &mut triangle);
+                    d0 = d;
+                }
+                polyline_join(resolution, first, d0, d1, radius, join, &mut triangle);
+            }
+            (Some(d1), Some(d0)) => {
+                polyline_cap(resolution, first, [-d1[0], -d1[1]], radius, cap, &mut triangle);
+                polyline_cap(resolution, prev, d0, radius, cap, &mut triangle);
+            }
+            _ => {
+                polyline_cap(resolution, first, [-1.0, 0.0], radius, cap, &mut triangle);
+                polyline_cap(resolution, first, [1.0, 0.0], radius, cap, &mut triangle);
+            }
+        }
+    }
+    if i > 0 {
+        ensure_ccw(&mut vertices[..i]);
+        f(&vertices[..i]);
+    }
+}
+
+/// Returns the unit vector from one point to another, distinct point.
+#[inline(always)]
+fn unit_direction(a: Vec2d, b: Vec2d) -> Vec2d {
+    let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+    let len = (dx * dx + dy * dy).sqrt();
+    [dx / len, dy / len]
+}
+
+/// Emits the two triangles of a polyline segment with direction `d`.
+fn polyline_segment(
+    a: Vec2d,
+    b: Vec2d,
+    d: Vec2d,
+    radius: Radius,
+    triangle: &mut FnMut(Vec2d, Vec2d, Vec2d)
+) {
+    let (nx, ny) = (-d[1] * radius, d[0] * radius);
+    triangle([a[0] + nx, a[1] + ny], [a[0] - nx, a[1] - ny], [b[0] - nx, b[1] - ny]);
+    triangle([a[0] + nx, a[1] + ny], [b[0] - nx, b[1] - ny], [b[0] + nx, b[1] + ny]);
+}
+
+/// Emits the triangles filling the outside of a corner at `p`,
+/// between segments with the directions `d0` and `d1`.
+fn polyline_join(
+    resolution: usize,
+    p: Vec2d,
+    d0: Vec2d,
+    d1: Vec2d,
+    radius: Radius,
+    join: Join,
+    triangle: &mut FnMut(Vec2d, Vec2d, Vec2d)
+) {
+    let cross = d0[0] * d1[1] - d0[1] * d1[0];
+    let dot = d0[0] * d1[0] + d0[1] * d1[1];
+    // Straight on, the segments already meet.
+    if cross == 0.0 && dot > 0.0 { return; }
+    // The outside is to the right of a left turn, and the other way around.
+    let s = if cross > 0.0 { radius } else { -radius };
+    let n0 = [d0[1] * s, -d0[0] * s];
+    let n1 = [d1[1] * s, -d1[0] * s];
+    let a = [p[0] + n0[0], p[1] + n0[1]];
+    let b = [p[0] + n1[0], p[1] + n1[1]];
+    match join {
+        Join::Round => {
+            // Turn the other way around a reversal, through the front.
+            let turn = cross.atan2(dot).abs();
+            let turn = if cross > 0.0 { turn } else { -turn };
+            let start = n0[1].atan2(n0[0]);
+            let n = arc_segments(resolution, 0.0, turn);
+            let mut prev = a;
+            for k in 1..n + 1 {
+                let q = if k == n { b } else {
+                    let u = unit_circle(start + turn * k as Scalar / n as Scalar);
+                    [p[0] + u[0] * radius, p[1] + u[1] * radius]
+                };
+                triangle(p, prev, q);
+                prev = q;
+            }
+        }
+        Join::Bevel => triangle(p, a, b),
+        Join::Miter => {
+            triangle(p, a, b);
+            // The miter is `1 / cos(angle / 2)` radii long,
+            // where `cos(angle / 2)^2 = (1 + dot) / 2`.
+            if MITER_LIMIT * MITER_LIMIT * (1.0 + dot) < 2.0 { return; }
+            let k = 1.0 / (1.0 + dot);
+            let tip = [p[0] + (n0[0] + n1[0]) * k, p[1] + (n0[1] + n1[1]) * k];
+            triangle(a, tip, b);
+        }
+    }
+}
+
+/// Emits the triangles of a cap at the end `p` of a polyline,
+/// pointing outwards in the direction `d`.
+fn polyline_cap(
+    resolution: usize,
+    p: Vec2d,
+    d: Vec2d,
+    radius: Radius,
+    cap: Shape,
+    triangle: &mut FnMut(Vec2d, Vec2d, Vec2d)
+) {
+    let n = [-d[1] * radius, d[0] * radius];
+    let a = [p[0] + n[0], p[1] + n[1]];
+    let b = [p[0] - n[0], p[1] - n[1]];
+    match cap {
+        Shape::Square => {}
+        Shape::Bevel => triangle(a, [p[0] + d[0] * radius, p[1] + d[1] * radius], b),
+        Shape::Round => {
+            let half: Scalar = Radians::_180();
+            let start = n[1].atan2(n[0]);
+            let segments = arc_segments(resolution, 0.0, half);
+            let mut prev = a;
+            for k in 1..segments + 1 {
+                let q = if k == segments { b } else {
+                    let u = unit_circle(start - half * k as Scalar / segments as Scalar);
+                    [p[0] + u[0] * radius, p[1] + u[1] * radius]
+                };
+                triangle(p, prev, q);
+                prev = q;
+            }
+        }
+    }
+}
+
+/// Streams a feathered band around a polygon, with a color per vertex.
+///
+/// The band extends `width` outwards from the edges of the polygon
+/// and fades from `color` at the edges to transparent,
+/// which approximates anti-aliasing when drawn over the shape.
+/// Colors have four components per vertex.
+/// The band has two triangles per edge, the triangles with two vertices
+/// on the polygon come first, followed by those with one.
+///
+/// The `antialias` builders of the primitives draw such bands,
+/// approximating anti-aliasing without multisampling.
+/// Their width is in device pixels, converted with `Context::local_width`,
+/// so edges stay sharp when zooming. A width of one pixel works well.
+/// The band increases the vertex count and adds a call to `tri_list_c`,
+/// which is skipped for back-ends without `has_vertex_colors`.
+pub fn with_polygon_feather_tri_list_c<F>(
+    m: Matrix2d,
+    polygon: Polygon,
+    width: Scalar,
+    color: Color,
+    mut f: F
+)
+    where
+        F: FnMut(&[f32], &[f32])
+{
+    let n = polygon.len();
+    if n < 3 { return; }
+
+    // The sign of the area decides which side of the edges is outwards.
+    let area = (0..n).fold(0.0, |acc, i| {
+        let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+        acc + a[0] * b[1] - b[0] * a[1]
+    });
+    let sign = if area < 0.0 { -1.0 } else { 1.0 };
+    let normal = |i: usize| -> Vec2d {
+        let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+        let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+        let len = (dx * dx + dy * dy).sqrt();
+        if len == 0.0 { [0.0, 0.0] } else { [sign * dy / len, -sign * dx / len] }
+    };
+    let outer: Vec<Vec2d> = (0..n).map(|i| {
+        let (n1, n2) = (normal((i + n - 1) % n), normal(i));
+        let (sx, sy) = (n1[0] + n2[0], n1[1] + n2[1]);
+        let len = (sx * sx + sy * sy).sqrt();
+        let dir = if len < 0.000001 { n2 } else { [sx / len, sy / len] };
+        // Extend the miter to keep the width, limited at sharp corners.
+        let cos = (dir[0] * n2[0] + dir[1] * n2[1]).max(0.5);
+        let p = polygon[i];
+        [p[0] + dir[0] * width / cos, p[1] + dir[1] * width / cos]
+    }).collect();
+
+    let transparent = [color[0], color[1], color[2], 0.0];
+    let mut vertices: Vec<f32> = Vec::with_capacity(n * 12);
+    let mut colors: Vec<f32> = Vec::with_capacity(n * 24);
+    {
+        let mut push = |p: Vec2d, c: &Color| {
+            vertices.push(tx(m, p[0], p[1]));
+            vertices.push(ty(m, p[0], p[1]));
+            colors.extend(c.iter().map(|x| *x));
+        };
+        for i in 0..n {
+            let j = (i + 1) % n;
+            push(polygon[i], &color);
+            push(polygon[j], &color);
+            push(outer[i], &transparent);
+        }
+        for i in 0..n {
+            let j = (i + 1) % n;
+            push(outer[i], &transparent);
+            push(polygon[j], &color);
+            push(outer[j], &transparent);
+        }
+    }
+    ensure_ccw_c(&mut vertices, &mut colors);
+    f(&vertices, &colors);
+}
+
+/// Streams a feathered band around an ellipse, with a color per vertex.
+///
+/// See `with_polygon_feather_tri_list_c`.
+pub fn with_ellipse_feather_tri_list_c<F>(
+    resolution: usize,
+    m: Matrix2d,
+    rect: Rectangle,
+    width: Scalar,
+    color: Color,
+    f: F
+)
+    where
+        F: FnMut(&[f32], &[f32])
+{
+    let (x, y, w, h) = (rect[0], rect[1], rect[2], rect[3]);
+    let (cw, ch) = (0.5 * w, 0.5 * h);
+    let (cx, cy) = (x + cw, y + ch);
+    let points: Vec<Vec2d> = (0..resolution).map(|i| {
+        let angle = i as Scalar / resolution as Scalar * Radians::_360();
+        let p = unit_circle(angle);
+        [cx + p[0] * cw, cy + p[1] * ch]
+    }).collect();
+    with_polygon_feather_tri_list_c(m, &points, width, color, f);
+}
+
+/// Streams a feathered band around a round border line,
+/// with a color per vertex.
+///
+/// See `with_polygon_feather_tri_list_c`.
+pub fn with_round_border_line_feather_tri_list_c<F>(
+    resolution_cap: usize,
+    m: Matrix2d,
+    line: Line,
+    round_border_radius: Radius,
+    width: Scalar,
+    color: Color,
+    f: F
+)
+    where
+        F: FnMut(&[f32], &[f32])
+{
+    let (x1, y1, x2, y2) = (line[0], line[1], line[2], line[3]);
+    let (dx, dy) = (x2 - x1, y2 - y1);
+    let w = (dx * dx + dy * dy).sqrt();
+    let m = multiply(m, translate([x1, y1]));
+    let m = multiply(m, orient(dx, dy));
+    let points: Vec<Vec2d> = (0..resolution_cap * 2)
+        .map(|j| round_border_line_point(resolution_cap, w, round_border_radius, j))
+        .collect();
+    with_polygon_feather_tri_list_c(m, &points, width, color, f);
+}
+
+/// Streams a rectangle filled with a gradient along the x axis,
+/// with a color per vertex.
+///
+/// The rectangle is split into one strip between each pair of adjacent
+/// stops, with the colors of the stops at the edges,
+/// so the colors interpolate between the stops.
+/// Stops outside 0 to 1 are clamped, see `Gradient::sample`.
+/// Colors have four components per vertex.
+pub fn with_gradient_rect_tri_list_c<F>(
+    m: Matrix2d,
+    rect: Rectangle,
+    gradient: &Gradient,
+    mut f: F
+)
+    where
+        F: FnMut(&[f32], &[f32])
+{
+    let mut ts = vec![0.0];
+    for &(t, _) in gradient.stops.iter() {
+        if t > 0.0 && t < 1.0 && t > ts[ts.len() - 1] { ts.push(t); }
+    }
+    ts.push(1.0);
+
+    let (x, y, w, h) = (rect[0], rect[1], rect[2], rect[3]);
+    let mut vertices: Vec<f32> = Vec::with_capacity((ts.len() - 1) * 12);
+    let mut colors: Vec<f32> = Vec::with_capacity((ts.len() - 1) * 24);
+    for i in 0..ts.len() - 1 {
+        let (ta, tb) = (ts[i], ts[i + 1]);
+        let (ca, cb) = (gradient.sample(ta), gradient.sample(tb));
+        let (x0, x1) = (x + w * ta as Scalar, x + w * tb as Scalar);
+        for &(px, py, c) in [
+            (x0, y, ca), (x1, y, cb), (x0, y + h, ca),
+            (x1, y, cb), (x1, y + h, cb), (x0, y + h, ca)
+        ].iter() {
+            vertices.push(tx(m, px, py));
+            vertices.push(ty(m, px, py));
+            colors.extend(c.iter().map(|x| *x));
+        }
+    }
+    ensure_ccw_c(&mut vertices, &mut colors);
+    f(&vertices, &colors);
+}
+
 /// Streams a round rectangle.
 #[inline(always)]
 pub fn with_round_rectangle_tri_list<F>(
@@ -192,7 +836,8 @@ pub fn with_round_rectangle_tri_list<F>(
                     + 3.0 * Radians::_90();
                 // Set center of the circle to the last corner.
                 let (cx, cy) = (x + w - radius, y + radius);
-                Some([cx + angle.cos() * radius, cy + angle.sin() * radius])
+                let p = unit_circle(angle);
+                Some([cx + p[0] * radius, cy + p[1] * radius])
             },
             j if j >= resolution_corner * 2 => {
                 // Compute the angle to match start and end
@@ -204,7 +849,8 @@ pub fn with_round_rectangle_tri_list<F>(
                     + Radians::_180();
                 // Set center of the circle to the second last corner.
                 let (cx, cy) = (x + radius, y + radius);
-                Some([cx + angle.cos() * radius, cy + angle.sin() * radius])
+                let p = unit_circle(angle);
+                Some([cx + p[0] * radius, cy + p[1] * radius])
             },
             j if j >= resolution_corner * 1 => {
                 // Compute the angle to match start and end
@@ -216,7 +862,8 @@ pub fn with_round_rectangle_tri_list<F>(
                     + Radians::_90();
                 // Set center of the circle to the second corner.
                 let (cx, cy) = (x + radius, y + h - radius);
-                Some([cx + angle.cos() * radius, cy + angle.sin() * radius])
+                let p = unit_circle(angle);
+                Some([cx + p[0] * radius, cy + p[1] * radius])
             },
             j => {
                 // Compute the angle to match start and end
@@ -226,7 +873,8 @@ pub fn with_round_rectangle_tri_list<F>(
                     * Radians::_90();
                 // Set center of the circle to the first corner.
                 let (cx, cy) = (x + w - radius, y + h - radius);
-                Some([cx + angle.cos() * radius, cy + angle.sin() * radius])
+                let p = unit_circle(angle);
+                Some([cx + p[0] * radius, cy + p[1] * radius])
             },
         }
     }, f);
@@ -284,17 +932,23 @@ pub fn stream_polygon_tri_list<E, F>(
         // Buffer is full.
         if i * align_vertices + 2 >= vertices.len() {
             // Send chunk and start over.
+            ensure_ccw(&mut vertices[0..i * align_vertices]);
             f(&vertices[0..i * align_vertices]);
             i = 0;
         }
     }
 
     if i > 0 {
+        ensure_ccw(&mut vertices[0..i * align_vertices]);
         f(&vertices[0..i * align_vertices]);
     }
 }
 
 /// Streams an ellipse border specified by a resolution.
+///
+/// The border is centered on the edge of the ellipse.
+/// When the border radius exceeds half an axis,
+/// the inner edge is clamped to the center, filling the ellipse.
 #[inline(always)]
 pub fn with_ellipse_border_tri_list<F>(
     resolution: usize,
@@ -310,7 +964,8 @@ pub fn with_ellipse_border_tri_list<F>(
     let (x, y, w, h) = (rect[0], rect[1], rect[2], rect[3]);
     let (cw, ch) = (0.5 * w, 0.5 * h);
     let (cw1, ch1) = (cw + border_radius, ch + border_radius);
-    let (cw2, ch2) = (cw - border_radius, ch - border_radius);
+    // Close into a disk instead of crossing the center.
+    let (cw2, ch2) = ((cw - border_radius).max(0.0), (ch - border_radius).max(0.0));
     let (cx, cy) = (x + cw, y + ch);
     let n = resolution;
     let mut i: usize = 0;
@@ -318,14 +973,180 @@ pub fn with_ellipse_border_tri_list<F>(
         if i > n { return None; }
 
         let angle = i as Scalar / n as Scalar * Radians::_360();
-        let cos = angle.cos();
-        let sin = angle.sin();
+        let p = unit_circle(angle);
+        let (cos, sin) = (p[0], p[1]);
         i += 1;
         Some(([cx + cos * cw1, cy + sin * ch1],
             [cx + cos * cw2, cy + sin * ch2]))
     }, f);
 }
 
+/// Streams a ring between an outer and an inner ellipse as a tri strip.
+///
+/// The ellipses are given by their bounding rectangles.
+/// The inner ellipse must be contained in the outer one,
+/// but does not need to share the center.
+/// Both edges are sampled along rays from the inner center,
+/// so the strip does not intersect itself.
+/// An inner rectangle of zero size gives a filled ellipse.
+#[inline(always)]
+pub fn with_ellipse_ring_tri_strip<F>(
+    resolution: usize,
+    m: Matrix2d,
+    outer: Rectangle,
+    inner: Rectangle,
+    start: Scalar,
+    end: Scalar,
+    f: F
+)
+    where
+        F: FnMut(&[f32])
+{
+
+    let (ow, oh) = (0.5 * outer[2], 0.5 * outer[3]);
+    let (ocx, ocy) = (outer[0] + ow, outer[1] + oh);
+    let (iw, ih) = (0.5 * inner[2], 0.5 * inner[3]);
+    let (icx, icy) = (inner[0] + iw, inner[1] + ih);
+    let (dx, dy) = (icx - ocx, icy - ocy);
+    let n = resolution;
+    let mut i: usize = 0;
+    stream_quad_tri_strip(m, || {
+        if i > n { return None; }
+
+        let angle = start + (end - start) * i as Scalar / n as Scalar;
+        let p = unit_circle(angle);
+        let (cos, sin) = (p[0], p[1]);
+        i += 1;
+        // Distance along the ray to the inner ellipse.
+        let ti = if iw <= 0.0 || ih <= 0.0 { 0.0 } else {
+            1.0 / ((cos / iw).powi(2) + (sin / ih).powi(2)).sqrt()
+        };
+        // Distance along the ray to the outer ellipse.
+        let a = (cos / ow).powi(2) + (sin / oh).powi(2);
+        let b = 2.0 * (dx * cos / (ow * ow) + dy * sin / (oh * oh));
+        let c = (dx / ow).powi(2) + (dy / oh).powi(2) - 1.0;
+        let to = (-b + (b * b - 4.0 * a * c).max(0.0).sqrt()) / (2.0 * a);
+        Some(([icx + cos * ti, icy + sin * ti],
+            [icx + cos * to, icy + sin * to]))
+    }, f);
+}
+
+/// Streams a ring between two radii as a tri strip.
+///
+/// The ring covers the angles from `start` to `end` in radians,
+/// which gives an arc segment of the ring when the span is
+/// less than a full circle.
+#[inline(always)]
+pub fn with_ring_tri_strip<F>(
+    resolution: usize,
+    m: Matrix2d,
+    center: Vec2d,
+    inner_radius: Radius,
+    outer_radius: Radius,
+    start: Scalar,
+    end: Scalar,
+    f: F
+)
+    where
+        F: FnMut(&[f32])
+{
+
+    let (cx, cy) = (center[0], center[1]);
+    let n = resolution;
+    let mut i: usize = 0;
+    stream_quad_tri_strip(m, || {
+        if i > n { return None; }
+
+        let angle = start + (end - start) * i as Scalar / n as Scalar;
+        let p = unit_circle(angle);
+        let (cos, sin) = (p[0], p[1]);
+        i += 1;
+        Some(([cx + cos * inner_radius, cy + sin * inner_radius],
+            [cx + cos * outer_radius, cy + sin * outer_radius]))
+    }, f);
+}
+
+/// Returns point `j` of the outline of a rectangle with a radius per corner.
+///
+/// The radii are for the top-left, top-right, bottom-right and
+/// bottom-left corners, which have `resolution_corner` points each.
+/// Each radius is clamped between zero and half the shorter side,
+/// so adjacent corners do not overlap.
+/// The point is moved outwards by `offset`,
+/// diagonally at sharp corners with a radius of zero.
+pub fn round_corners_rectangle_point(
+    resolution_corner: usize,
+    rect: Rectangle,
+    radii: [Radius; 4],
+    j: usize,
+    offset: Scalar
+) -> Vec2d {
+    let (x, y, w, h) = (rect[0], rect[1], rect[2], rect[3]);
+    let k = (j / resolution_corner) % 4;
+    let r = radii[k].max(0.0).min(0.5 * w.abs().min(h.abs()));
+    let (sx, sy) = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)][k];
+    let cx = if sx < 0.0 { x + r } else { x + w - r };
+    let cy = if sy < 0.0 { y + r } else { y + h - r };
+    if r == 0.0 { return [cx + sx * offset, cy + sy * offset]; }
+
+    let t = if resolution_corner > 1 {
+        (j % resolution_corner) as Scalar / (resolution_corner - 1) as Scalar
+    } else { 0.5 };
+    let angle = ((2 + k) as Scalar + t) * Radians::_90();
+    let radius = r + offset;
+    let p = unit_circle(angle);
+    [cx + p[0] * radius, cy + p[1] * radius]
+}
+
+/// Streams a rectangle with a radius per corner.
+///
+/// See `round_corners_rectangle_point`.
+pub fn with_round_corners_rectangle_tri_list<F>(
+    resolution_corner: usize,
+    m: Matrix2d,
+    rect: Rectangle,
+    radii: [Radius; 4],
+    f: F
+)
+    where
+        F: FnMut(&[f32])
+{
+    let n = resolution_corner * 4;
+    let mut j: usize = 0;
+    stream_polygon_tri_list(m, || {
+        if j >= n { return None; }
+        j += 1;
+        Some(round_corners_rectangle_point(resolution_corner, rect, radii, j - 1, 0.0))
+    }, f);
+}
+
+/// Streams the border of a rectangle with a radius per corner.
+///
+/// The border is centered on the outline, and mitered at sharp corners.
+/// See `round_corners_rectangle_point`.
+pub fn with_round_corners_rectangle_border_tri_list<F>(
+    resolution_corner: usize,
+    m: Matrix2d,
+    rect: Rectangle,
+    radii: [Radius; 4],
+    border_radius: Radius,
+    f: F
+)
+    where
+        F: FnMut(&[f32])
+{
+    let n = resolution_corner * 4;
+    let mut j: usize = 0;
+    stream_quad_tri_list(m, || {
+        if j > n { return None; }
+        let point = |offset| round_corners_rectangle_point(
+            resolution_corner, rect, radii, j % n, offset);
+        let edge = (point(border_radius), point(-border_radius));
+        j += 1;
+        Some(edge)
+    }, f);
+}
+
 /// Streams a round rectangle border.
 #[inline(always)]
 pub fn with_round_rectangle_border_tri_list<F>(
@@ -371,8 +1192,8 @@ pub fn with_round_rectangle_border_tri_list<F>(
                     + 3.0 * Radians::_90();
                 // Set center of the circle to the last corner.
                 let (cx, cy) = (x + w - radius, y + radius);
-                let cos = angle.cos();
-                let sin = angle.sin();
+                let p = unit_circle(angle);
+                let (cos, sin) = (p[0], p[1]);
                 Some(([cx + cos * radius1, cy + sin * radius1],
                     [cx + cos * radius2, cy + sin * radius2]))
             },
@@ -386,8 +1207,8 @@ pub fn with_round_rectangle_border_tri_list<F>(
                     + Radians::_180();
                 // Set center of the circle to the second last corner.
                 let (cx, cy) = (x + radius, y + radius);
-                let cos = angle.cos();
-                let sin = angle.sin();
+                let p = unit_circle(angle);
+                let (cos, sin) = (p[0], p[1]);
                 Some(([cx + cos * radius1, cy + sin * radius1],
                     [cx + cos * radius2, cy + sin * radius2]))
             },
@@ -401,8 +1222,8 @@ pub fn with_round_rectangle_border_tri_list<F>(
                     + Radians::_90();
                 // Set center of the circle to the second corner.
                 let (cx, cy) = (x + radius, y + h - radius);
-                let cos = angle.cos();
-                let sin = angle.sin();
+                let p = unit_circle(angle);
+                let (cos, sin) = (p[0], p[1]);
                 Some(([cx + cos * radius1, cy + sin * radius1],
                     [cx + cos * radius2, cy + sin * radius2]))
             },
@@ -414,8 +1235,8 @@ pub fn with_round_rectangle_border_tri_list<F>(
                     * Radians::_90();
                 // Set center of the circle to the first corner.
                 let (cx, cy) = (x + w - radius, y + h - radius);
-                let cos = angle.cos();
-                let sin = angle.sin();
+                let p = unit_circle(angle);
+                let (cos, sin) = (p[0], p[1]);
                 Some(([cx + cos * radius1, cy + sin * radius1],
                     [cx + cos * radius2, cy + sin * radius2]))
             },
@@ -507,16 +1328,219 @@ pub fn stream_quad_tri_list<E, F>(
         // Buffer is full.
         if i * align_vertices >= vertices.len() {
             // Send chunk and start over.
+            ensure_ccw(&mut vertices[0..i * align_vertices]);
             f(&vertices[0..i * align_vertices]);
             i = 0;
         }
     }
 
     if i > 0 {
+        ensure_ccw(&mut vertices[0..i * align_vertices]);
         f(&vertices[0..i * align_vertices]);
     }
 }
 
+/// Streams quads into tri strip.
+///
+/// Uses buffers that fit inside L1 cache.
+/// The 'quad_edge' stream returns two points
+/// defining the next edge.
+/// When the buffer is full, the strip is sent and
+/// continued from the last edge in a new strip.
+pub fn stream_quad_tri_strip<E, F>(
+    m: Matrix2d,
+    mut quad_edge: E,
+    mut f: F
+)
+    where
+        E: FnMut() -> Option<(Vec2d, Vec2d)>,
+        F: FnMut(&[f32])
+{
+
+    let mut vertices: [f32; 720] = [0.0; 720];
+    // Counts the edges.
+    let mut i = 0;
+    let vertices_per_edge = 2;
+    let position_components_per_vertex = 2;
+    let align_vertices =
+        vertices_per_edge
+        * position_components_per_vertex;
+    loop {
+        let (p1, p2) = match quad_edge() {
+            None => break,
+            Some((val1, val2)) => (val1, val2)
+        };
+        let ind_out = i * align_vertices;
+        vertices[ind_out + 0] = tx(m, p1[0], p1[1]);
+        vertices[ind_out + 1] = ty(m, p1[0], p1[1]);
+        vertices[ind_out + 2] = tx(m, p2[0], p2[1]);
+        vertices[ind_out + 3] = ty(m, p2[0], p2[1]);
+        i += 1;
+
+        // Buffer is full.
+        if i * align_vertices >= vertices.len() {
+            // Send chunk and start over with the last edge.
+            let last = (i - 1) * align_vertices;
+            let mut last_edge = [0.0; 4];
+            for j in 0..align_vertices {
+                last_edge[j] = vertices[last + j];
+            }
+            ensure_ccw_quad_strip(&mut vertices[0..i * align_vertices]);
+            f(&vertices[0..i * align_vertices]);
+            for j in 0..align_vertices {
+                vertices[j] = last_edge[j];
+            }
+            i = 1;
+        }
+    }
+
+    if i > 1 {
+        ensure_ccw_quad_strip(&mut vertices[0..i * align_vertices]);
+        f(&vertices[0..i * align_vertices]);
+    }
+}
+
+/// Returns twice the signed area of a triangle in a vertex list.
+///
+/// The area is positive for counter-clockwise triangles
+/// with the y axis pointing up.
+#[inline(always)]
+fn signed_area2(t: &[f32]) -> f32 {
+    (t[2] - t[0]) * (t[5] - t[1]) - (t[4] - t[0]) * (t[3] - t[1])
+}
+
+/// Makes every triangle of a triangle list counter-clockwise.
+///
+/// Clockwise triangles are flipped by swapping the last two vertices.
+/// The triangulation functions emit counter-clockwise triangles
+/// in normalized device coordinates, with the y axis pointing up,
+/// which is the default front face for culling.
+pub fn ensure_ccw(vertices: &mut [f32]) {
+    for t in vertices.chunks_mut(6) {
+        if t.len() == 6 && signed_area2(t) < 0.0 {
+            t.swap(2, 4);
+            t.swap(3, 5);
+        }
+    }
+}
+
+/// Makes every triangle of a triangle list counter-clockwise,
+/// keeping the texture coordinates with their vertices.
+pub fn ensure_ccw_uv(vertices: &mut [f32], uvs: &mut [f32]) {
+    for (t, uv) in vertices.chunks_mut(6).zip(uvs.chunks_mut(6)) {
+        if t.len() == 6 && uv.len() == 6 && signed_area2(t) < 0.0 {
+            t.swap(2, 4);
+            t.swap(3, 5);
+            uv.swap(2, 4);
+            uv.swap(3, 5);
+        }
+    }
+}
+
+/// Makes every triangle of a triangle list counter-clockwise,
+/// keeping the colors with their vertices.
+///
+/// Colors have four components per vertex.
+pub fn ensure_ccw_c(vertices: &mut [f32], colors: &mut [f32]) {
+    for (t, c) in vertices.chunks_mut(6).zip(colors.chunks_mut(12)) {
+        if t.len() == 6 && c.len() == 12 && signed_area2(t) < 0.0 {
+            t.swap(2, 4);
+            t.swap(3, 5);
+            for k in 0..4 {
+                c.swap(4 + k, 8 + k);
+            }
+        }
+    }
+}
+
+/// Makes a strip of quad edges counter-clockwise,
+/// by swapping the points of each edge when the first triangle is clockwise.
+fn ensure_ccw_quad_strip(vertices: &mut [f32]) {
+    if vertices.len() < 6 || signed_area2(vertices) >= 0.0 { return; }
+
+    for edge in vertices.chunks_mut(4) {
+        if edge.len() == 4 {
+            edge.swap(0, 2);
+            edge.swap(1, 3);
+        }
+    }
+}
+
+/// Converts a tri strip into tri list.
+///
+/// Every second triangle is flipped to keep the winding
+/// of the first triangle.
+/// Uses buffers that fit inside L1 cache.
+pub fn tri_strip_to_tri_list<F>(strip: &[f32], mut f: F)
+    where
+        F: FnMut(&[f32])
+{
+    let mut vertices: [f32; 720] = [0.0; 720];
+    let n = strip.len() / 2;
+    let mut i = 0;
+    let align_vertices = 6;
+    for k in 2..n {
+        let (a, b, c) = if k % 2 == 0 { (k - 2, k - 1, k) } else { (k - 1, k - 2, k) };
+        let ind_out = i * align_vertices;
+        vertices[ind_out + 0] = strip[a * 2];
+        vertices[ind_out + 1] = strip[a * 2 + 1];
+        vertices[ind_out + 2] = strip[b * 2];
+        vertices[ind_out + 3] = strip[b * 2 + 1];
+        vertices[ind_out + 4] = strip[c * 2];
+        vertices[ind_out + 5] = strip[c * 2 + 1];
+        i += 1;
+
+        // Buffer is full.
+        if i * align_vertices >= vertices.len() {
+            // Send chunk and start over.
+            f(&vertices[0..i * align_vertices]);
+            i = 0;
+        }
+    }
+
+    if i > 0 {
+        f(&vertices[0..i * align_vertices]);
+    }
+}
+
+/// Converts a tri strip with texture coordinates into tri list.
+///
+/// Every second triangle is flipped to keep the winding
+/// of the first triangle.
+/// Uses buffers that fit inside L1 cache.
+pub fn tri_strip_uv_to_tri_list<F>(strip: &[f32], uvs: &[f32], mut f: F)
+    where
+        F: FnMut(&[f32], &[f32])
+{
+    let mut vertices: [f32; 720] = [0.0; 720];
+    let mut texture_coords: [f32; 720] = [0.0; 720];
+    let n = strip.len() / 2;
+    let mut i = 0;
+    let align_vertices = 6;
+    for k in 2..n {
+        let (a, b, c) = if k % 2 == 0 { (k - 2, k - 1, k) } else { (k - 1, k - 2, k) };
+        let ind_out = i * align_vertices;
+        for (j, &v) in [a, b, c].iter().enumerate() {
+            vertices[ind_out + j * 2] = strip[v * 2];
+            vertices[ind_out + j * 2 + 1] = strip[v * 2 + 1];
+            texture_coords[ind_out + j * 2] = uvs[v * 2];
+            texture_coords[ind_out + j * 2 + 1] = uvs[v * 2 + 1];
+        }
+        i += 1;
+
+        // Buffer is full.
+        if i * align_vertices >= vertices.len() {
+            // Send chunk and start over.
+            f(&vertices[0..i * align_vertices], &texture_coords[0..i * align_vertices]);
+            i = 0;
+        }
+    }
+
+    if i > 0 {
+        f(&vertices[0..i * align_vertices], &texture_coords[0..i * align_vertices]);
+    }
+}
+
 /// Splits polygon into convex segments.
 /// Create a buffer that fits into L1 cache with 1KB overhead.
 pub fn with_polygon_tri_list<F>(
@@ -541,7 +1565,335 @@ pub fn with_polygon_tri_list<F>(
     );
 }
 
+/// Returns true if a polygon is convex and does not intersect itself.
+///
+/// Convex polygons are filled the same under both fill rules,
+/// and can be streamed with `with_polygon_tri_list` without allocating.
+/// Collinear and repeated points are allowed.
+/// Polygons with fewer than 3 points are convex,
+/// and polygons with non-finite points are not.
+pub fn is_convex(polygon: Polygon) -> bool {
+    let n = polygon.len();
+    if n < 3 { return true; }
+    let mut turn = 0.0;
+    // The signs of the first and previous edge directions,
+    // and the number of sign changes, for x and y.
+    let mut first = [0.0; 2];
+    let mut prev = [0.0; 2];
+    let mut flips = [0; 2];
+    for i in 0..n {
+        let (a, b, c) = (polygon[i], polygon[(i + 1) % n], polygon[(i + 2) % n]);
+        if !(a[0].is_finite() && a[1].is_finite()) { return false; }
+        let cross = (b[0] - a[0]) * (c[1] - b[1]) - (b[1] - a[1]) * (c[0] - b[0]);
+        if cross != 0.0 {
+            if turn * cross < 0.0 { return false; }
+            turn = cross;
+        }
+        for k in 0..2 {
+            let d = b[k] - a[k];
+            if d == 0.0 { continue; }
+            if first[k] == 0.0 { first[k] = d; }
+            if prev[k] * d < 0.0 { flips[k] += 1; }
+            prev[k] = d;
+        }
+    }
+    // Close the cycle of directions.
+    for k in 0..2 {
+        if prev[k] * first[k] < 0.0 { flips[k] += 1; }
+    }
+    // A pentagram turns the same way at every corner,
+    // but changes direction more than twice along each axis.
+    flips[0] <= 2 && flips[1] <= 2
+}
+
+/// Orders numbers, with NaN after all other numbers.
+fn cmp_nan_last(a: Scalar, b: Scalar) -> Ordering {
+    match a.partial_cmp(&b) {
+        Some(ord) => ord,
+        None if a != a && b != b => Ordering::Equal,
+        None if a != a => Ordering::Greater,
+        None => Ordering::Less,
+    }
+}
+
+/// The rule deciding which areas of overlapping polygons are filled.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FillRule {
+    /// Fills areas with a winding number different from zero.
+    NonZero,
+    /// Fills areas with an odd winding number.
+    EvenOdd,
+}
+
+/// Scratch memory for tessellating polygons, reused between draws.
+///
+/// Keeping one around avoids growing new buffers for every draw,
+/// once it fits the largest polygons.
+#[derive(Clone)]
+pub struct TessellationBuffers {
+    // Edges as [x0, y0, x1, y1] with y0 < y1, and winding direction.
+    edges: Vec<([Scalar; 4], i32)>,
+    ys: Vec<Scalar>,
+    // Sorted by x in the middle of slab, storing x at the top and bottom.
+    active: Vec<(Scalar, Scalar, Scalar, i32)>,
+}
+
+impl TessellationBuffers {
+    /// Creates empty buffers, without allocating.
+    pub fn new() -> TessellationBuffers {
+        TessellationBuffers {
+            edges: Vec::new(),
+            ys: Vec::new(),
+            active: Vec::new(),
+        }
+    }
+
+    /// Returns the total number of elements the buffers
+    /// can hold without allocating.
+    pub fn capacity(&self) -> usize {
+        self.edges.capacity() + self.ys.capacity() + self.active.capacity()
+    }
+}
+
+/// Streams polygons into tri list using a fill rule.
+///
+/// The polygons may be concave, intersect themselves and each other.
+/// Under the non-zero rule, a polygon inside another
+/// with opposite orientation becomes a hole.
+/// The area is split into horizontal slabs at every vertex and
+/// edge intersection, and each covered span of a slab is
+/// emitted as a trapezoid.
+///
+/// Edges with infinite or NaN coordinates are skipped.
+/// This allocates temporary memory, see `with_polygons_tri_list_buffered`,
+/// and takes quadratic time in the number of edges, so use
+/// `with_polygon_tri_list` for convex polygons, see `is_convex`.
+pub fn with_polygons_tri_list<F>(
+    m: Matrix2d,
+    polygons: Polygons,
+    fill_rule: FillRule,
+    f: F
+)
+    where
+        F: FnMut(&[f32])
+{
+    with_polygons_tri_list_buffered(
+        &mut TessellationBuffers::new(), m, polygons, fill_rule, f);
+}
+
+/// Streams polygons into tri list using a fill rule,
+/// using buffers for temporary memory.
+///
+/// See `with_polygons_tri_list`.
+pub fn with_polygons_tri_list_buffered<F>(
+    buffers: &mut TessellationBuffers,
+    m: Matrix2d,
+    polygons: Polygons,
+    fill_rule: FillRule,
+    f: F
+)
+    where
+        F: FnMut(&[f32])
+{
+    stream_polygons_tri_list_buffered(
+        buffers, m, polygons.iter().map(|p| *p), fill_rule, f);
+}
+
+/// Streams polygons from an iterator into tri list using a fill rule,
+/// using buffers for temporary memory.
+///
+/// Unlike `with_polygons_tri_list_buffered`, this does not need
+/// a slice of the polygons, so it does not allocate once the buffers
+/// are large enough. See `with_polygons_tri_list`.
+pub fn stream_polygons_tri_list_buffered<'a, I, F>(
+    buffers: &mut TessellationBuffers,
+    m: Matrix2d,
+    polygons: I,
+    fill_rule: FillRule,
+    mut f: F
+)
+    where
+        I: Iterator<Item = Polygon<'a>>,
+        F: FnMut(&[f32])
+{
+    let TessellationBuffers { ref mut edges, ref mut ys, ref mut active } = *buffers;
+    edges.clear();
+    ys.clear();
+    for polygon in polygons {
+        let n = polygon.len();
+        if n < 3 { continue; }
+        for i in 0..n {
+            let a = polygon[i];
+            let b = polygon[(i + 1) % n];
+            let finite = a.iter().chain(b.iter()).all(|x| x.is_finite());
+            if !finite { continue; }
+            if a[1] < b[1] {
+                edges.push(([a[0], a[1], b[0], b[1]], 1));
+            } else if a[1] > b[1] {
+                edges.push(([b[0], b[1], a[0], a[1]], -1));
+            }
+        }
+    }
+
+    // Split at every vertex and every edge intersection,
+    // so edges do not cross inside a slab.
+    for i in 0..edges.len() {
+        let (e, _) = edges[i];
+        ys.push(e[1]);
+        ys.push(e[3]);
+        for j in i + 1..edges.len() {
+            let (g, _) = edges[j];
+            let d = (e[2] - e[0]) * (g[3] - g[1]) - (e[3] - e[1]) * (g[2] - g[0]);
+            if d == 0.0 { continue; }
+            let t = ((g[0] - e[0]) * (g[3] - g[1]) - (g[1] - e[1]) * (g[2] - g[0])) / d;
+            let u = ((g[0] - e[0]) * (e[3] - e[1]) - (g[1] - e[1]) * (e[2] - e[0])) / d;
+            if t > 0.0 && t < 1.0 && u > 0.0 && u < 1.0 {
+                ys.push(e[1] + t * (e[3] - e[1]));
+            }
+        }
+    }
+    ys.sort_by(|&a, &b| cmp_nan_last(a, b));
+    ys.dedup();
+
+    let x_at = |e: [Scalar; 4], y: Scalar| -> Scalar {
+        e[0] + (y - e[1]) * (e[2] - e[0]) / (e[3] - e[1])
+    };
+
+    let mut vertices: [f32; 720] = [0.0; 720];
+    let mut i = 0;
+    let align_vertices = 12;
+    for k in 1..ys.len() {
+        let (ya, yb) = (ys[k - 1], ys[k]);
+        let ym = 0.5 * (ya + yb);
+        active.clear();
+        for &(e, dir) in edges.iter() {
+            if e[1] < ym && e[3] > ym {
+                active.push((x_at(e, ym), x_at(e, ya), x_at(e, yb), dir));
+            }
+        }
+        active.sort_by(|a, b| cmp_nan_last(a.0, b.0));
+
+        let is_inside = |winding: i32| match fill_rule {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        };
+        let mut winding = 0;
+        let mut left = (0.0, 0.0);
+        for &(_, xa, xb, dir) in active.iter() {
+            let was_inside = is_inside(winding);
+            winding += dir;
+            let inside = is_inside(winding);
+            if !was_inside && inside {
+                left = (xa, xb);
+            } else if was_inside && !inside {
+                let (lxa, lxb) = left;
+                let ind_out = i * align_vertices;
+                vertices[ind_out + 0] = tx(m, lxa, ya);
+                vertices[ind_out + 1] = ty(m, lxa, ya);
+                vertices[ind_out + 2] = tx(m, xa, ya);
+                vertices[ind_out + 3] = ty(m, xa, ya);
+                vertices[ind_out + 4] = tx(m, xb, yb);
+                vertices[ind_out + 5] = ty(m, xb, yb);
+
+                vertices[ind_out + 6] = tx(m, lxa, ya);
+                vertices[ind_out + 7] = ty(m, lxa, ya);
+                vertices[ind_out + 8] = tx(m, xb, yb);
+                vertices[ind_out + 9] = ty(m, xb, yb);
+                vertices[ind_out + 10] = tx(m, lxb, yb);
+                vertices[ind_out + 11] = ty(m, lxb, yb);
+
+                i += 1;
+                // Buffer is full.
+                if i * align_vertices >= vertices.len() {
+                    // Send chunk and start over.
+                    ensure_ccw(&mut vertices[0..i * align_vertices]);
+                    f(&vertices[0..i * align_vertices]);
+                    i = 0;
+                }
+            }
+        }
+    }
+
+    if i > 0 {
+        ensure_ccw(&mut vertices[0..i * align_vertices]);
+        f(&vertices[0..i * align_vertices]);
+    }
+}
+
+/// Streams the pixels of a line between two pixels into tri list.
+///
+/// The pixels are chosen with Bresenham's algorithm,
+/// and both end pixels are included.
+/// Consecutive pixels in the same row, or in the same column
+/// for steep lines, are merged into one quad of 1 unit thickness.
+/// The matrix transforms pixel coordinates, usually `Context::view`.
+pub fn with_pixel_line_tri_list<F>(
+    m: Matrix2d,
+    from: [i64; 2],
+    to: [i64; 2],
+    mut f: F
+)
+    where F: FnMut(&[f32])
+{
+    use std::cmp::{ max, min };
+
+    let mut vertices: [f32; 720] = [0.0; 720];
+    let mut i = 0;
+    let (x1, y1) = (to[0], to[1]);
+    let dx = if x1 > from[0] { x1 - from[0] } else { from[0] - x1 };
+    let dy = if y1 > from[1] { y1 - from[1] } else { from[1] - y1 };
+    let sx = if from[0] < x1 { 1 } else { -1 };
+    let sy = if from[1] < y1 { 1 } else { -1 };
+    let x_major = dx >= dy;
+    let mut err = dx - dy;
+    let (mut x, mut y) = (from[0], from[1]);
+    let mut run = (x, y);
+    loop {
+        let done = x == x1 && y == y1;
+        let (px, py) = (x, y);
+        if !done {
+            let e2 = 2 * err;
+            if e2 > -dy { err -= dy; x += sx; }
+            if e2 < dx { err += dx; y += sy; }
+        }
+        // The run ends at the last pixel, or when stepping on the minor axis.
+        if done || (x_major && y != py) || (!x_major && x != px) {
+            let (rx, ry) = (min(run.0, px), min(run.1, py));
+            let rect = [
+                rx as Scalar,
+                ry as Scalar,
+                (max(run.0, px) - rx + 1) as Scalar,
+                (max(run.1, py) - ry + 1) as Scalar
+            ];
+            let mut quad = rect_tri_list_xy(m, rect);
+            ensure_ccw(&mut quad);
+            for (v, q) in vertices[i..i + 12].iter_mut().zip(quad.iter()) {
+                *v = *q;
+            }
+            i += 12;
+            // Buffer is full.
+            if i == vertices.len() {
+                f(&vertices[..i]);
+                i = 0;
+            }
+            run = (x, y);
+        }
+        if done { break; }
+    }
+
+    if i > 0 {
+        f(&vertices[..i]);
+    }
+}
+
 /// Creates triangle list vertices from rectangle.
+///
+/// Returns the six vertices in an array on the stack,
+/// so drawing a square rectangle does not allocate.
+/// The vertices match the texture coordinates of `rect_tri_list_uv`,
+/// so the winding depends on the transform.
+/// Use `ensure_ccw_uv` on both to make the triangles counter-clockwise.
 #[inline(always)]
 pub fn rect_tri_list_xy(
     m: Matrix2d,
@@ -549,17 +1901,20 @@ pub fn rect_tri_list_xy(
 ) -> [f32; 12] {
     let (x, y, w, h) = (rect[0], rect[1], rect[2], rect[3]);
     let (x2, y2) = (x + w, y + h);
+    let p = transform4(m, [x, x2, x, x2], [y, y, y2, y2]);
     [
-        tx(m,x,y), ty(m,x,y),
-        tx(m,x2,y), ty(m,x2,y),
-        tx(m,x,y2), ty(m,x,y2),
-        tx(m,x2,y), ty(m,x2,y),
-        tx(m,x2,y2), ty(m,x2,y2),
-        tx(m,x,y2), ty(m,x,y2)
+        p[0], p[1],
+        p[2], p[3],
+        p[4], p[5],
+        p[2], p[3],
+        p[6], p[7],
+        p[4], p[5]
     ]
 }
 
-/// Creates triangle list vertices from rectangle.
+/// Creates triangle list vertices from rectangle border.
+///
+/// The triangles are counter-clockwise, see `ensure_ccw`.
 #[inline(always)]
 pub fn rect_border_tri_list_xy(
     m: Matrix2d,
@@ -573,39 +1928,28 @@ pub fn rect_border_tri_list_xy(
     let (x21, y21) = (x + border_radius, y + border_radius);
     let (x12, y12) = (x + w1, y + h1);
     let (x22, y22) = (x + w2, y + h2);
-    [
-        tx(m, x11, y11), ty(m, x11, y11),
-        tx(m, x12, y11), ty(m, x12, y11),
-        tx(m, x21, y21), ty(m, x21, y21),
-
-        tx(m, x21, y21), ty(m, x21, y21),
-        tx(m, x12, y11), ty(m, x12, y11),
-        tx(m, x22, y21), ty(m, x22, y21),
-
-        tx(m, x22, y21), ty(m, x22, y21),
-        tx(m, x12, y11), ty(m, x12, y11),
-        tx(m, x12, y12), ty(m, x12, y12),
-
-        tx(m, x22, y21), ty(m, x22, y21),
-        tx(m, x12, y12), ty(m, x12, y12),
-        tx(m, x22, y22), ty(m, x22, y22),
-
-        tx(m, x12, y12), ty(m, x12, y12),
-        tx(m, x22, y22), ty(m, x22, y22),
-        tx(m, x11, y12), ty(m, x11, y12),
-
-        tx(m, x22, y22), ty(m, x22, y22),
-        tx(m, x11, y12), ty(m, x11, y12),
-        tx(m, x21, y22), ty(m, x21, y22),
-
-        tx(m, x11, y12), ty(m, x11, y12),
-        tx(m, x21, y21), ty(m, x21, y21),
-        tx(m, x21, y22), ty(m, x21, y22),
-
-        tx(m, x11, y12), ty(m, x11, y12),
-        tx(m, x11, y11), ty(m, x11, y11),
-        tx(m, x21, y21), ty(m, x21, y21),
-    ]
+    // The outer corners, then the inner corners.
+    let o = transform4(m, [x11, x12, x12, x11], [y11, y11, y12, y12]);
+    let i = transform4(m, [x21, x22, x22, x21], [y21, y21, y22, y22]);
+    let (o11, o21, o22, o12) = ([o[0], o[1]], [o[2], o[3]], [o[4], o[5]], [o[6], o[7]]);
+    let (i11, i21, i22, i12) = ([i[0], i[1]], [i[2], i[3]], [i[4], i[5]], [i[6], i[7]]);
+    let mut vertices = [0.0; 48];
+    let triangles = [
+        o11, o21, i11,
+        i11, o21, i21,
+        i21, o21, o22,
+        i21, o22, i22,
+        o22, i22, o12,
+        i22, o12, i12,
+        o12, i11, i12,
+        o12, o11, i11,
+    ];
+    for (v, p) in vertices.chunks_mut(2).zip(triangles.iter()) {
+        v[0] = p[0];
+        v[1] = p[1];
+    }
+    ensure_ccw(&mut vertices);
+    vertices
 }
 
 /// Creates triangle list texture coords from image.
@@ -628,3 +1972,309 @@ pub fn rect_tri_list_uv<I: ImageSize>(
     ]
 }
 
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_unit_circle() {
+        use radians::Radians;
+        use std::num::Float;
+
+        let full: Scalar = Radians::_360();
+        for &n in [3, 16, 32, 100, 128, 1000, 1024].iter() {
+            for i in 0..n + 1 {
+                let angle = i as Scalar * full / n as Scalar - 0.5 * full;
+                let p = unit_circle(angle);
+                assert!((p[0] - angle.cos()).abs() < 0.00001);
+                assert!((p[1] - angle.sin()).abs() < 0.00001);
+            }
+        }
+        assert_eq!(unit_circle(0.0), [1.0, 0.0]);
+        assert_eq!(unit_circle(0.25 * full), [0.0, 1.0]);
+        let nan: Scalar = Float::nan();
+        let inf: Scalar = Float::infinity();
+        assert!(unit_circle(nan)[0].is_nan());
+        assert!(unit_circle(inf)[1].is_nan());
+        assert!(unit_circle(-inf)[0].is_nan());
+        let p = unit_circle(1e30);
+        assert!((p[0] * p[0] + p[1] * p[1] - 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_tessellation_buffers() {
+        use std::num::Float;
+        use vecmath::identity;
+
+        let star: Vec<[Scalar; 2]> = (0..10).map(|i| {
+            let r = if i % 2 == 0 { 10.0 } else { 4.0 };
+            let angle = i as Scalar * 0.6283;
+            [r * angle.cos(), r * angle.sin()]
+        }).collect();
+        let polygons = [&star[..]];
+        let mut expected = Vec::new();
+        with_polygons_tri_list(identity(), &polygons, FillRule::NonZero,
+            |v| expected.extend(v.iter().map(|x| *x)));
+
+        let mut buffers = TessellationBuffers::new();
+        assert_eq!(buffers.capacity(), 0);
+        let mut capacity = 0;
+        for k in 0..3 {
+            let mut vertices = Vec::new();
+            with_polygons_tri_list_buffered(&mut buffers, identity(), &polygons,
+                FillRule::NonZero, |v| vertices.extend(v.iter().map(|x| *x)));
+            assert_eq!(vertices, expected);
+            // The buffers do not grow after the first draw.
+            if k == 0 { capacity = buffers.capacity(); }
+            assert!(capacity > 0);
+            assert_eq!(buffers.capacity(), capacity);
+        }
+    }
+
+    #[test]
+    fn test_resolution() {
+        use radians::Radians;
+        use std::num::Float;
+
+        let adaptive = Resolution::Adaptive(0.25);
+        let (small, large) = (adaptive.segments(10.0), adaptive.segments(20.0));
+        assert!(small > MIN_SEGMENTS && small < large && large < MAX_SEGMENTS);
+        // The chord deviation stays under the tolerance.
+        let pi: Scalar = Radians::_180();
+        assert!(10.0 * (1.0 - (pi / small as Scalar).cos()) <= 0.25);
+        assert_eq!(adaptive.segments(0.0), MIN_SEGMENTS);
+        assert_eq!(adaptive.segments(1e9), MAX_SEGMENTS);
+        assert_eq!(Resolution::Adaptive(0.0).segments(10.0), MAX_SEGMENTS);
+        assert_eq!(Resolution::Fixed(5).segments(1e9), 5);
+    }
+
+    #[test]
+    fn test_tri_strip_to_tri_list() {
+        // A zig-zag strip of 6 vertices.
+        let strip = [
+            0.0, 0.0,  0.0, 1.0,  1.0, 0.0,
+            1.0, 1.0,  2.0, 0.0,  2.0, 1.0
+        ];
+        let n = strip.len() / 2;
+        let mut list = Vec::new();
+        tri_strip_to_tri_list(&strip, |v| list.extend(v.iter().map(|x| *x)));
+        assert_eq!(list.len() / 2, (n - 2) * 3);
+        // All triangles have the same winding as the first one.
+        for t in list.chunks(6) {
+            let cross = (t[2] - t[0]) * (t[5] - t[1]) - (t[4] - t[0]) * (t[3] - t[1]);
+            assert!(cross < 0.0);
+        }
+        assert_eq!(list[6..12].to_vec(), vec![1.0, 0.0, 0.0, 1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_transform4() {
+        use vecmath::{ multiply, rotate_radians, translate };
+
+        let m = multiply(translate([300.0, -20.0]), rotate_radians(0.7));
+        let (xs, ys) = ([0.0, 10.5, -3.25, 1000.0], [1.0, -7.0, 0.5, 250.0]);
+        let p = transform4(m, xs, ys);
+        let q = transform4_scalar(m, xs, ys);
+        for i in 0..4 {
+            assert_eq!(q[2 * i], tx(m, xs[i], ys[i]));
+            assert_eq!(q[2 * i + 1], ty(m, xs[i], ys[i]));
+            assert!((p[2 * i] - tx(m, xs[i], ys[i])).abs() < 0.001);
+            assert!((p[2 * i + 1] - ty(m, xs[i], ys[i])).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn test_polyline() {
+        use line::{ Join, Shape };
+        use vecmath::{ identity, inside_triangle };
+
+        let triangles = |points: &[Vec2d], closed: bool, cap: Shape, join: Join| {
+            let mut triangles = Vec::new();
+            with_polyline_tri_list(128, identity(), points, closed, 1.0, cap, join, |v| {
+                for t in v.chunks(6) {
+                    let p = |i: usize| [t[2 * i] as Scalar, t[2 * i + 1] as Scalar];
+                    triangles.push([p(0), p(1), p(2)]);
+                }
+            });
+            triangles
+        };
+        let covered = |triangles: &[[Vec2d; 3]], p: Vec2d| {
+            triangles.iter().any(|t| inside_triangle(*t, p))
+        };
+
+        let corner = [[0.0, 0.0], [10.0, 0.0], [10.0, 0.0], [10.0, 10.0]];
+        let miter = triangles(&corner, false, Shape::Square, Join::Miter);
+        assert_eq!(miter.len(), 6);
+        assert!(miter.iter().any(|t| t.iter().any(|&p| p == [11.0, -1.0])));
+        assert!(covered(&miter, [10.9, -0.9]));
+        assert!(!covered(&miter, [-0.1, 0.0]));
+        let bevel = triangles(&corner, false, Shape::Square, Join::Bevel);
+        assert_eq!(bevel.len(), 5);
+        assert!(!covered(&bevel, [10.9, -0.9]));
+        let round = triangles(&corner, false, Shape::Round, Join::Round);
+        assert_eq!(round.len(), 4 + 32 + 2 * 64);
+        assert!(covered(&round, [10.6, -0.6]));
+        assert!(covered(&round, [-0.9, 0.0]));
+        assert!(!covered(&round, [10.9, -0.9]));
+
+        // Straight on, there is nothing to join.
+        assert_eq!(triangles(&[[0.0, 0.0], [5.0, 0.0], [10.0, 0.0]],
+            false, Shape::Bevel, Join::Miter).len(), 4 + 2);
+        // Sharp corners are beveled.
+        assert_eq!(triangles(&[[0.0, 0.0], [10.0, 0.0], [0.0, 1.0]],
+            false, Shape::Square, Join::Miter).len(), 5);
+        // Closed polylines join all corners, with or without the first point repeated.
+        let square = [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]];
+        assert_eq!(triangles(&square, true, Shape::Round, Join::Miter).len(), 16);
+        let mut repeated = square.to_vec();
+        repeated.push([0.0, 0.0]);
+        assert_eq!(triangles(&repeated, true, Shape::Round, Join::Miter).len(), 16);
+        // A single point draws a dot.
+        assert_eq!(triangles(&[[1.0, 1.0]], false, Shape::Bevel, Join::Miter).len(), 2);
+        assert_eq!(triangles(&[], false, Shape::Round, Join::Round).len(), 0);
+
+        // All triangles are counter-clockwise, in one call per 120 triangles.
+        let zigzag: Vec<Vec2d> = (0..100)
+            .map(|i| [i as Scalar, if i % 2 == 0 { 0.0 } else { 5.0 }]).collect();
+        let mut calls = 0;
+        with_polyline_tri_list(128, identity(), &zigzag, false, 1.0,
+            Shape::Round, Join::Round, |v| {
+                calls += 1;
+                for t in v.chunks(6) { assert!(signed_area2(t) >= 0.0); }
+            });
+        assert!(calls > 1);
+    }
+
+    #[test]
+    fn test_fill_rule() {
+        use radians::Radians;
+        use std::num::Float;
+        use vecmath::{ identity, inside_triangle };
+
+        // A self-intersecting five-pointed star.
+        let star: Vec<Vec2d> = (0..5).map(|i| {
+            let angle: Scalar = Radians::_90() + i as Scalar * 4.0 * Radians::_360() / 10.0;
+            [angle.cos(), angle.sin()]
+        }).collect();
+        let polygons = [&star[..]];
+        let covered = |fill_rule: FillRule, p: Vec2d| {
+            let mut res = false;
+            with_polygons_tri_list(identity(), &polygons, fill_rule, |v| {
+                for t in v.chunks(6) {
+                    let tri = [
+                        [t[0] as Scalar, t[1] as Scalar],
+                        [t[2] as Scalar, t[3] as Scalar],
+                        [t[4] as Scalar, t[5] as Scalar]
+                    ];
+                    if inside_triangle(tri, p) { res = true; }
+                }
+            });
+            res
+        };
+        let center = [0.01, 0.02];
+        let tip = [0.0, 0.8];
+        assert!(covered(FillRule::NonZero, center));
+        assert!(!covered(FillRule::EvenOdd, center));
+        assert!(covered(FillRule::NonZero, tip));
+        assert!(covered(FillRule::EvenOdd, tip));
+    }
+
+    #[test]
+    fn test_is_convex() {
+        use radians::Radians;
+        use std::num::Float;
+        use vecmath::identity;
+
+        let square = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+        assert!(is_convex(&square));
+        let reversed = [[0.0, 0.0], [0.0, 1.0], [1.0, 1.0], [1.0, 0.0]];
+        assert!(is_convex(&reversed));
+        assert!(is_convex(&[[0.0, 0.0], [1.0, 0.0], [2.0, 0.0], [1.0, 1.0]]));
+        assert!(!is_convex(&[[0.0, 0.0], [2.0, 0.0], [1.0, 0.5], [2.0, 2.0], [0.0, 2.0]]));
+        let star: Vec<Vec2d> = (0..5).map(|i| {
+            let angle: Scalar = Radians::_90() + i as Scalar * 4.0 * Radians::_360() / 10.0;
+            [angle.cos(), angle.sin()]
+        }).collect();
+        assert!(!is_convex(&star));
+
+        // Non-finite points neither count as convex nor panic when sorting.
+        let nan: Scalar = Float::nan();
+        let bad = [[0.0, 0.0], [nan, 1.0], [1.0, 1.0], [2.0, 0.5], [1.0, 0.0]];
+        assert!(!is_convex(&bad));
+        let polygons = [&bad[..]];
+        with_polygons_tri_list(identity(), &polygons, FillRule::NonZero, |_| {});
+        let inf: Scalar = Float::infinity();
+        assert!(!is_convex(&[[0.0, 0.0], [inf, 0.0], [1.0, 1.0]]));
+    }
+
+    #[test]
+    fn test_ellipse_border() {
+        use vecmath::identity;
+
+        let radii = |rect: Rectangle, border_radius: Radius| {
+            let mut vertices = Vec::new();
+            with_ellipse_border_tri_list(16, identity(), rect, border_radius,
+                |v| vertices.extend(v.iter().map(|x| *x)));
+            // Two triangles per segment.
+            assert_eq!(vertices.len(), 16 * 6 * 2);
+            let (cx, cy) = (rect[0] + 0.5 * rect[2], rect[1] + 0.5 * rect[3]);
+            vertices.chunks(2).fold((Scalar::max_value(), 0.0), |(min, max), v| {
+                let (dx, dy) = (v[0] as Scalar - cx, v[1] as Scalar - cy);
+                let d = (dx * dx + dy * dy).sqrt();
+                (min.min(d), max.max(d))
+            })
+        };
+        let (inner, outer) = radii([0.0, 0.0, 10.0, 10.0], 1.0);
+        assert!((inner - 4.0).abs() < 1e-5);
+        assert!((outer - 6.0).abs() < 1e-5);
+        // A border wider than the ellipse closes into a disk.
+        let (inner, outer) = radii([0.0, 0.0, 10.0, 4.0], 3.0);
+        assert!(inner < 1e-5);
+        assert!((outer - 8.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_ellipse_ring() {
+        use vecmath::identity;
+
+        let strip = |outer: Rectangle, inner: Rectangle| {
+            let mut vertices = Vec::new();
+            with_ellipse_ring_tri_strip(32, identity(), outer, inner,
+                0.0, Radians::_360(), |v| vertices.extend(v.iter().map(|x| *x)));
+            assert_eq!(vertices.len(), 2 * 33 * 2);
+            vertices
+        };
+        let on_ellipse = |rect: Rectangle, x: f32, y: f32| {
+            let (w, h) = (0.5 * rect[2], 0.5 * rect[3]);
+            let (u, v) = ((x as Scalar - rect[0] - w) / w, (y as Scalar - rect[1] - h) / h);
+            (u * u + v * v - 1.0).abs() < 1e-4
+        };
+
+        // Concentric.
+        let (outer, inner) = ([0.0, 0.0, 20.0, 10.0], [5.0, 2.5, 10.0, 5.0]);
+        for p in strip(outer, inner).chunks(4) {
+            assert!(on_ellipse(inner, p[0], p[1]));
+            assert!(on_ellipse(outer, p[2], p[3]));
+        }
+
+        // Offset inner ellipse, the triangles keep the same winding.
+        let (outer, inner) = ([0.0, 0.0, 20.0, 20.0], [2.0, 8.0, 6.0, 4.0]);
+        let vertices = strip(outer, inner);
+        for p in vertices.chunks(4) {
+            assert!(on_ellipse(inner, p[0], p[1]));
+            assert!(on_ellipse(outer, p[2], p[3]));
+        }
+        let mut list = Vec::new();
+        tri_strip_to_tri_list(&vertices[..], |v| list.extend(v.iter().map(|x| *x)));
+        let cross = |t: &[f32]| (t[2] - t[0]) * (t[5] - t[1]) - (t[4] - t[0]) * (t[3] - t[1]);
+        let sign = cross(&list[..6]).signum();
+        for t in list.chunks(6) {
+            assert!(cross(t) * sign > 0.0);
+        }
+
+        // A zero size inner rectangle collapses to the center.
+        for p in strip(outer, [10.0, 10.0, 0.0, 0.0]).chunks(4) {
+            assert_eq!((p[0], p[1]), (10.0, 10.0));
+        }
+    }
+}
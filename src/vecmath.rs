@@ -29,8 +29,27 @@ use std::num::{Float};
 use modular_index::{previous};
 
 /// The type used for scalars.
+///
+/// This is `f64` with the default `scalar-f64` feature,
+/// or `f32` with the `scalar-f32` feature.
+#[cfg(feature = "scalar-f64")]
 pub type Scalar = f64;
 
+/// The type used for scalars.
+///
+/// This is `f64` with the default `scalar-f64` feature,
+/// or `f32` with the `scalar-f32` feature.
+#[cfg(feature = "scalar-f32")]
+pub type Scalar = f32;
+
+/// The tolerance of tests for rounding errors in `Scalar`.
+#[cfg(all(test, feature = "scalar-f64"))]
+pub const EPSILON: Scalar = 0.000001;
+
+/// The tolerance of tests for rounding errors in `Scalar`.
+#[cfg(all(test, feature = "scalar-f32"))]
+pub const EPSILON: Scalar = 0.0001;
+
 /// The type used for matrices.
 pub type Matrix2d = vecmath_lib::Matrix2x3<Scalar>;
 
@@ -101,6 +120,18 @@ pub fn identity() -> Matrix2d {
      [0.0, 1.0, 0.0]]
 }
 
+/// Computes the inverse of a matrix.
+///
+/// The matrix must be invertible,
+/// otherwise the result contains infinite or NaN values.
+#[inline(always)]
+pub fn invert(m: Matrix2d) -> Matrix2d {
+    let det = m[0][0] * m[1][1] - m[0][1] * m[1][0];
+    let (a, b, c, d) = (m[1][1] / det, -m[0][1] / det, -m[1][0] / det, m[0][0] / det);
+    [[a, b, -(a * m[0][2] + b * m[1][2])],
+     [c, d, -(c * m[0][2] + d * m[1][2])]]
+}
+
 /// Extract scale information from matrix.
 #[inline(always)]
 pub fn get_scale(m: Matrix2d) -> Vec2d {
@@ -110,6 +141,60 @@ pub fn get_scale(m: Matrix2d) -> Vec2d {
     ]
 }
 
+/// Decomposes a matrix into translation, rotation in radians and scale.
+///
+/// The rotation is the angle of the transformed x axis,
+/// and the scale is the length of the transformed axes.
+/// This is exact for matrices built by `compose`.
+/// A negative determinant is represented as a negative y scale,
+/// although a flip of the x axis and a rotation of 180 degrees
+/// gives the same matrix. Shear can not be represented
+/// and is lost, leaving the x axis intact.
+pub fn decompose(m: Matrix2d) -> (Vec2d, Scalar, Vec2d) {
+    let translation = [m[0][2], m[1][2]];
+    let rotation = m[1][0].atan2(m[0][0]);
+    let sx = (m[0][0] * m[0][0] + m[1][0] * m[1][0]).sqrt();
+    let sy = (m[0][1] * m[0][1] + m[1][1] * m[1][1]).sqrt();
+    let det = m[0][0] * m[1][1] - m[0][1] * m[1][0];
+    let sy = if det < 0.0 { -sy } else { sy };
+    (translation, rotation, [sx, sy])
+}
+
+/// Composes a matrix from translation, rotation in radians and scale.
+///
+/// Scales first, then rotates, then translates.
+pub fn compose(translation: Vec2d, rotation: Scalar, scale: Vec2d) -> Matrix2d {
+    let (c, s) = (rotation.cos(), rotation.sin());
+    [[c * scale[0], -s * scale[1], translation[0]],
+     [s * scale[0],  c * scale[1], translation[1]]]
+}
+
+#[cfg(test)]
+mod test_decompose {
+    use std::num::Float;
+    use super::*;
+
+    #[test]
+    fn test_decompose() {
+        let m = multiply(multiply(translate([10.0, -5.0]), rotate_radians(0.75)),
+            scale(2.0, 3.0));
+        let (t, r, s) = decompose(m);
+        let eps = 0.00001;
+        assert!((t[0] - 10.0).abs() < eps && (t[1] + 5.0).abs() < eps);
+        assert!((r - 0.75).abs() < eps);
+        assert!((s[0] - 2.0).abs() < eps && (s[1] - 3.0).abs() < eps);
+        let n = compose(t, r, s);
+        for (a, b) in m.iter().zip(n.iter()) {
+            for (x, y) in a.iter().zip(b.iter()) {
+                assert!((x - y).abs() < eps);
+            }
+        }
+
+        let (_, _, s) = decompose(scale(2.0, -1.0));
+        assert!((s[0] - 2.0).abs() < eps && (s[1] + 1.0).abs() < eps);
+    }
+}
+
 /// Compute the shortest vector from point to ray.
 /// A ray stores starting point and directional vector.
 #[inline(always)]
@@ -342,6 +427,51 @@ mod test_triangle {
     }
 }
 
+/// Returns true if point is inside polygon, or on its boundary.
+///
+/// Uses ray casting, which counts the edges crossed by a horizontal ray
+/// from the point, so the polygon may be concave or intersect itself.
+/// Areas where parts of the polygon overlap are treated as
+/// under the even-odd fill rule.
+pub fn point_in_polygon(polygon: Polygon, v: Vec2d) -> bool {
+    let n = polygon.len();
+    let mut inside = false;
+    for i in 0..n {
+        let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+        // Points on an edge are inside.
+        if line_side([a[0], a[1], b[0], b[1]], v) == 0.0
+        && v[0] >= a[0].min(b[0]) && v[0] <= a[0].max(b[0])
+        && v[1] >= a[1].min(b[1]) && v[1] <= a[1].max(b[1]) {
+            return true;
+        }
+        if (a[1] > v[1]) != (b[1] > v[1]) {
+            let x = a[0] + (v[1] - a[1]) * (b[0] - a[0]) / (b[1] - a[1]);
+            if v[0] < x { inside = !inside; }
+        }
+    }
+    inside
+}
+
+#[cfg(test)]
+mod test_point_in_polygon {
+    use super::*;
+
+    #[test]
+    fn test_point_in_polygon() {
+        // An L shape.
+        let polygon = [[0.0, 0.0], [10.0, 0.0], [10.0, 4.0], [4.0, 4.0], [4.0, 10.0], [0.0, 10.0]];
+        assert!(point_in_polygon(&polygon, [2.0, 8.0]));
+        assert!(point_in_polygon(&polygon, [8.0, 2.0]));
+        assert!(!point_in_polygon(&polygon, [8.0, 8.0]));
+        assert!(!point_in_polygon(&polygon, [-1.0, 2.0]));
+        // The boundary, including the inner corner and a vertex.
+        assert!(point_in_polygon(&polygon, [4.0, 7.0]));
+        assert!(point_in_polygon(&polygon, [4.0, 4.0]));
+        assert!(point_in_polygon(&polygon, [10.0, 0.0]));
+        assert!(!point_in_polygon(&[], [0.0, 0.0]));
+    }
+}
+
 /// Transforms from cartesian coordinates to barycentric.
 #[inline(always)]
 pub fn to_barycentric(triangle: Triangle, pos: Vec2d) -> Vec3d {